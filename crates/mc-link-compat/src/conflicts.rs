@@ -0,0 +1,140 @@
+//! Detection of mods that declare each other incompatible.
+
+use crate::environment::parse_range;
+use mc_link_core::{DependencyKind, ModInfo};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fmt;
+
+/// A pair of installed mods where one declares the other incompatible.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ModConflict {
+    /// ID of the mod declaring the incompatibility
+    pub mod_id: String,
+    /// Name of the mod declaring the incompatibility
+    pub mod_name: String,
+    /// ID of the mod it is incompatible with
+    pub conflicts_with: String,
+    /// Name of the mod it is incompatible with
+    pub conflicts_with_name: String,
+    /// Version range the declaration applies to, if any
+    pub version_range: Option<String>,
+    /// [`DependencyKind::Incompatible`] for a hard conflict the loader refuses
+    /// to boot with, or [`DependencyKind::Discouraged`] for a soft one
+    pub kind: DependencyKind,
+}
+
+impl ModConflict {
+    /// Returns true if the loader refuses to start with both mods installed.
+    pub fn is_hard(&self) -> bool {
+        self.kind == DependencyKind::Incompatible
+    }
+
+    /// Returns true if `mod_id` is either side of the conflict.
+    pub fn involves(&self, mod_id: &str) -> bool {
+        self.mod_id == mod_id || self.conflicts_with == mod_id
+    }
+}
+
+impl fmt::Display for ModConflict {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let relation = if self.is_hard() {
+            "is incompatible with"
+        } else {
+            "discourages"
+        };
+        write!(
+            f,
+            "{} ({}) {} {} ({})",
+            self.mod_name, self.mod_id, relation, self.conflicts_with_name, self.conflicts_with
+        )?;
+        if let Some(range) = &self.version_range {
+            write!(f, " {range}")?;
+        }
+        Ok(())
+    }
+}
+
+/// Finds declared incompatibilities between mods installed together.
+///
+/// A declaration with a version range only applies when the other mod's
+/// version falls within it; one whose range or version cannot be read is
+/// assumed to apply.
+pub fn find_conflicts<'a>(mods: impl IntoIterator<Item = &'a ModInfo>) -> Vec<ModConflict> {
+    let mods: Vec<&ModInfo> = mods.into_iter().collect();
+    let by_id: HashMap<&str, &ModInfo> = mods.iter().map(|m| (m.id.as_str(), *m)).collect();
+
+    let mut conflicts = Vec::new();
+    for mod_info in &mods {
+        for dependency in &mod_info.dependencies {
+            if !matches!(
+                dependency.kind,
+                DependencyKind::Incompatible | DependencyKind::Discouraged
+            ) {
+                continue;
+            }
+            let Some(other) = by_id.get(dependency.mod_id.as_str()) else {
+                continue;
+            };
+            if other.id == mod_info.id {
+                continue;
+            }
+
+            let applies = match (&dependency.version_range, &other.version) {
+                (Some(spec), Some(version)) => parse_range(&mod_info.loader, spec)
+                    .and_then(|range| range.contains_str(version))
+                    .unwrap_or(true),
+                _ => true,
+            };
+            if applies {
+                conflicts.push(ModConflict {
+                    mod_id: mod_info.id.clone(),
+                    mod_name: mod_info.name.clone(),
+                    conflicts_with: other.id.clone(),
+                    conflicts_with_name: other.name.clone(),
+                    version_range: dependency.version_range.clone(),
+                    kind: dependency.kind.clone(),
+                });
+            }
+        }
+    }
+    conflicts
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use mc_link_core::{ModDependency, ModLoader, ModSide};
+
+    fn fabric_mod(id: &str, version: &str, breaks: &[(&str, Option<&str>)]) -> ModInfo {
+        ModInfo {
+            version: Some(version.to_string()),
+            side: ModSide::Both,
+            loader: ModLoader::Fabric,
+            dependencies: breaks
+                .iter()
+                .map(|(id, range)| ModDependency {
+                    mod_id: id.to_string(),
+                    version_range: range.map(str::to_string),
+                    kind: DependencyKind::Incompatible,
+                })
+                .collect(),
+            ..ModInfo::new(id, format!("{id}.jar"))
+        }
+    }
+
+    #[test]
+    fn reports_conflicts_within_declared_range() {
+        let mods = [
+            fabric_mod("sodium", "0.6.0", &[("optifabric", None)]),
+            fabric_mod("iris", "1.8.0", &[("sodium", Some("<0.6"))]),
+            fabric_mod("optifabric", "1.14.0", &[]),
+        ];
+
+        let conflicts = find_conflicts(&mods);
+        assert_eq!(conflicts.len(), 1);
+        assert_eq!(conflicts[0].mod_id, "sodium");
+        assert_eq!(conflicts[0].conflicts_with, "optifabric");
+        assert!(conflicts[0].is_hard());
+    }
+}
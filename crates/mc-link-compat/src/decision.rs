@@ -0,0 +1,151 @@
+//! Records of why the compatibility check treated each mod the way it did.
+
+use crate::conflicts::ModConflict;
+use crate::environment::EnvironmentIssue;
+use crate::rules::{RuleSource, RuleType};
+use mc_link_core::{ModSide, SideGuess};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// One step in the compatibility check's handling of a mod.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Decision {
+    /// Mod ID the decision is about
+    pub mod_id: String,
+    /// What was decided
+    pub outcome: DecisionOutcome,
+    /// Why it was decided
+    pub cause: DecisionCause,
+}
+
+/// What the compatibility check decided for a mod.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum DecisionOutcome {
+    /// The mod is left out of the comparison
+    Ignored,
+    /// The mod has to be added to the server
+    MissingOnServer,
+    /// The mod has to be removed from the server
+    MissingOnClient,
+    /// The mod is on both sides with different versions
+    VersionMismatch,
+    /// A warning was raised, and checking continued
+    Warned,
+    /// The mod is on both sides with matching versions
+    Compatible,
+    /// The mod is missing on the server but must not be added
+    Blocked,
+}
+
+/// Why the compatibility check reached a decision.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum DecisionCause {
+    /// The mod is in an ignore list
+    IgnoreList {
+        /// Which ignore list: [`RuleSource::GlobalIgnoreList`],
+        /// [`RuleSource::ServerIgnoreList`], or [`RuleSource::Config`] for
+        /// [`CompatConfig::ignore_list`](crate::CompatConfig::ignore_list)
+        source: RuleSource,
+    },
+    /// A custom rule matched the mod
+    Rule {
+        /// Pattern of the matching rule
+        pattern: String,
+        /// Type of the matching rule
+        rule_type: RuleType,
+        /// Where the rule came from
+        source: RuleSource,
+        /// Reason given by the rule
+        reason: String,
+    },
+    /// The mod's declared side decided it
+    Side(ModSide),
+    /// The versions on both sides differ
+    VersionMismatch {
+        /// Version on the client
+        client_version: String,
+        /// Version on the server
+        server_version: String,
+    },
+    /// The mod is only present on one side
+    Absent,
+    /// The versions on both sides are equivalent, or at least one is unknown
+    Matching,
+    /// A declared incompatibility with another mod
+    Conflict(ModConflict),
+    /// The mod cannot run in the other instance's environment
+    Environment(EnvironmentIssue),
+    /// The mod's side was guessed from its contents
+    SideGuess(SideGuess),
+}
+
+impl fmt::Display for DecisionOutcome {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let text = match self {
+            DecisionOutcome::Ignored => "ignored",
+            DecisionOutcome::MissingOnServer => "missing on server (will be added)",
+            DecisionOutcome::MissingOnClient => "missing on client (will be removed from server)",
+            DecisionOutcome::VersionMismatch => "version mismatch (will be updated)",
+            DecisionOutcome::Warned => "warning",
+            DecisionOutcome::Compatible => "compatible",
+            DecisionOutcome::Blocked => "blocked (will not be added)",
+        };
+        f.write_str(text)
+    }
+}
+
+impl fmt::Display for DecisionCause {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DecisionCause::IgnoreList {
+                source: RuleSource::Config,
+            } => write!(f, "listed in the ignore list of the compatibility config"),
+            DecisionCause::IgnoreList { source } => write!(f, "listed in {source}"),
+            DecisionCause::Rule {
+                pattern,
+                rule_type,
+                source,
+                reason,
+            } => {
+                write!(f, "{rule_type:?} rule '{pattern}' from {source}")?;
+                if !reason.is_empty() {
+                    write!(f, " ({reason})")?;
+                }
+                Ok(())
+            }
+            DecisionCause::Side(side) => write!(f, "mod declares side {side:?}"),
+            DecisionCause::VersionMismatch {
+                client_version,
+                server_version,
+            } => write!(
+                f,
+                "client has {client_version}, server has {server_version}"
+            ),
+            DecisionCause::Absent => write!(f, "not present on the other side"),
+            DecisionCause::Matching => write!(f, "versions match"),
+            DecisionCause::Conflict(conflict) => write!(f, "{conflict}"),
+            DecisionCause::Environment(issue) => write!(f, "{issue}"),
+            DecisionCause::SideGuess(guess) => {
+                write!(
+                    f,
+                    "no side declared, guessed {:?} with {:?} confidence",
+                    guess.side, guess.confidence
+                )?;
+                if !guess.evidence.is_empty() {
+                    write!(f, " ({})", guess.evidence.join("; "))?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+impl fmt::Display for Decision {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}: {}, because {}",
+            self.mod_id, self.outcome, self.cause
+        )
+    }
+}
@@ -0,0 +1,237 @@
+//! Detection of mods installed more than once in a single instance.
+
+use crate::version::compare_versions;
+use mc_link_core::ModInfo;
+use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
+use std::collections::HashMap;
+use std::fmt;
+
+/// Why a set of files was considered duplicates.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum DuplicateKind {
+    /// Several files declare the same mod ID
+    SameId {
+        /// The shared mod ID
+        mod_id: String,
+    },
+    /// Several files have identical contents under different names
+    SameContent {
+        /// The shared SHA-256 hash
+        sha256: String,
+    },
+}
+
+/// A group of mod files that should not be installed together.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DuplicateGroup {
+    /// What the files have in common
+    pub kind: DuplicateKind,
+    /// Every copy, with the one to keep first
+    pub mods: Vec<ModInfo>,
+}
+
+impl DuplicateGroup {
+    /// The copy to keep: the highest version, then the first file name.
+    pub fn preferred(&self) -> &ModInfo {
+        &self.mods[0]
+    }
+
+    /// Every copy except the preferred one.
+    pub fn stale(&self) -> &[ModInfo] {
+        &self.mods[1..]
+    }
+}
+
+impl fmt::Display for DuplicateGroup {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let files: Vec<String> = self
+            .mods
+            .iter()
+            .map(|m| m.file_path.display().to_string())
+            .collect();
+        match &self.kind {
+            DuplicateKind::SameId { mod_id } => {
+                write!(
+                    f,
+                    "mod '{}' is installed {} times: {}",
+                    mod_id,
+                    files.len(),
+                    files.join(", ")
+                )
+            }
+            DuplicateKind::SameContent { .. } => {
+                write!(f, "identical files: {}", files.join(", "))
+            }
+        }
+    }
+}
+
+/// Finds mods that share an ID or have identical file contents.
+///
+/// Files sharing an ID are reported once as [`DuplicateKind::SameId`], or as
+/// [`DuplicateKind::SameContent`] when every copy is byte-identical. Identical
+/// files with different IDs (e.g. when metadata could not be read) are
+/// reported as [`DuplicateKind::SameContent`].
+pub fn find_duplicates(mods: &[ModInfo]) -> Vec<DuplicateGroup> {
+    let mut groups = Vec::new();
+
+    for (mod_id, copies) in group_by(mods, |m| Some(m.id.as_str())) {
+        let first_hash = copies[0].hashes.sha256.as_deref();
+        let kind = match first_hash {
+            Some(sha256)
+                if copies
+                    .iter()
+                    .all(|m| m.hashes.sha256.as_deref() == first_hash) =>
+            {
+                DuplicateKind::SameContent {
+                    sha256: sha256.to_string(),
+                }
+            }
+            _ => DuplicateKind::SameId {
+                mod_id: mod_id.to_string(),
+            },
+        };
+        groups.push(DuplicateGroup {
+            kind,
+            mods: sorted_by_preference(copies),
+        });
+    }
+
+    for (sha256, copies) in group_by(mods, |m| m.hashes.sha256.as_deref()) {
+        // Already reported through their shared ID
+        if copies.iter().all(|m| m.id == copies[0].id) {
+            continue;
+        }
+        groups.push(DuplicateGroup {
+            kind: DuplicateKind::SameContent {
+                sha256: sha256.to_string(),
+            },
+            mods: sorted_by_preference(copies),
+        });
+    }
+
+    groups
+}
+
+/// Picks one copy per mod ID, preferring the highest version.
+pub fn deduplicate(mods: &[ModInfo]) -> HashMap<&str, &ModInfo> {
+    let mut map: HashMap<&str, &ModInfo> = HashMap::new();
+    for mod_info in mods {
+        map.entry(mod_info.id.as_str())
+            .and_modify(|current| {
+                if preference(mod_info, current) == Ordering::Less {
+                    *current = mod_info;
+                }
+            })
+            .or_insert(mod_info);
+    }
+    map
+}
+
+/// Groups mods by key, keeping only groups with more than one member, in first-seen order.
+fn group_by<'a>(
+    mods: &'a [ModInfo],
+    key: impl Fn(&'a ModInfo) -> Option<&'a str>,
+) -> Vec<(&'a str, Vec<&'a ModInfo>)> {
+    let mut order = Vec::new();
+    let mut groups: HashMap<&str, Vec<&ModInfo>> = HashMap::new();
+    for mod_info in mods {
+        let Some(key) = key(mod_info) else {
+            continue;
+        };
+        groups
+            .entry(key)
+            .or_insert_with(|| {
+                order.push(key);
+                Vec::new()
+            })
+            .push(mod_info);
+    }
+
+    order
+        .into_iter()
+        .filter_map(|key| {
+            let copies = groups.remove(key)?;
+            (copies.len() > 1).then_some((key, copies))
+        })
+        .collect()
+}
+
+fn sorted_by_preference(mut copies: Vec<&ModInfo>) -> Vec<ModInfo> {
+    copies.sort_by(|a, b| preference(a, b));
+    copies.into_iter().cloned().collect()
+}
+
+/// Orders the copy to keep first: highest version, then file name.
+fn preference(a: &ModInfo, b: &ModInfo) -> Ordering {
+    let by_version = match (&a.version, &b.version) {
+        (Some(a), Some(b)) => compare_versions(b, a).unwrap_or(Ordering::Equal),
+        (Some(_), None) => Ordering::Less,
+        (None, Some(_)) => Ordering::Greater,
+        (None, None) => Ordering::Equal,
+    };
+    by_version.then_with(|| a.file_path.cmp(&b.file_path))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use mc_link_core::{FileHashes, ModLoader, ModSide};
+
+    fn jar(id: &str, version: &str, file: &str, sha256: &str) -> ModInfo {
+        ModInfo {
+            version: Some(version.to_string()),
+            side: ModSide::Both,
+            loader: ModLoader::NeoForge,
+            hashes: FileHashes {
+                sha256: Some(sha256.to_string()),
+                ..FileHashes::default()
+            },
+            ..ModInfo::new(id, file)
+        }
+    }
+
+    #[test]
+    fn keeps_highest_version_of_duplicate_id() {
+        let mods = [
+            jar("jei", "19.0.0", "mods/jei-19.0.0.jar", "aaa"),
+            jar("jei", "19.21.0", "mods/jei-19.21.0.jar", "bbb"),
+            jar("create", "6.0.0", "mods/create.jar", "ccc"),
+        ];
+
+        let groups = find_duplicates(&mods);
+        assert_eq!(groups.len(), 1);
+        assert_eq!(
+            groups[0].kind,
+            DuplicateKind::SameId {
+                mod_id: "jei".to_string()
+            }
+        );
+        assert_eq!(groups[0].preferred().version.as_deref(), Some("19.21.0"));
+        assert_eq!(groups[0].stale().len(), 1);
+
+        assert_eq!(
+            deduplicate(&mods)["jei"].version.as_deref(),
+            Some("19.21.0")
+        );
+    }
+
+    #[test]
+    fn reports_identical_files_once() {
+        let mods = [
+            jar("jei", "19.21.0", "mods/jei.jar", "aaa"),
+            jar("jei", "19.21.0", "mods/jei (1).jar", "aaa"),
+            jar("unknown-a", "1", "mods/a.jar", "fff"),
+            jar("unknown-b", "1", "mods/b.jar", "fff"),
+        ];
+
+        let groups = find_duplicates(&mods);
+        assert_eq!(groups.len(), 2);
+        assert!(
+            groups
+                .iter()
+                .all(|g| matches!(g.kind, DuplicateKind::SameContent { .. }))
+        );
+    }
+}
@@ -0,0 +1,433 @@
+//! Checks mods against the Minecraft version and loader of an instance.
+
+use crate::version::VersionRange;
+use mc_link_core::{DependencyKind, ModInfo, ModLoader};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// Minecraft version and mod loader an instance is configured for.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GameEnvironment {
+    /// Minecraft version (e.g., "1.21.1"), if known
+    pub minecraft_version: Option<String>,
+    /// Mod loader, or `None` for vanilla or an unconfigured instance
+    pub loader: Option<ModLoader>,
+    /// Version of the mod loader (e.g., "21.1.77"), if known
+    #[serde(default)]
+    pub loader_version: Option<String>,
+}
+
+impl GameEnvironment {
+    /// Creates an environment from a Minecraft version and loader.
+    pub fn new(minecraft_version: Option<String>, loader: Option<ModLoader>) -> Self {
+        Self {
+            minecraft_version,
+            loader,
+            loader_version: None,
+        }
+    }
+
+    /// Sets the version of the instance's loader.
+    pub fn with_loader_version(mut self, loader_version: Option<String>) -> Self {
+        self.loader_version = loader_version;
+        self
+    }
+
+    /// Whether mods built for `mod_loader` can run in this environment.
+    ///
+    /// Unknown loaders on either side are accepted. NeoForge still loads
+    /// Forge mods on 1.20.1, where it was forked from Forge.
+    pub fn accepts_loader(&self, mod_loader: &ModLoader) -> bool {
+        let Some(instance_loader) = &self.loader else {
+            return true;
+        };
+        match (mod_loader, instance_loader) {
+            (ModLoader::Unknown, _) | (_, ModLoader::Unknown) => true,
+            (ModLoader::Forge, ModLoader::NeoForge) => self
+                .minecraft_version
+                .as_deref()
+                .is_none_or(|version| version == "1.20.1"),
+            (mod_loader, instance_loader) => mod_loader == instance_loader,
+        }
+    }
+
+    /// Returns this environment, guessing the loader from `mods` if it is not set.
+    pub fn with_detected_loader(&self, mods: &[ModInfo]) -> Self {
+        Self {
+            minecraft_version: self.minecraft_version.clone(),
+            loader: self.loader.clone().or_else(|| detect_loader(mods)),
+            loader_version: self.loader_version.clone(),
+        }
+    }
+}
+
+/// Guesses an instance's loader from the loader most of its mods were built for.
+///
+/// Returns `None` when no mod has a known loader.
+pub fn detect_loader(mods: &[ModInfo]) -> Option<ModLoader> {
+    let mut counts: Vec<(&ModLoader, usize)> = Vec::new();
+    for mod_info in mods {
+        if mod_info.loader == ModLoader::Unknown {
+            continue;
+        }
+        match counts
+            .iter_mut()
+            .find(|(loader, _)| *loader == &mod_info.loader)
+        {
+            Some((_, count)) => *count += 1,
+            None => counts.push((&mod_info.loader, 1)),
+        }
+    }
+    counts
+        .into_iter()
+        .max_by_key(|(_, count)| *count)
+        .map(|(loader, _)| loader.clone())
+}
+
+/// Two instances running loaders that cannot share mods.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LoaderMismatch {
+    /// Loader the client runs
+    pub client_loader: ModLoader,
+    /// Loader the server runs
+    pub server_loader: ModLoader,
+}
+
+impl fmt::Display for LoaderMismatch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "the client runs {:?}, but the server runs {:?}",
+            self.client_loader, self.server_loader
+        )
+    }
+}
+
+/// A mod that cannot run in the configured environment.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EnvironmentIssue {
+    /// ID of the offending mod
+    pub mod_id: String,
+    /// Display name of the offending mod
+    pub mod_name: String,
+    /// What is wrong
+    pub kind: EnvironmentIssueKind,
+}
+
+/// Kinds of environment mismatch.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum EnvironmentIssueKind {
+    /// The mod's declared Minecraft range excludes the instance version
+    MinecraftVersion {
+        /// Range declared by the mod
+        required: String,
+        /// Version the instance runs
+        actual: String,
+    },
+    /// The mod was built for a different loader
+    Loader {
+        /// Loader the mod was built for
+        mod_loader: ModLoader,
+        /// Loader the instance runs
+        instance_loader: ModLoader,
+    },
+    /// The mod's declared loader range excludes the instance's loader version
+    LoaderVersion {
+        /// Loader the instance runs
+        loader: ModLoader,
+        /// Range declared by the mod
+        required: String,
+        /// Loader version the instance runs
+        actual: String,
+    },
+}
+
+impl fmt::Display for EnvironmentIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.kind {
+            EnvironmentIssueKind::MinecraftVersion { required, actual } => write!(
+                f,
+                "{} requires Minecraft {}, but the instance runs {}",
+                self.mod_name, required, actual
+            ),
+            EnvironmentIssueKind::Loader {
+                mod_loader,
+                instance_loader,
+            } => write!(
+                f,
+                "{} is a {:?} mod, but the instance runs {:?}",
+                self.mod_name, mod_loader, instance_loader
+            ),
+            EnvironmentIssueKind::LoaderVersion {
+                loader,
+                required,
+                actual,
+            } => write!(
+                f,
+                "{} requires {:?} {}, but the instance runs {}",
+                self.mod_name, loader, required, actual
+            ),
+        }
+    }
+}
+
+/// Checks every mod against the Minecraft version and loader of `env`.
+///
+/// Loader mismatches are reported first and suppress the version checks for
+/// that mod, since its ranges are meaningless on the wrong loader. Loader
+/// version ranges are only checked when `env` knows its loader version.
+/// Ranges that cannot be parsed are treated as satisfied.
+pub fn check_environment(mods: &[ModInfo], env: &GameEnvironment) -> Vec<EnvironmentIssue> {
+    let mut issues = Vec::new();
+
+    for mod_info in mods {
+        if !env.accepts_loader(&mod_info.loader) {
+            issues.push(EnvironmentIssue {
+                mod_id: mod_info.id.clone(),
+                mod_name: mod_info.name.clone(),
+                kind: EnvironmentIssueKind::Loader {
+                    mod_loader: mod_info.loader.clone(),
+                    instance_loader: env.loader.clone().unwrap_or(ModLoader::Unknown),
+                },
+            });
+            continue;
+        }
+
+        if let Some(actual) = env.minecraft_version.as_deref()
+            && let Some(required) = minecraft_requirement(mod_info)
+            && let Some(range) = parse_range(&mod_info.loader, required)
+            && range.contains_str(actual) == Some(false)
+        {
+            issues.push(EnvironmentIssue {
+                mod_id: mod_info.id.clone(),
+                mod_name: mod_info.name.clone(),
+                kind: EnvironmentIssueKind::MinecraftVersion {
+                    required: required.to_string(),
+                    actual: actual.to_string(),
+                },
+            });
+        }
+
+        if let Some(loader) = &env.loader
+            && let Some(actual) = env.loader_version.as_deref()
+            && let Some(required) = loader_requirement(mod_info, loader)
+            && let Some(range) = parse_range(&mod_info.loader, required)
+            && range.contains_str(actual) == Some(false)
+        {
+            issues.push(EnvironmentIssue {
+                mod_id: mod_info.id.clone(),
+                mod_name: mod_info.name.clone(),
+                kind: EnvironmentIssueKind::LoaderVersion {
+                    loader: loader.clone(),
+                    required: required.to_string(),
+                    actual: actual.to_string(),
+                },
+            });
+        }
+    }
+
+    issues
+}
+
+/// Whether each mod declares support for a Minecraft version.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct UpgradeReport {
+    /// Minecraft version that was checked
+    pub target_version: String,
+    /// Mods whose declared range includes the target version
+    pub supported: Vec<ModReadiness>,
+    /// Mods whose declared range excludes the target version
+    pub unsupported: Vec<ModReadiness>,
+    /// Mods that declare no Minecraft range, or one that could not be parsed
+    pub undeclared: Vec<ModReadiness>,
+}
+
+impl UpgradeReport {
+    /// Whether every mod declares support for the target version.
+    pub fn is_ready(&self) -> bool {
+        self.unsupported.is_empty() && self.undeclared.is_empty()
+    }
+}
+
+/// A single mod's entry in an [`UpgradeReport`].
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ModReadiness {
+    /// Mod identifier
+    pub mod_id: String,
+    /// Display name of the mod
+    pub mod_name: String,
+    /// Installed version of the mod
+    pub version: Option<String>,
+    /// Minecraft range declared by the mod
+    pub declared_range: Option<String>,
+}
+
+/// Sorts mods by whether their declared Minecraft range includes `target_version`.
+pub fn check_upgrade(mods: &[ModInfo], target_version: &str) -> UpgradeReport {
+    let mut report = UpgradeReport {
+        target_version: target_version.to_string(),
+        ..Default::default()
+    };
+
+    for mod_info in mods {
+        let declared = minecraft_requirement(mod_info);
+        let entry = ModReadiness {
+            mod_id: mod_info.id.clone(),
+            mod_name: mod_info.name.clone(),
+            version: mod_info.version.clone(),
+            declared_range: declared.map(str::to_string),
+        };
+
+        let supported = declared
+            .and_then(|spec| parse_range(&mod_info.loader, spec))
+            .and_then(|range| range.contains_str(target_version));
+        match supported {
+            Some(true) => report.supported.push(entry),
+            Some(false) => report.unsupported.push(entry),
+            None => report.undeclared.push(entry),
+        }
+    }
+
+    report
+}
+
+fn minecraft_requirement(mod_info: &ModInfo) -> Option<&str> {
+    mod_info
+        .dependencies
+        .iter()
+        .find(|dep| dep.mod_id == "minecraft" && dep.kind == DependencyKind::Required)
+        .and_then(|dep| dep.version_range.as_deref())
+}
+
+/// Returns the range a mod requires of `loader`, keyed by the loader's mod ID.
+fn loader_requirement<'a>(mod_info: &'a ModInfo, loader: &ModLoader) -> Option<&'a str> {
+    let loader_id = match loader {
+        ModLoader::NeoForge => "neoforge",
+        ModLoader::Forge => "forge",
+        ModLoader::Fabric => "fabricloader",
+        ModLoader::Unknown => return None,
+    };
+    mod_info
+        .dependencies
+        .iter()
+        .find(|dep| dep.mod_id == loader_id && dep.kind == DependencyKind::Required)
+        .and_then(|dep| dep.version_range.as_deref())
+}
+
+/// Parses a range in the dialect of the loader that declared it.
+pub(crate) fn parse_range(loader: &ModLoader, spec: &str) -> Option<VersionRange> {
+    match loader {
+        ModLoader::Fabric => VersionRange::parse_semver(spec),
+        ModLoader::Forge | ModLoader::NeoForge => VersionRange::parse_maven(spec),
+        ModLoader::Unknown => VersionRange::parse(spec),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use mc_link_core::{ModDependency, ModSide};
+
+    fn mod_with(loader: ModLoader, minecraft: &str) -> ModInfo {
+        ModInfo {
+            name: "Example".to_string(),
+            version: Some("1.0.0".to_string()),
+            side: ModSide::Both,
+            loader,
+            dependencies: vec![ModDependency {
+                mod_id: "minecraft".to_string(),
+                version_range: Some(minecraft.to_string()),
+                kind: DependencyKind::Required,
+            }],
+            ..ModInfo::new("example", "example.jar")
+        }
+    }
+
+    fn env(minecraft: &str, loader: ModLoader) -> GameEnvironment {
+        GameEnvironment::new(Some(minecraft.to_string()), Some(loader))
+    }
+
+    #[test]
+    fn flags_minecraft_range_mismatch() {
+        let mods = [mod_with(ModLoader::NeoForge, "[1.20.1,1.20.2)")];
+
+        let issues = check_environment(&mods, &env("1.21.1", ModLoader::NeoForge));
+        assert_eq!(issues.len(), 1);
+        assert!(matches!(
+            issues[0].kind,
+            EnvironmentIssueKind::MinecraftVersion { .. }
+        ));
+
+        assert!(check_environment(&mods, &env("1.20.1", ModLoader::NeoForge)).is_empty());
+    }
+
+    #[test]
+    fn uses_semver_for_fabric_ranges() {
+        let mods = [mod_with(ModLoader::Fabric, "~1.20")];
+        assert!(check_environment(&mods, &env("1.20.4", ModLoader::Fabric)).is_empty());
+        assert_eq!(
+            check_environment(&mods, &env("1.21.1", ModLoader::Fabric)).len(),
+            1
+        );
+    }
+
+    #[test]
+    fn sorts_mods_by_upgrade_readiness() {
+        let mut undeclared = mod_with(ModLoader::NeoForge, "");
+        undeclared.dependencies.clear();
+        let mods = [
+            mod_with(ModLoader::NeoForge, "[1.21,1.22)"),
+            mod_with(ModLoader::NeoForge, "[1.20.1,1.20.2)"),
+            undeclared,
+        ];
+
+        let report = check_upgrade(&mods, "1.21.4");
+        assert_eq!(report.supported.len(), 1);
+        assert_eq!(report.unsupported.len(), 1);
+        assert_eq!(report.undeclared.len(), 1);
+        assert!(!report.is_ready());
+    }
+
+    #[test]
+    fn flags_loader_version_mismatch() {
+        let mut needs_new_loader = mod_with(ModLoader::NeoForge, "[1.21.1]");
+        needs_new_loader.dependencies.push(ModDependency {
+            mod_id: "neoforge".to_string(),
+            version_range: Some("[21.1.90,)".to_string()),
+            kind: DependencyKind::Required,
+        });
+        let mods = [needs_new_loader];
+
+        let old = env("1.21.1", ModLoader::NeoForge).with_loader_version(Some("21.1.77".into()));
+        let issues = check_environment(&mods, &old);
+        assert_eq!(issues.len(), 1);
+        assert!(matches!(
+            issues[0].kind,
+            EnvironmentIssueKind::LoaderVersion { .. }
+        ));
+
+        let new = env("1.21.1", ModLoader::NeoForge).with_loader_version(Some("21.1.95".into()));
+        assert!(check_environment(&mods, &new).is_empty());
+        // Without a known loader version the requirement cannot be checked
+        assert!(check_environment(&mods, &env("1.21.1", ModLoader::NeoForge)).is_empty());
+    }
+
+    #[test]
+    fn flags_loader_mismatch() {
+        let mods = [mod_with(ModLoader::Fabric, "*")];
+        let issues = check_environment(&mods, &env("1.21.1", ModLoader::NeoForge));
+        assert_eq!(issues.len(), 1);
+        assert!(matches!(
+            issues[0].kind,
+            EnvironmentIssueKind::Loader { .. }
+        ));
+
+        // NeoForge 1.20.1 still loads Forge mods
+        let forge = [mod_with(ModLoader::Forge, "[1.20.1]")];
+        assert!(check_environment(&forge, &env("1.20.1", ModLoader::NeoForge)).is_empty());
+        assert_eq!(
+            check_environment(&forge, &env("1.21.1", ModLoader::NeoForge)).len(),
+            1
+        );
+    }
+}
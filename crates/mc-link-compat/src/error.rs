@@ -11,6 +11,10 @@ pub enum CompatError {
     #[error("Metadata parsing error: {mod_name} - {reason}")]
     MetadataError { mod_name: String, reason: String },
 
+    /// A compatibility rule pattern could not be compiled
+    #[error("Invalid rule pattern '{pattern}': {reason}")]
+    InvalidRule { pattern: String, reason: String },
+
     /// Core error passthrough
     #[error(transparent)]
     Core(#[from] mc_link_core::CoreError),
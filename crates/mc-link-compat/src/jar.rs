@@ -1,5 +1,8 @@
+use crate::side::classify_side;
 use crate::{CompatError, Result};
-use mc_link_core::{ModInfo, ModLoader, ModSide};
+use mc_link_core::{
+    DependencyKind, FileHashes, ModDependency, ModInfo, ModLoader, ModMetadata, ModSide,
+};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::io::Read;
@@ -18,6 +21,33 @@ pub struct FabricModInfo {
     pub version: String,
     /// Environment the mod runs in
     pub environment: Option<String>,
+    /// Required dependencies (mod ID to version predicate or list of predicates)
+    #[serde(default)]
+    pub depends: HashMap<String, serde_json::Value>,
+    /// Recommended dependencies
+    #[serde(default)]
+    pub recommends: HashMap<String, serde_json::Value>,
+    /// Suggested dependencies
+    #[serde(default)]
+    pub suggests: HashMap<String, serde_json::Value>,
+    /// Mods that must not be installed alongside this one
+    #[serde(default)]
+    pub breaks: HashMap<String, serde_json::Value>,
+    /// Mods that conflict with this one but can still load
+    #[serde(default)]
+    pub conflicts: HashMap<String, serde_json::Value>,
+    /// Description
+    pub description: Option<String>,
+    /// Authors, as names or `{ "name": ..., "contact": ... }` objects
+    #[serde(default)]
+    pub authors: Vec<serde_json::Value>,
+    /// License identifier, or a list of them
+    pub license: Option<serde_json::Value>,
+    /// Contact URLs keyed by kind (`homepage`, `issues`, `sources`, ...)
+    #[serde(default)]
+    pub contact: HashMap<String, serde_json::Value>,
+    /// Icon path, or a map of icon sizes to paths
+    pub icon: Option<serde_json::Value>,
 }
 
 /// NeoForge/Forge mods.toml structure.
@@ -26,6 +56,35 @@ pub struct ForgeModsToml {
     /// Array of mods defined in this file
     #[serde(rename = "mods")]
     pub mods: Vec<ForgeModInfo>,
+    /// Dependencies keyed by the mod ID that declares them
+    #[serde(default)]
+    pub dependencies: HashMap<String, Vec<ForgeDependency>>,
+    /// License of every mod in the file
+    pub license: Option<String>,
+    /// Issue tracker URL
+    #[serde(rename = "issueTrackerURL")]
+    pub issue_tracker_url: Option<String>,
+    /// Logo file inside the jar
+    #[serde(rename = "logoFile")]
+    pub logo_file: Option<String>,
+}
+
+/// Dependency entry from a `[[dependencies.<modId>]]` table in mods.toml.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ForgeDependency {
+    /// ID of the dependency
+    #[serde(rename = "modId")]
+    pub mod_id: String,
+    /// Whether the dependency is required (Forge)
+    pub mandatory: Option<bool>,
+    /// Dependency type: required, optional, incompatible or discouraged (NeoForge)
+    #[serde(rename = "type")]
+    pub kind: Option<String>,
+    /// Maven version range
+    #[serde(rename = "versionRange")]
+    pub version_range: Option<String>,
+    /// Side the dependency applies to
+    pub side: Option<String>,
 }
 
 /// Individual NeoForge/Forge mod metadata.
@@ -41,6 +100,19 @@ pub struct ForgeModInfo {
     pub version: String,
     /// Side specification
     pub side: Option<String>,
+    /// Description
+    pub description: Option<String>,
+    /// Authors, usually a single comma-separated string
+    pub authors: Option<toml::Value>,
+    /// Homepage URL
+    #[serde(rename = "displayURL")]
+    pub display_url: Option<String>,
+    /// URL of the update checker JSON
+    #[serde(rename = "updateJSONURL")]
+    pub update_json_url: Option<String>,
+    /// Logo file inside the jar, overriding the file-level one
+    #[serde(rename = "logoFile")]
+    pub logo_file: Option<String>,
 }
 
 /// Legacy Forge mcmod.info structure.
@@ -57,6 +129,17 @@ pub struct McModInfo {
     pub description: Option<String>,
     /// Authors
     pub authors: Option<Vec<String>>,
+    /// Authors, as written by most mods
+    #[serde(rename = "authorList")]
+    pub author_list: Option<Vec<String>>,
+    /// Homepage URL
+    pub url: Option<String>,
+    /// URL of the update checker JSON
+    #[serde(rename = "updateJSON")]
+    pub update_json: Option<String>,
+    /// Logo file inside the jar
+    #[serde(rename = "logoFile")]
+    pub logo_file: Option<String>,
 }
 
 pub fn extract_jar_info<P: AsRef<Path>>(jar_path: P) -> Result<ModInfo> {
@@ -85,22 +168,22 @@ pub fn extract_jar_info<P: AsRef<Path>>(jar_path: P) -> Result<ModInfo> {
 
     if let Ok(forge_info) = extract_mods_toml_info(&mut archive, jar_path) {
         debug!(jar_path = %jar_path.display(), mod_id = %forge_info.id, mod_name = %forge_info.name, version = ?forge_info.version, loader = ?forge_info.loader, "Extracted mod info from mods.toml");
-        return Ok(forge_info);
+        return Ok(with_side_guess(&mut archive, forge_info));
     }
 
     if let Ok(fabric_info) = extract_fabric_info(&mut archive, jar_path) {
         info!(jar_path = %jar_path.display(), mod_id = %fabric_info.id, mod_name = %fabric_info.name, version = ?fabric_info.version, "Successfully extracted mod info from fabric.mod.json");
-        return Ok(fabric_info);
+        return Ok(with_side_guess(&mut archive, fabric_info));
     }
 
     if let Ok(mcmod_info) = extract_mcmod_info(&mut archive, jar_path) {
         info!(jar_path = %jar_path.display(), mod_id = %mcmod_info.id, mod_name = %mcmod_info.name, version = ?mcmod_info.version, "Successfully extracted mod info from mcmod.info");
-        return Ok(mcmod_info);
+        return Ok(with_side_guess(&mut archive, mcmod_info));
     }
 
     if let Ok(manifest_info) = extract_manifest_info(&mut archive, jar_path) {
         info!(jar_path = %jar_path.display(), mod_id = %manifest_info.id, mod_name = %manifest_info.name, version = ?manifest_info.version, "Successfully extracted mod info from MANIFEST.MF");
-        return Ok(manifest_info);
+        return Ok(with_side_guess(&mut archive, manifest_info));
     }
 
     let filename = jar_path
@@ -109,16 +192,27 @@ pub fn extract_jar_info<P: AsRef<Path>>(jar_path: P) -> Result<ModInfo> {
         .unwrap_or("unknown");
     warn!(jar_path = %jar_path.display(), filename = %filename, "Could not extract mod metadata, falling back to filename");
 
-    Ok(ModInfo {
-        id: filename.to_string(),
-        name: filename.to_string(),
+    let fallback_info = ModInfo {
         version: Some("unknown".to_string()),
-        file_path: jar_path.to_path_buf(),
-        enabled: true,
-        side: ModSide::Unknown,
-        loader: ModLoader::Unknown,
-        raw_metadata: HashMap::new(),
-    })
+        ..ModInfo::new(filename, jar_path)
+    };
+    Ok(with_side_guess(&mut archive, fallback_info))
+}
+
+/// Fills in the side of a mod whose metadata does not declare one.
+///
+/// The side is only changed when the guess is at least
+/// [`SideConfidence::Medium`](mc_link_core::SideConfidence::Medium);
+/// otherwise the mod is assumed to run on both sides.
+fn with_side_guess(archive: &mut ZipArchive<std::fs::File>, mut mod_info: ModInfo) -> ModInfo {
+    if mod_info.side != ModSide::Unknown {
+        return mod_info;
+    }
+    let guess = classify_side(archive);
+    debug!(mod_id = %mod_info.id, side = ?guess.side, confidence = ?guess.confidence, "Guessed undeclared mod side");
+    mod_info.side = guess.effective_side();
+    mod_info.side_guess = Some(guess);
+    mod_info
 }
 
 fn extract_mods_toml_info(
@@ -127,18 +221,21 @@ fn extract_mods_toml_info(
 ) -> Result<ModInfo> {
     use tracing::{debug, warn};
 
-    let contents = {
-        let mut file = match archive.by_name("META-INF/mods.toml") {
-            Ok(f) => f,
-            Err(e) => {
-                debug!("No META-INF/mods.toml found: {}", e);
-                return Err(CompatError::MetadataError {
-                    mod_name: "unknown".to_string(),
-                    reason: format!("No mods.toml file: {}", e),
-                });
-            }
-        };
+    // NeoForge 20.5+ uses its own file name; older NeoForge and Forge use mods.toml
+    let toml_path = ["META-INF/neoforge.mods.toml", "META-INF/mods.toml"]
+        .into_iter()
+        .find(|path| archive.by_name(path).is_ok());
+
+    let Some(toml_path) = toml_path else {
+        debug!("No META-INF/mods.toml found");
+        return Err(CompatError::MetadataError {
+            mod_name: "unknown".to_string(),
+            reason: "No mods.toml file".to_string(),
+        });
+    };
 
+    let contents = {
+        let mut file = archive.by_name(toml_path)?;
         let mut contents = String::new();
         file.read_to_string(&mut contents)?;
         contents
@@ -164,6 +261,18 @@ fn extract_mods_toml_info(
         }
     };
 
+    // Keep the mod's own table, as the typed structs only cover common keys
+    let raw_metadata = toml::from_str::<toml::Value>(&contents)
+        .ok()
+        .and_then(|value| value.get("mods")?.get(0).cloned())
+        .and_then(|table| serde_json::to_value(table).ok())
+        .and_then(|value| match value {
+            serde_json::Value::Object(map) => Some(map.into_iter().collect()),
+            _ => None,
+        })
+        .unwrap_or_default();
+
+    let mut dependency_tables = forge_toml.dependencies;
     let forge_mod =
         forge_toml
             .mods
@@ -173,6 +282,33 @@ fn extract_mods_toml_info(
                 mod_name: "unknown".to_string(),
                 reason: "No mods found in mods.toml".to_string(),
             })?;
+    let forge_dependencies = dependency_tables
+        .remove(&forge_mod.mod_id)
+        .unwrap_or_default();
+
+    let metadata = ModMetadata {
+        description: forge_mod
+            .description
+            .as_deref()
+            .map(str::trim)
+            .filter(|d| !d.is_empty())
+            .map(str::to_string),
+        authors: match &forge_mod.authors {
+            Some(toml::Value::String(authors)) => split_authors(authors),
+            Some(toml::Value::Array(authors)) => authors
+                .iter()
+                .filter_map(|a| a.as_str())
+                .flat_map(split_authors)
+                .collect(),
+            _ => Vec::new(),
+        },
+        license: forge_toml.license,
+        homepage: forge_mod.display_url,
+        issues: forge_toml.issue_tracker_url,
+        sources: None,
+        update_json_url: forge_mod.update_json_url,
+        logo_path: forge_mod.logo_file.or(forge_toml.logo_file),
+    };
 
     let version = if forge_mod.version.trim() == "${file.jarVersion}" {
         manifest_version.unwrap_or_else(|| "unknown".to_string())
@@ -181,13 +317,31 @@ fn extract_mods_toml_info(
     };
 
     let side = parse_forge_side(&forge_mod.side);
-    let loader = if contents.contains("javafml") || contents.contains("lowcodefml") {
+    let depends_on = |id: &str| forge_dependencies.iter().any(|dep| dep.mod_id == id);
+    let loader = if toml_path == "META-INF/neoforge.mods.toml" || depends_on("neoforge") {
+        ModLoader::NeoForge
+    } else if depends_on("forge") {
+        ModLoader::Forge
+    } else if contents.contains("javafml") || contents.contains("lowcodefml") {
         ModLoader::NeoForge
     } else if contents.contains("forge") || contents.contains("minecraftforge") {
         ModLoader::Forge
     } else {
         ModLoader::Unknown
     };
+    let dependencies = forge_dependencies
+        .into_iter()
+        .map(|dep| ModDependency {
+            kind: match (dep.kind.as_deref(), dep.mandatory) {
+                (Some("incompatible"), _) => DependencyKind::Incompatible,
+                (Some("discouraged"), _) => DependencyKind::Discouraged,
+                (Some("optional"), _) | (None, Some(false)) => DependencyKind::Optional,
+                _ => DependencyKind::Required,
+            },
+            mod_id: dep.mod_id,
+            version_range: dep.version_range,
+        })
+        .collect();
 
     Ok(ModInfo {
         id: forge_mod.mod_id.clone(),
@@ -196,8 +350,12 @@ fn extract_mods_toml_info(
         file_path: jar_path.to_path_buf(),
         enabled: true,
         side,
+        side_guess: None,
         loader,
-        raw_metadata: HashMap::new(),
+        raw_metadata,
+        dependencies,
+        metadata,
+        hashes: FileHashes::default(),
     })
 }
 
@@ -229,10 +387,59 @@ fn extract_fabric_info(
     let side = match fabric_info.environment.as_deref() {
         Some("client") => ModSide::Client,
         Some("server") => ModSide::Server,
-        Some("*") | None => ModSide::Both,
+        Some("*") => ModSide::Both,
         _ => ModSide::Unknown,
     };
 
+    let contact = |kind: &str| {
+        fabric_info
+            .contact
+            .get(kind)
+            .and_then(|url| url.as_str())
+            .map(str::to_string)
+    };
+    let metadata = ModMetadata {
+        description: fabric_info.description.clone(),
+        authors: fabric_info
+            .authors
+            .iter()
+            .filter_map(|author| match author {
+                serde_json::Value::String(name) => Some(name.clone()),
+                other => other.get("name")?.as_str().map(str::to_string),
+            })
+            .collect(),
+        license: match &fabric_info.license {
+            Some(serde_json::Value::String(license)) => Some(license.clone()),
+            Some(serde_json::Value::Array(licenses)) => {
+                let licenses: Vec<&str> = licenses.iter().filter_map(|l| l.as_str()).collect();
+                (!licenses.is_empty()).then(|| licenses.join(", "))
+            }
+            _ => None,
+        },
+        homepage: contact("homepage"),
+        issues: contact("issues"),
+        sources: contact("sources"),
+        update_json_url: None,
+        logo_path: fabric_info.icon.as_ref().and_then(fabric_icon_path),
+    };
+
+    let mut dependencies = Vec::new();
+    for (entries, kind) in [
+        (&fabric_info.depends, DependencyKind::Required),
+        (&fabric_info.recommends, DependencyKind::Optional),
+        (&fabric_info.suggests, DependencyKind::Optional),
+        (&fabric_info.breaks, DependencyKind::Incompatible),
+        (&fabric_info.conflicts, DependencyKind::Discouraged),
+    ] {
+        for (mod_id, predicate) in entries {
+            dependencies.push(ModDependency {
+                mod_id: mod_id.clone(),
+                version_range: fabric_version_predicate(predicate),
+                kind: kind.clone(),
+            });
+        }
+    }
+
     Ok(ModInfo {
         id: fabric_info.id.clone(),
         name: fabric_info.name.unwrap_or(fabric_info.id),
@@ -240,15 +447,33 @@ fn extract_fabric_info(
         file_path: jar_path.to_path_buf(),
         enabled: true,
         side,
+        side_guess: None,
         loader: ModLoader::Fabric,
         raw_metadata: if let serde_json::Value::Object(map) = raw {
             map.into_iter().collect()
         } else {
             HashMap::new()
         },
+        dependencies,
+        metadata,
+        hashes: FileHashes::default(),
     })
 }
 
+/// Converts a fabric.mod.json version predicate (string or array) into one string.
+///
+/// Array entries are alternatives, so they are joined with `||`.
+fn fabric_version_predicate(value: &serde_json::Value) -> Option<String> {
+    match value {
+        serde_json::Value::String(predicate) => Some(predicate.clone()),
+        serde_json::Value::Array(predicates) => {
+            let predicates: Vec<&str> = predicates.iter().filter_map(|p| p.as_str()).collect();
+            (!predicates.is_empty()).then(|| predicates.join(" || "))
+        }
+        _ => None,
+    }
+}
+
 fn extract_mcmod_info(archive: &mut ZipArchive<std::fs::File>, jar_path: &Path) -> Result<ModInfo> {
     use tracing::debug;
 
@@ -312,15 +537,33 @@ fn extract_mcmod_info(archive: &mut ZipArchive<std::fs::File>, jar_path: &Path)
         })?
     };
 
+    let metadata = ModMetadata {
+        description: mcmod_info.description.filter(|d| !d.trim().is_empty()),
+        authors: mcmod_info
+            .author_list
+            .or(mcmod_info.authors)
+            .unwrap_or_default(),
+        license: None,
+        homepage: mcmod_info.url.filter(|url| !url.is_empty()),
+        issues: None,
+        sources: None,
+        update_json_url: mcmod_info.update_json.filter(|url| !url.is_empty()),
+        logo_path: mcmod_info.logo_file.filter(|path| !path.is_empty()),
+    };
+
     Ok(ModInfo {
         id: mcmod_info.mod_id.clone(),
         name: mcmod_info.name.unwrap_or(mcmod_info.mod_id.clone()),
         version: Some(mcmod_info.version),
         file_path: jar_path.to_path_buf(),
         enabled: true,
-        side: ModSide::Both, // mcmod.info doesn't typically specify side
+        side: ModSide::Unknown, // mcmod.info doesn't typically specify side
+        side_guess: None,
         loader: ModLoader::Forge,
         raw_metadata: HashMap::new(),
+        dependencies: Vec::new(),
+        metadata,
+        hashes: FileHashes::default(),
     })
 }
 
@@ -391,17 +634,253 @@ fn extract_manifest_info(
         version: Some(version),
         file_path: jar_path.to_path_buf(),
         enabled: true,
-        side: ModSide::Both,        // Can't determine from manifest
+        side: ModSide::Unknown, // Can't determine from manifest
+        side_guess: None,
         loader: ModLoader::Unknown, // Can't determine from manifest
         raw_metadata: HashMap::new(),
+        dependencies: Vec::new(),
+        metadata: ModMetadata::default(),
+        hashes: FileHashes::default(),
     })
 }
 
+/// Splits a Forge `authors` string such as `"Alice, Bob and Carol"` into names.
+fn split_authors(authors: &str) -> Vec<String> {
+    authors
+        .split([',', '&'])
+        .flat_map(|part| part.split(" and "))
+        .map(str::trim)
+        .filter(|name| !name.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+/// Picks the icon path from fabric.mod.json, preferring the largest size.
+fn fabric_icon_path(icon: &serde_json::Value) -> Option<String> {
+    match icon {
+        serde_json::Value::String(path) => Some(path.clone()),
+        serde_json::Value::Object(sizes) => sizes
+            .iter()
+            .max_by_key(|(size, _)| size.parse::<u32>().unwrap_or(0))
+            .and_then(|(_, path)| path.as_str())
+            .map(str::to_string),
+        _ => None,
+    }
+}
+
+/// Reads a mod's logo image from its jar, if it declares one.
+pub fn read_logo<P: AsRef<Path>>(jar_path: P, metadata: &ModMetadata) -> Result<Option<Vec<u8>>> {
+    let Some(logo_path) = &metadata.logo_path else {
+        return Ok(None);
+    };
+    let file = std::fs::File::open(jar_path.as_ref())?;
+    let mut archive = ZipArchive::new(file)?;
+    let mut logo = match archive.by_name(logo_path.trim_start_matches('/')) {
+        Ok(logo) => logo,
+        Err(zip::result::ZipError::FileNotFound) => return Ok(None),
+        Err(e) => return Err(e.into()),
+    };
+    let mut bytes = Vec::new();
+    logo.read_to_end(&mut bytes)?;
+    Ok(Some(bytes))
+}
+
 fn parse_forge_side(side: &Option<String>) -> ModSide {
     match side.as_deref() {
         Some("CLIENT") => ModSide::Client,
         Some("SERVER") => ModSide::Server,
-        Some("BOTH") | None => ModSide::Both,
+        Some("BOTH") => ModSide::Both,
         _ => ModSide::Unknown,
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+    use std::path::PathBuf;
+    use zip::write::{SimpleFileOptions, ZipWriter};
+
+    const LOGO: &[u8] = b"\x89PNG fake logo";
+
+    fn write_jar(dir: &Path, entries: &[(&str, &[u8])]) -> PathBuf {
+        let path = dir.join("example.jar");
+        let mut writer = ZipWriter::new(std::fs::File::create(&path).unwrap());
+        for (name, data) in entries {
+            writer
+                .start_file(*name, SimpleFileOptions::default())
+                .unwrap();
+            writer.write_all(data).unwrap();
+        }
+        writer.finish().unwrap();
+        path
+    }
+
+    #[test]
+    fn splits_forge_author_strings() {
+        assert_eq!(
+            split_authors("Alice, Bob and Carol & Dave"),
+            ["Alice", "Bob", "Carol", "Dave"]
+        );
+        assert!(split_authors(" ").is_empty());
+    }
+
+    #[test]
+    fn picks_largest_fabric_icon() {
+        let icon =
+            serde_json::json!({"16": "icon16.png", "128": "icon128.png", "64": "icon64.png"});
+        assert_eq!(fabric_icon_path(&icon).as_deref(), Some("icon128.png"));
+        assert_eq!(
+            fabric_icon_path(&serde_json::json!("icon.png")).as_deref(),
+            Some("icon.png")
+        );
+    }
+
+    #[test]
+    fn reads_mods_toml_metadata() {
+        let mods_toml = br#"
+modLoader = "javafml"
+loaderVersion = "[4,)"
+license = "MIT"
+issueTrackerURL = "https://example.com/issues"
+logoFile = "logo.png"
+
+[[mods]]
+modId = "example"
+version = "1.2.0"
+displayName = "Example"
+description = '''
+An example mod.
+'''
+authors = "Alice, Bob"
+displayURL = "https://example.com"
+updateJSONURL = "https://example.com/update.json"
+"#;
+        for toml_path in ["META-INF/mods.toml", "META-INF/neoforge.mods.toml"] {
+            let dir = tempfile::tempdir().unwrap();
+            let jar = write_jar(dir.path(), &[(toml_path, mods_toml), ("logo.png", LOGO)]);
+
+            let info = extract_jar_info(&jar).unwrap();
+            assert_eq!(info.id, "example");
+            assert_eq!(info.loader, ModLoader::NeoForge);
+            assert_eq!(
+                info.metadata,
+                ModMetadata {
+                    description: Some("An example mod.".to_string()),
+                    authors: vec!["Alice".to_string(), "Bob".to_string()],
+                    license: Some("MIT".to_string()),
+                    homepage: Some("https://example.com".to_string()),
+                    issues: Some("https://example.com/issues".to_string()),
+                    sources: None,
+                    update_json_url: Some("https://example.com/update.json".to_string()),
+                    logo_path: Some("logo.png".to_string()),
+                },
+                "{toml_path}"
+            );
+            assert_eq!(info.raw_metadata["displayName"], "Example");
+            assert_eq!(
+                read_logo(&jar, &info.metadata).unwrap().as_deref(),
+                Some(LOGO)
+            );
+        }
+    }
+
+    #[test]
+    fn reads_fabric_metadata() {
+        let fabric_json = br#"{
+            "id": "example",
+            "version": "1.2.0",
+            "description": "An example mod.",
+            "authors": ["Alice", {"name": "Bob", "contact": {}}],
+            "license": ["MIT", "Apache-2.0"],
+            "contact": {
+                "homepage": "https://example.com",
+                "issues": "https://example.com/issues",
+                "sources": "https://example.com/src"
+            },
+            "icon": {"32": "assets/example/icon32.png", "128": "assets/example/icon.png"}
+        }"#;
+        let dir = tempfile::tempdir().unwrap();
+        let jar = write_jar(
+            dir.path(),
+            &[
+                ("fabric.mod.json", fabric_json),
+                ("assets/example/icon.png", LOGO),
+            ],
+        );
+
+        let info = extract_jar_info(&jar).unwrap();
+        assert_eq!(info.loader, ModLoader::Fabric);
+        assert_eq!(
+            info.metadata,
+            ModMetadata {
+                description: Some("An example mod.".to_string()),
+                authors: vec!["Alice".to_string(), "Bob".to_string()],
+                license: Some("MIT, Apache-2.0".to_string()),
+                homepage: Some("https://example.com".to_string()),
+                issues: Some("https://example.com/issues".to_string()),
+                sources: Some("https://example.com/src".to_string()),
+                update_json_url: None,
+                logo_path: Some("assets/example/icon.png".to_string()),
+            }
+        );
+        assert_eq!(info.raw_metadata["id"], "example");
+        assert_eq!(
+            read_logo(&jar, &info.metadata).unwrap().as_deref(),
+            Some(LOGO)
+        );
+    }
+
+    #[test]
+    fn reads_mcmod_info_metadata() {
+        let mcmod_info = br#"[{
+            "modid": "example",
+            "name": "Example",
+            "version": "1.2.0",
+            "description": "An example mod.",
+            "authorList": ["Alice", "Bob"],
+            "url": "https://example.com",
+            "updateJSON": "https://example.com/update.json",
+            "logoFile": "/logo.png"
+        }]"#;
+        let dir = tempfile::tempdir().unwrap();
+        let jar = write_jar(
+            dir.path(),
+            &[("mcmod.info", mcmod_info), ("logo.png", LOGO)],
+        );
+
+        let info = extract_jar_info(&jar).unwrap();
+        assert_eq!(info.loader, ModLoader::Forge);
+        assert_eq!(
+            info.metadata,
+            ModMetadata {
+                description: Some("An example mod.".to_string()),
+                authors: vec!["Alice".to_string(), "Bob".to_string()],
+                license: None,
+                homepage: Some("https://example.com".to_string()),
+                issues: None,
+                sources: None,
+                update_json_url: Some("https://example.com/update.json".to_string()),
+                logo_path: Some("/logo.png".to_string()),
+            }
+        );
+        assert_eq!(
+            read_logo(&jar, &info.metadata).unwrap().as_deref(),
+            Some(LOGO)
+        );
+    }
+
+    #[test]
+    fn missing_logo_reads_as_none() {
+        let dir = tempfile::tempdir().unwrap();
+        let jar = write_jar(
+            dir.path(),
+            &[("mcmod.info", br#"{"modid": "example", "version": "1"}"#)],
+        );
+
+        let mut metadata = extract_jar_info(&jar).unwrap().metadata;
+        assert_eq!(read_logo(&jar, &metadata).unwrap(), None);
+        metadata.logo_path = Some("logo.png".to_string());
+        assert_eq!(read_logo(&jar, &metadata).unwrap(), None);
+    }
+}
@@ -5,11 +5,23 @@
 
 #![cfg_attr(not(debug_assertions), forbid(missing_docs))]
 
+pub mod conflicts;
+pub mod decision;
+pub mod duplicates;
+pub mod environment;
 pub mod error;
 pub mod jar;
 pub mod prelude;
 pub mod rules;
+pub mod side;
+pub mod version;
 
+pub use conflicts::*;
+pub use decision::*;
+pub use duplicates::*;
+pub use environment::*;
 pub use error::*;
 pub use jar::*;
 pub use rules::*;
+pub use side::*;
+pub use version::*;
@@ -2,9 +2,19 @@
 //!
 //! Import with `use mc_link_compat::prelude::*;` to get commonly used compatibility types.
 
+pub use crate::conflicts::{ModConflict, find_conflicts};
+pub use crate::decision::{Decision, DecisionCause, DecisionOutcome};
+pub use crate::duplicates::{DuplicateGroup, DuplicateKind, find_duplicates};
+pub use crate::environment::{
+    EnvironmentIssue, EnvironmentIssueKind, GameEnvironment, LoaderMismatch, ModReadiness,
+    UpgradeReport, check_environment, check_upgrade, detect_loader,
+};
 pub use crate::error::{CompatError, Result};
-pub use crate::jar::extract_jar_info;
+pub use crate::jar::{extract_jar_info, read_logo};
 pub use crate::rules::{
-    CompatConfig, CompatResult, CompatRule, RuleType, VersionMismatch, check_compatibility,
+    CompatConfig, CompatResult, CompatRule, CompatWarning, DowngradePolicy, PatternKind,
+    RuleSource, RuleType, VersionMismatch, VersionTolerance, check_compatibility,
 };
+pub use crate::side::classify_side;
+pub use crate::version::{ModVersion, VersionDirection, VersionRange, compare_versions};
 pub use mc_link_core::{ModInfo, ModLoader, ModSide};
@@ -1,6 +1,15 @@
-use crate::Result;
-use mc_link_core::{ModInfo, ModSide};
+use crate::conflicts::{ModConflict, find_conflicts};
+use crate::decision::{Decision, DecisionCause, DecisionOutcome};
+use crate::duplicates::{DuplicateGroup, deduplicate, find_duplicates};
+use crate::environment::{EnvironmentIssue, EnvironmentIssueKind, GameEnvironment, LoaderMismatch};
+use crate::version::{
+    VersionDirection, VersionRange, classify_version_change, same_major_minor, versions_equivalent,
+};
+use crate::{CompatError, Result};
+use mc_link_core::{ModInfo, ModSide, SideConfidence};
+use regex::Regex;
 use serde::{Deserialize, Serialize};
+use std::borrow::Cow;
 use std::collections::{HashMap, HashSet};
 
 /// Configuration for mod compatibility checking.
@@ -14,6 +23,18 @@ pub struct CompatConfig {
     pub auto_ignore_client_only: bool,
     /// Whether to automatically ignore server-only mods on client
     pub auto_ignore_server_only: bool,
+    /// How version mismatches that would downgrade the target are handled
+    #[serde(default)]
+    pub downgrade_policy: DowngradePolicy,
+    /// Alternative mod IDs mapped to the ID they are compared under
+    #[serde(default)]
+    pub aliases: HashMap<String, String>,
+    /// Environment of the client; a missing loader is detected from its mods
+    #[serde(default)]
+    pub client_environment: GameEnvironment,
+    /// Environment of the server; a missing loader is detected from its mods
+    #[serde(default)]
+    pub server_environment: GameEnvironment,
 }
 
 impl Default for CompatConfig {
@@ -23,23 +44,318 @@ impl Default for CompatConfig {
             custom_rules: Vec::new(),
             auto_ignore_client_only: true,
             auto_ignore_server_only: true,
+            downgrade_policy: DowngradePolicy::default(),
+            aliases: HashMap::new(),
+            client_environment: GameEnvironment::default(),
+            server_environment: GameEnvironment::default(),
         }
     }
 }
 
+impl CompatConfig {
+    /// Returns the ID a mod is compared under, resolving aliases.
+    pub fn canonical_id<'a>(&'a self, mod_id: &'a str) -> &'a str {
+        self.aliases.get(mod_id).map_or(mod_id, String::as_str)
+    }
+
+    /// Rewrites aliased mod IDs, including those of dependencies, to their canonical ID.
+    ///
+    /// Borrows `mods` unchanged when no mod has an alias.
+    pub fn apply_aliases<'m>(&self, mods: &'m [ModInfo]) -> Cow<'m, [ModInfo]> {
+        let aliased = |id: &String| self.aliases.contains_key(id);
+        if !mods
+            .iter()
+            .any(|m| aliased(&m.id) || m.dependencies.iter().any(|d| aliased(&d.mod_id)))
+        {
+            return Cow::Borrowed(mods);
+        }
+        Cow::Owned(
+            mods.iter()
+                .map(|m| {
+                    let mut m = m.clone();
+                    m.id = self.canonical_id(&m.id).to_string();
+                    for dependency in &mut m.dependencies {
+                        dependency.mod_id = self.canonical_id(&dependency.mod_id).to_string();
+                    }
+                    m
+                })
+                .collect(),
+        )
+    }
+}
+
+/// Policy for version mismatches where the target has the newer version.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum DowngradePolicy {
+    /// Downgrade the target like any other update
+    Allow,
+    /// Plan the downgrade but require explicit confirmation before applying it
+    #[default]
+    Confirm,
+    /// Never downgrade; keep the target's newer version
+    Refuse,
+}
+
 /// Custom compatibility rule for specific mod combinations.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CompatRule {
-    /// Mod ID this rule applies to
+    /// Mod ID or pattern this rule applies to
     pub mod_id: String,
+    /// How `mod_id` is matched against mod IDs
+    #[serde(default)]
+    pub pattern: PatternKind,
     /// Rule type
     pub rule_type: RuleType,
     /// Human-readable reason for the rule
     pub reason: String,
+    /// Where the rule was defined
+    #[serde(default)]
+    pub source: RuleSource,
+}
+
+impl CompatRule {
+    /// Creates a rule matching a single mod ID exactly.
+    pub fn new(mod_id: impl Into<String>, rule_type: RuleType, reason: impl Into<String>) -> Self {
+        Self {
+            mod_id: mod_id.into(),
+            pattern: PatternKind::Exact,
+            rule_type,
+            reason: reason.into(),
+            source: RuleSource::default(),
+        }
+    }
+
+    /// Records where the rule was defined.
+    pub fn with_source(mut self, source: RuleSource) -> Self {
+        self.source = source;
+        self
+    }
+
+    /// Sets how the rule's `mod_id` is matched.
+    pub fn with_pattern(mut self, pattern: PatternKind) -> Self {
+        self.pattern = pattern;
+        self
+    }
+}
+
+impl std::str::FromStr for CompatRule {
+    type Err = CompatError;
+
+    /// Parses a rule written as `<action> <pattern> [# reason]`.
+    ///
+    /// The reason starts at the first `#` that follows whitespace, so
+    /// patterns like `/a#b/` keep their `#`. Write `\#` for a literal `#`
+    /// after whitespace, as in `ignore /^a \#b$/`.
+    ///
+    /// Actions are `ignore`, `require`, `client-only`, `server-only`, `warn`
+    /// and `tolerate`. A `tolerate` rule takes the accepted drift after the
+    /// pattern: `minor` (the default), `any`, or a version range, as in
+    /// `tolerate jei >=19.21 <20`. A pattern written as `/.../` or prefixed with `re:` is a
+    /// regular expression; one containing `*` or `?` is a glob; anything
+    /// else is an exact mod ID. For example: `client-only *-client # UI only`.
+    fn from_str(s: &str) -> Result<Self> {
+        let invalid = |reason: &str| CompatError::InvalidRule {
+            pattern: s.to_string(),
+            reason: reason.to_string(),
+        };
+
+        let (rule, reason) = split_reason(s);
+        let (action, mut pattern) = rule
+            .trim()
+            .split_once(char::is_whitespace)
+            .map(|(action, pattern)| (action, pattern.trim()))
+            .ok_or_else(|| invalid("expected `<action> <pattern>`"))?;
+
+        let rule_type = match action.to_ascii_lowercase().as_str() {
+            "ignore" => RuleType::AlwaysIgnore,
+            "require" => RuleType::RequireBoth,
+            "client-only" => RuleType::ClientOnly,
+            "server-only" => RuleType::ServerOnly,
+            "warn" => RuleType::Warn,
+            "tolerate" => {
+                let (mod_pattern, tolerance) = pattern
+                    .split_once(char::is_whitespace)
+                    .unwrap_or((pattern, "minor"));
+                pattern = mod_pattern;
+                RuleType::Tolerate(tolerance.trim().into())
+            }
+            _ => return Err(invalid(&format!("unknown action '{action}'"))),
+        };
+
+        let (pattern, kind) = if let Some(regex) = pattern.strip_prefix("re:") {
+            (regex, PatternKind::Regex)
+        } else if let Some(regex) = pattern
+            .strip_prefix('/')
+            .and_then(|p| p.strip_suffix('/'))
+            .filter(|p| !p.is_empty())
+        {
+            (regex, PatternKind::Regex)
+        } else if pattern.contains(['*', '?']) {
+            (pattern, PatternKind::Glob)
+        } else {
+            (pattern, PatternKind::Exact)
+        };
+        if pattern.is_empty()
+            || (kind != PatternKind::Regex && pattern.contains(char::is_whitespace))
+        {
+            return Err(invalid("expected a single mod ID or pattern"));
+        }
+
+        Ok(CompatRule::new(pattern, rule_type, reason).with_pattern(kind))
+    }
+}
+
+/// Splits a textual rule at the `#` that starts its reason, unescaping `\#`.
+fn split_reason(s: &str) -> (String, &str) {
+    let mut rule = String::with_capacity(s.len());
+    let mut chars = s.char_indices().peekable();
+    let mut after_space = true;
+    while let Some((i, c)) = chars.next() {
+        match c {
+            '\\' if chars.peek().is_some_and(|&(_, next)| next == '#') => {
+                chars.next();
+                rule.push('#');
+                after_space = false;
+                continue;
+            }
+            '#' if after_space => return (rule, s[i + 1..].trim()),
+            _ => rule.push(c),
+        }
+        after_space = c.is_whitespace();
+    }
+    (rule, "")
+}
+
+/// Where a compatibility rule was defined.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum RuleSource {
+    /// Added directly to the config, e.g. by code
+    #[default]
+    Config,
+    /// The global ignore list
+    GlobalIgnoreList,
+    /// The server's ignore list
+    ServerIgnoreList,
+    /// One of the server's custom rules
+    ServerRule {
+        /// Position of the rule in the server's list, starting at 1
+        index: usize,
+    },
+    /// A rule in a compatibility profile
+    Profile {
+        /// Profile name
+        name: String,
+        /// Position of the rule in the profile, starting at 1
+        index: usize,
+    },
+    /// The command line
+    CommandLine,
+}
+
+impl std::fmt::Display for RuleSource {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RuleSource::Config => write!(f, "the compatibility config"),
+            RuleSource::GlobalIgnoreList => write!(f, "the global ignore list"),
+            RuleSource::ServerIgnoreList => write!(f, "the server ignore list"),
+            RuleSource::ServerRule { index } => write!(f, "server custom rule {index}"),
+            RuleSource::Profile { name, index } => write!(f, "rule {index} in profile '{name}'"),
+            RuleSource::CommandLine => write!(f, "the command line"),
+        }
+    }
+}
+
+/// How a rule pattern is matched against mod IDs.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum PatternKind {
+    /// The pattern is a literal mod ID
+    #[default]
+    Exact,
+    /// Shell-style glob where `*` matches any run of characters and `?` one character
+    Glob,
+    /// Regular expression, matching anywhere in the ID unless anchored
+    Regex,
+}
+
+/// A rule with its pattern compiled, ready for matching.
+struct CompiledRule<'a> {
+    rule: &'a CompatRule,
+    regex: Option<Regex>,
+    range: Option<VersionRange>,
+}
+
+impl CompiledRule<'_> {
+    fn matches(&self, mod_id: &str) -> bool {
+        match &self.regex {
+            Some(regex) => regex.is_match(mod_id),
+            None => self.rule.mod_id == mod_id,
+        }
+    }
+
+    /// Returns true if the rule accepts the drift between two versions.
+    fn tolerates(&self, client_version: &str, server_version: &str) -> bool {
+        match &self.rule.rule_type {
+            RuleType::Tolerate(VersionTolerance::Any) => true,
+            RuleType::Tolerate(VersionTolerance::SameMinor) => {
+                same_major_minor(client_version, server_version)
+            }
+            RuleType::Tolerate(VersionTolerance::Range(_)) => {
+                self.range.as_ref().is_some_and(|range| {
+                    range.contains_str(client_version) == Some(true)
+                        && range.contains_str(server_version) == Some(true)
+                })
+            }
+            _ => false,
+        }
+    }
+}
+
+/// Compiles rule patterns once so they are not rebuilt for every mod.
+fn compile_rules(rules: &[CompatRule]) -> Result<Vec<CompiledRule<'_>>> {
+    rules
+        .iter()
+        .map(|rule| {
+            let source = match rule.pattern {
+                PatternKind::Exact => None,
+                PatternKind::Glob => Some(glob_to_regex(&rule.mod_id)),
+                PatternKind::Regex => Some(rule.mod_id.clone()),
+            };
+            let regex = source
+                .map(|source| Regex::new(&source))
+                .transpose()
+                .map_err(|e| CompatError::InvalidRule {
+                    pattern: rule.mod_id.clone(),
+                    reason: e.to_string(),
+                })?;
+            let range = match &rule.rule_type {
+                RuleType::Tolerate(VersionTolerance::Range(spec)) => Some(
+                    VersionRange::parse(spec).ok_or_else(|| CompatError::InvalidRule {
+                        pattern: rule.mod_id.clone(),
+                        reason: format!("invalid version range '{spec}'"),
+                    })?,
+                ),
+                _ => None,
+            };
+            Ok(CompiledRule { rule, regex, range })
+        })
+        .collect()
+}
+
+fn glob_to_regex(glob: &str) -> String {
+    let mut regex = String::from("^");
+    for c in glob.chars() {
+        match c {
+            '*' => regex.push_str(".*"),
+            '?' => regex.push('.'),
+            c => regex.push_str(&regex::escape(&c.to_string())),
+        }
+    }
+    regex.push('$');
+    regex
 }
 
 /// Types of compatibility rules.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub enum RuleType {
     /// Always ignore this mod
     AlwaysIgnore,
@@ -49,6 +365,36 @@ pub enum RuleType {
     ClientOnly,
     /// Only allow on server
     ServerOnly,
+    /// Check normally, but report a warning whenever the mod is present
+    Warn,
+    /// Check normally, but accept version differences within a tolerance
+    ///
+    /// Unlike the other rule types, the first matching `Tolerate` rule applies
+    /// even when another rule matched the mod first, so a `Warn` rule does
+    /// not cancel a tolerance.
+    Tolerate(VersionTolerance),
+}
+
+/// How much version drift a [`RuleType::Tolerate`] rule accepts.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum VersionTolerance {
+    /// Versions sharing the same major and minor components, e.g. `5.2.0` and `5.2.3`
+    SameMinor,
+    /// Versions that both fall within a range, in semver or Maven syntax
+    Range(String),
+    /// Any pair of versions
+    Any,
+}
+
+impl From<&str> for VersionTolerance {
+    /// Reads `minor` or `any`; anything else is taken as a version range.
+    fn from(spec: &str) -> Self {
+        match spec.to_ascii_lowercase().as_str() {
+            "minor" | "major.minor" => VersionTolerance::SameMinor,
+            "any" | "*" => VersionTolerance::Any,
+            _ => VersionTolerance::Range(spec.to_string()),
+        }
+    }
 }
 
 /// Result of a compatibility check between client and server mods.
@@ -62,10 +408,69 @@ pub struct CompatResult {
     pub version_mismatches: Vec<VersionMismatch>,
     /// Mods that were ignored during the check
     pub ignored_mods: Vec<String>,
+    /// Non-fatal findings that do not affect `is_compatible`
+    #[serde(default)]
+    pub warnings: Vec<CompatWarning>,
+    /// Every decision made during the check, in order
+    #[serde(default)]
+    pub decisions: Vec<Decision>,
+    /// Mods installed more than once on the client; each group is also
+    /// reported as a warning, since only the preferred copy is synced
+    #[serde(default)]
+    pub client_duplicates: Vec<DuplicateGroup>,
+    /// Mods installed more than once on the server, which is being synced
+    /// to; these make the instances incompatible
+    #[serde(default)]
+    pub server_duplicates: Vec<DuplicateGroup>,
+    /// Declared incompatibilities between mods on the client
+    #[serde(default)]
+    pub client_conflicts: Vec<ModConflict>,
+    /// Declared incompatibilities between mods on the server
+    #[serde(default)]
+    pub server_conflicts: Vec<ModConflict>,
+    /// Hard conflicts that adding mods from `missing_on_server` would
+    /// introduce; the mods involved must not be added
+    #[serde(default)]
+    pub blocked_additions: Vec<ModConflict>,
+    /// Set when the two instances run loaders that cannot share mods
+    #[serde(default)]
+    pub loader_mismatch: Option<LoaderMismatch>,
+    /// Mods from `missing_on_server` built for a loader the server cannot
+    /// run; these must not be added
+    #[serde(default)]
+    pub unloadable_additions: Vec<EnvironmentIssue>,
     /// Overall compatibility status
     pub is_compatible: bool,
 }
 
+impl CompatResult {
+    /// Returns the decisions made for one mod, in the order they were made.
+    pub fn explain<'a>(&'a self, mod_id: &'a str) -> impl Iterator<Item = &'a Decision> {
+        self.decisions.iter().filter(move |d| d.mod_id == mod_id)
+    }
+
+    fn decide(&mut self, mod_id: &str, outcome: DecisionOutcome, cause: DecisionCause) {
+        self.decisions.push(Decision {
+            mod_id: mod_id.to_string(),
+            outcome,
+            cause,
+        });
+    }
+}
+
+/// A non-fatal finding raised by a [`RuleType::Warn`] rule.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CompatWarning {
+    /// Mod ID
+    pub mod_id: String,
+    /// Mod name
+    pub mod_name: String,
+    /// Pattern of the rule that raised the warning
+    pub rule: String,
+    /// Reason given by the rule
+    pub reason: String,
+}
+
 /// Information about a version mismatch between client and server.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct VersionMismatch {
@@ -77,6 +482,8 @@ pub struct VersionMismatch {
     pub client_version: String,
     /// Version on server
     pub server_version: String,
+    /// Direction of the change when the server is brought to the client version
+    pub direction: VersionDirection,
 }
 
 /// Checks compatibility between client and server mod lists.
@@ -93,40 +500,64 @@ pub fn check_compatibility(
         "Starting a detailed compatibility check"
     );
 
+    let rules = compile_rules(&config.custom_rules)?;
+
+    // Compare mods that go by different IDs under one canonical ID
+    let client_mods = config.apply_aliases(client_mods);
+    let server_mods = config.apply_aliases(server_mods);
+    let (client_mods, server_mods) = (client_mods.as_ref(), server_mods.as_ref());
+
     let mut result = CompatResult {
         missing_on_server: Vec::new(),
         missing_on_client: Vec::new(),
         version_mismatches: Vec::new(),
         ignored_mods: Vec::new(),
+        warnings: Vec::new(),
+        decisions: Vec::new(),
+        client_duplicates: find_duplicates(client_mods),
+        server_duplicates: find_duplicates(server_mods),
+        client_conflicts: Vec::new(),
+        server_conflicts: Vec::new(),
+        blocked_additions: Vec::new(),
+        loader_mismatch: None,
+        unloadable_additions: Vec::new(),
         is_compatible: true,
     };
+    // Only the preferred client copy is synced, so client duplicates are
+    // worth a warning; duplicates on the server break the synced instance
+    for group in &result.client_duplicates {
+        let preferred = group.preferred();
+        result.warnings.push(CompatWarning {
+            mod_id: preferred.id.clone(),
+            mod_name: preferred.name.clone(),
+            rule: "duplicate detection".to_string(),
+            reason: group.to_string(),
+        });
+    }
+    if !result.server_duplicates.is_empty() {
+        result.is_compatible = false;
+    }
 
-    // Create lookup maps for efficient comparison using the proper mod IDs
-    let client_map: HashMap<String, &ModInfo> = client_mods
-        .iter()
-        .map(|m| {
-            debug!(
-                mod_id = %m.id,
-                mod_name = %m.name,
-                file_path = %m.file_path.display(),
-                "Client mod mapping"
-            );
-            (m.id.clone(), m)
-        })
-        .collect();
-
-    let server_map: HashMap<String, &ModInfo> = server_mods
-        .iter()
-        .map(|m| {
-            debug!(
-                mod_id = %m.id,
-                mod_name = %m.name,
-                file_path = %m.file_path.display(),
-                "Server mod mapping"
-            );
-            (m.id.clone(), m)
-        })
-        .collect();
+    // Create lookup maps using the proper mod IDs; duplicates resolve to the
+    // copy with the highest version
+    let client_map: HashMap<&str, &ModInfo> = deduplicate(client_mods);
+    let server_map: HashMap<&str, &ModInfo> = deduplicate(server_mods);
+    for m in client_map.values() {
+        debug!(
+            mod_id = %m.id,
+            mod_name = %m.name,
+            file_path = %m.file_path.display(),
+            "Client mod mapping"
+        );
+    }
+    for m in server_map.values() {
+        debug!(
+            mod_id = %m.id,
+            mod_name = %m.name,
+            file_path = %m.file_path.display(),
+            "Server mod mapping"
+        );
+    }
 
     info!(
         unique_client_ids = client_map.len(),
@@ -134,33 +565,50 @@ pub fn check_compatibility(
         "Created mod ID mappings"
     );
 
-    // Check each client mod
+    // Check each client mod, skipping stale duplicate copies
     for client_mod in client_mods {
         let mod_id = &client_mod.id;
+        if !std::ptr::eq(client_map[mod_id.as_str()], client_mod) {
+            continue;
+        }
 
         // Skip if in ignore list
         if config.ignore_list.contains(mod_id) {
             result.ignored_mods.push(mod_id.clone());
+            result.decide(
+                mod_id,
+                DecisionOutcome::Ignored,
+                DecisionCause::IgnoreList {
+                    source: RuleSource::Config,
+                },
+            );
             continue;
         }
 
         // Apply custom rules
-        if let Some(rule) = config.custom_rules.iter().find(|r| r.mod_id == *mod_id) {
+        if let Some(rule) = rules.iter().find(|r| r.matches(mod_id)).map(|r| r.rule) {
             match rule.rule_type {
-                RuleType::AlwaysIgnore => {
-                    result.ignored_mods.push(mod_id.clone());
-                    continue;
-                }
-                RuleType::ClientOnly => {
+                RuleType::AlwaysIgnore | RuleType::ClientOnly => {
                     result.ignored_mods.push(mod_id.clone());
+                    result.decide(mod_id, DecisionOutcome::Ignored, rule_cause(rule));
                     continue;
                 }
                 RuleType::ServerOnly => {
                     result.missing_on_server.push(client_mod.clone());
                     result.is_compatible = false;
+                    result.decide(mod_id, DecisionOutcome::MissingOnServer, rule_cause(rule));
                     continue;
                 }
-                RuleType::RequireBoth => {
+                RuleType::Warn => {
+                    result.warnings.push(CompatWarning {
+                        mod_id: mod_id.clone(),
+                        mod_name: client_mod.name.clone(),
+                        rule: rule.mod_id.clone(),
+                        reason: rule.reason.clone(),
+                    });
+                    result.decide(mod_id, DecisionOutcome::Warned, rule_cause(rule));
+                }
+                RuleType::RequireBoth | RuleType::Tolerate(_) => {
                     // Continue with normal checking
                 }
             }
@@ -170,57 +618,138 @@ pub fn check_compatibility(
         match client_mod.side {
             ModSide::Client if config.auto_ignore_client_only => {
                 result.ignored_mods.push(mod_id.clone());
+                result.decide(
+                    mod_id,
+                    DecisionOutcome::Ignored,
+                    DecisionCause::Side(ModSide::Client),
+                );
                 continue;
             }
             ModSide::Server => {
                 result.missing_on_server.push(client_mod.clone());
                 result.is_compatible = false;
+                result.decide(
+                    mod_id,
+                    DecisionOutcome::MissingOnServer,
+                    DecisionCause::Side(ModSide::Server),
+                );
                 continue;
             }
             _ => {}
         }
 
         // Check if mod exists on server
-        if let Some(server_mod) = server_map.get(mod_id) {
+        if let Some(server_mod) = server_map.get(mod_id.as_str()) {
             // Check version compatibility
-            if client_mod.version != server_mod.version {
-                if let (Some(client_ver), Some(server_ver)) =
-                    (&client_mod.version, &server_mod.version)
-                {
+            if let (Some(client_ver), Some(server_ver)) = (&client_mod.version, &server_mod.version)
+            {
+                if !versions_equivalent(client_ver, server_ver) {
+                    let tolerance = rules.iter().find(|r| {
+                        r.matches(mod_id) && matches!(r.rule.rule_type, RuleType::Tolerate(_))
+                    });
+                    if let Some(rule) = tolerance.filter(|r| r.tolerates(client_ver, server_ver)) {
+                        result.decide(mod_id, DecisionOutcome::Compatible, rule_cause(rule.rule));
+                        continue;
+                    }
                     result.version_mismatches.push(VersionMismatch {
                         mod_id: mod_id.clone(),
                         mod_name: client_mod.name.clone(),
                         client_version: client_ver.clone(),
                         server_version: server_ver.clone(),
+                        direction: classify_version_change(server_ver, client_ver),
                     });
                     result.is_compatible = false;
+                    result.decide(
+                        mod_id,
+                        DecisionOutcome::VersionMismatch,
+                        DecisionCause::VersionMismatch {
+                            client_version: client_ver.clone(),
+                            server_version: server_ver.clone(),
+                        },
+                    );
+                    continue;
                 }
             }
+            result.decide(mod_id, DecisionOutcome::Compatible, DecisionCause::Matching);
         } else {
+            // Ask for a second look before uploading a mod whose side is a weak guess
+            if let Some(guess) = client_mod
+                .side_guess
+                .as_ref()
+                .filter(|guess| guess.confidence == SideConfidence::Low)
+            {
+                result.warnings.push(CompatWarning {
+                    mod_id: mod_id.clone(),
+                    mod_name: client_mod.name.clone(),
+                    rule: "side detection".to_string(),
+                    reason: format!(
+                        "no side declared and the guess ({:?}) is unreliable; it may be client-only",
+                        guess.side
+                    ),
+                });
+                result.decide(
+                    mod_id,
+                    DecisionOutcome::Warned,
+                    DecisionCause::SideGuess(guess.clone()),
+                );
+            }
             result.missing_on_server.push(client_mod.clone());
             result.is_compatible = false;
+            result.decide(
+                mod_id,
+                DecisionOutcome::MissingOnServer,
+                DecisionCause::Absent,
+            );
         }
     }
 
     // Check each server mod for client-missing mods
     for server_mod in server_mods {
         let mod_id = &server_mod.id;
+        if !std::ptr::eq(server_map[mod_id.as_str()], server_mod) {
+            continue;
+        }
+
+        // Skip if already processed
+        if client_map.contains_key(mod_id.as_str()) {
+            continue;
+        }
 
-        // Skip if in ignore list or already processed
-        if config.ignore_list.contains(mod_id) || client_map.contains_key(mod_id) {
+        // Skip if in ignore list
+        if config.ignore_list.contains(mod_id) {
+            result.decide(
+                mod_id,
+                DecisionOutcome::Ignored,
+                DecisionCause::IgnoreList {
+                    source: RuleSource::Config,
+                },
+            );
             continue;
         }
 
         // Apply custom rules
-        if let Some(rule) = config.custom_rules.iter().find(|r| r.mod_id == *mod_id) {
+        if let Some(rule) = rules.iter().find(|r| r.matches(mod_id)).map(|r| r.rule) {
             match rule.rule_type {
-                RuleType::AlwaysIgnore | RuleType::ServerOnly => continue,
+                RuleType::AlwaysIgnore | RuleType::ServerOnly => {
+                    result.decide(mod_id, DecisionOutcome::Ignored, rule_cause(rule));
+                    continue;
+                }
                 RuleType::ClientOnly => {
                     result.missing_on_client.push(server_mod.clone());
                     result.is_compatible = false;
+                    result.decide(mod_id, DecisionOutcome::MissingOnClient, rule_cause(rule));
                     continue;
                 }
-                RuleType::RequireBoth => {
+                RuleType::Warn => {
+                    result.warnings.push(CompatWarning {
+                        mod_id: mod_id.clone(),
+                        mod_name: server_mod.name.clone(),
+                        rule: rule.mod_id.clone(),
+                        reason: rule.reason.clone(),
+                    });
+                    result.decide(mod_id, DecisionOutcome::Warned, rule_cause(rule));
+                }
+                RuleType::RequireBoth | RuleType::Tolerate(_) => {
                     // Continue with normal checking
                 }
             }
@@ -228,10 +757,22 @@ pub fn check_compatibility(
 
         // Auto-ignore based on mod side information
         match server_mod.side {
-            ModSide::Server if config.auto_ignore_server_only => continue,
+            ModSide::Server if config.auto_ignore_server_only => {
+                result.decide(
+                    mod_id,
+                    DecisionOutcome::Ignored,
+                    DecisionCause::Side(ModSide::Server),
+                );
+                continue;
+            }
             ModSide::Client => {
                 result.missing_on_client.push(server_mod.clone());
                 result.is_compatible = false;
+                result.decide(
+                    mod_id,
+                    DecisionOutcome::MissingOnClient,
+                    DecisionCause::Side(ModSide::Client),
+                );
                 continue;
             }
             _ => {}
@@ -239,7 +780,487 @@ pub fn check_compatibility(
 
         result.missing_on_client.push(server_mod.clone());
         result.is_compatible = false;
+        result.decide(
+            mod_id,
+            DecisionOutcome::MissingOnClient,
+            DecisionCause::Absent,
+        );
+    }
+
+    // Loaders of both instances, as configured or detected from their mods
+    let client_env = config.client_environment.with_detected_loader(client_mods);
+    let server_env = config.server_environment.with_detected_loader(server_mods);
+    if let (Some(client_loader), Some(server_loader)) = (&client_env.loader, &server_env.loader)
+        && !server_env.accepts_loader(client_loader)
+    {
+        result.loader_mismatch = Some(LoaderMismatch {
+            client_loader: client_loader.clone(),
+            server_loader: server_loader.clone(),
+        });
+        result.is_compatible = false;
+    }
+    if let Some(server_loader) = &server_env.loader {
+        let unloadable: Vec<EnvironmentIssue> = result
+            .missing_on_server
+            .iter()
+            .filter(|m| !server_env.accepts_loader(&m.loader))
+            .map(|m| EnvironmentIssue {
+                mod_id: m.id.clone(),
+                mod_name: m.name.clone(),
+                kind: EnvironmentIssueKind::Loader {
+                    mod_loader: m.loader.clone(),
+                    instance_loader: server_loader.clone(),
+                },
+            })
+            .collect();
+        for issue in &unloadable {
+            result.decide(
+                &issue.mod_id,
+                DecisionOutcome::Blocked,
+                DecisionCause::Environment(issue.clone()),
+            );
+        }
+        result.unloadable_additions = unloadable;
+    }
+
+    // Declared incompatibilities within each instance
+    let client_unique = client_mods
+        .iter()
+        .filter(|m| std::ptr::eq(client_map[m.id.as_str()], *m));
+    let server_unique = server_mods
+        .iter()
+        .filter(|m| std::ptr::eq(server_map[m.id.as_str()], *m));
+    result.client_conflicts = find_conflicts(client_unique);
+    result.server_conflicts = find_conflicts(server_unique.clone());
+
+    // Conflicts the server would have once the missing mods are added,
+    // outdated ones updated and extra ones removed
+    let added: HashSet<&str> = result
+        .missing_on_server
+        .iter()
+        .map(|m| m.id.as_str())
+        .collect();
+    let replaced: HashSet<&str> = result
+        .missing_on_client
+        .iter()
+        .map(|m| m.id.as_str())
+        .chain(result.version_mismatches.iter().map(|m| m.mod_id.as_str()))
+        .collect();
+    let after_sync = server_unique
+        .filter(|m| !replaced.contains(m.id.as_str()))
+        .chain(
+            result
+                .version_mismatches
+                .iter()
+                .map(|m| client_map[m.mod_id.as_str()]),
+        )
+        .chain(&result.missing_on_server);
+    let blocked: Vec<ModConflict> = find_conflicts(after_sync)
+        .into_iter()
+        .filter(|c| {
+            c.is_hard()
+                && (added.contains(c.mod_id.as_str()) || added.contains(c.conflicts_with.as_str()))
+        })
+        .collect();
+
+    for conflict in &blocked {
+        for mod_id in [&conflict.mod_id, &conflict.conflicts_with] {
+            if result.missing_on_server.iter().any(|m| &m.id == mod_id) {
+                result.decide(
+                    mod_id,
+                    DecisionOutcome::Blocked,
+                    DecisionCause::Conflict(conflict.clone()),
+                );
+            }
+        }
+    }
+    result.blocked_additions = blocked;
+
+    let hard_conflicts = result
+        .client_conflicts
+        .iter()
+        .chain(&result.server_conflicts)
+        .chain(&result.blocked_additions)
+        .any(ModConflict::is_hard);
+    if hard_conflicts {
+        result.is_compatible = false;
     }
 
     Ok(result)
 }
+
+fn rule_cause(rule: &CompatRule) -> DecisionCause {
+    if let RuleSource::GlobalIgnoreList | RuleSource::ServerIgnoreList = rule.source {
+        return DecisionCause::IgnoreList {
+            source: rule.source.clone(),
+        };
+    }
+    DecisionCause::Rule {
+        pattern: rule.mod_id.clone(),
+        rule_type: rule.rule_type.clone(),
+        source: rule.source.clone(),
+        reason: rule.reason.clone(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use mc_link_core::ModLoader;
+    use std::path::PathBuf;
+
+    fn client_mod(id: &str) -> ModInfo {
+        ModInfo {
+            version: Some("1.0.0".to_string()),
+            side: ModSide::Both,
+            loader: ModLoader::NeoForge,
+            ..ModInfo::new(id, format!("{id}.jar"))
+        }
+    }
+
+    #[test]
+    fn rules_match_exact_glob_and_regex_patterns() {
+        let config = CompatConfig {
+            custom_rules: vec![
+                CompatRule::new("optifine", RuleType::AlwaysIgnore, ""),
+                CompatRule::new("journeymap*", RuleType::AlwaysIgnore, "")
+                    .with_pattern(PatternKind::Glob),
+                CompatRule::new(".*-client$", RuleType::ClientOnly, "")
+                    .with_pattern(PatternKind::Regex),
+            ],
+            ..Default::default()
+        };
+        let client = [
+            client_mod("optifine"),
+            client_mod("journeymap-api"),
+            client_mod("sodium-client"),
+            client_mod("create"),
+        ];
+
+        let result = check_compatibility(&client, &[], &config).unwrap();
+        assert_eq!(
+            result.ignored_mods,
+            ["optifine", "journeymap-api", "sodium-client"]
+        );
+        assert_eq!(result.missing_on_server.len(), 1);
+        assert_eq!(result.missing_on_server[0].id, "create");
+    }
+
+    #[test]
+    fn warn_rules_do_not_affect_compatibility() {
+        let config = CompatConfig {
+            custom_rules: vec![CompatRule::new("create", RuleType::Warn, "desyncs")],
+            ..Default::default()
+        };
+        let mods = [client_mod("create")];
+
+        let result = check_compatibility(&mods, &mods, &config).unwrap();
+        assert!(result.is_compatible);
+        assert_eq!(result.warnings.len(), 1);
+        assert_eq!(result.warnings[0].reason, "desyncs");
+    }
+
+    #[test]
+    fn decisions_record_rule_provenance() {
+        let config = CompatConfig {
+            custom_rules: vec![
+                CompatRule::new("optifine", RuleType::AlwaysIgnore, "client only").with_source(
+                    RuleSource::Profile {
+                        name: "default".to_string(),
+                        index: 1,
+                    },
+                ),
+            ],
+            ..Default::default()
+        };
+        let client = [client_mod("optifine"), client_mod("create")];
+
+        let result = check_compatibility(&client, &[], &config).unwrap();
+        let optifine: Vec<_> = result.explain("optifine").collect();
+        assert_eq!(optifine.len(), 1);
+        assert_eq!(optifine[0].outcome, DecisionOutcome::Ignored);
+        assert!(matches!(
+            &optifine[0].cause,
+            DecisionCause::Rule {
+                source: RuleSource::Profile { index: 1, .. },
+                ..
+            }
+        ));
+
+        let create: Vec<_> = result.explain("create").collect();
+        assert_eq!(create[0].outcome, DecisionOutcome::MissingOnServer);
+        assert!(matches!(create[0].cause, DecisionCause::Absent));
+    }
+
+    #[test]
+    fn decisions_name_the_ignore_list() {
+        let config = CompatConfig {
+            ignore_list: HashSet::from(["create".to_string()]),
+            custom_rules: vec![
+                CompatRule::new("optifine", RuleType::AlwaysIgnore, "")
+                    .with_source(RuleSource::ServerIgnoreList),
+                CompatRule::new("jei", RuleType::AlwaysIgnore, "")
+                    .with_source(RuleSource::GlobalIgnoreList),
+            ],
+            ..Default::default()
+        };
+        let client = [
+            client_mod("optifine"),
+            client_mod("jei"),
+            client_mod("create"),
+        ];
+
+        let result = check_compatibility(&client, &[], &config).unwrap();
+        let source = |mod_id| match &result.explain(mod_id).next().unwrap().cause {
+            DecisionCause::IgnoreList { source } => source.clone(),
+            cause => panic!("expected an ignore list cause, got {cause:?}"),
+        };
+        assert_eq!(source("optifine"), RuleSource::ServerIgnoreList);
+        assert_eq!(source("jei"), RuleSource::GlobalIgnoreList);
+        assert_eq!(source("create"), RuleSource::Config);
+    }
+
+    #[test]
+    fn parses_textual_rules() {
+        let rule: CompatRule = "client-only *-client # UI only".parse().unwrap();
+        assert_eq!(rule.rule_type, RuleType::ClientOnly);
+        assert_eq!(rule.pattern, PatternKind::Glob);
+        assert_eq!(rule.mod_id, "*-client");
+        assert_eq!(rule.reason, "UI only");
+
+        let rule: CompatRule = "ignore /^journeymap.*/".parse().unwrap();
+        assert_eq!(rule.pattern, PatternKind::Regex);
+        assert_eq!(rule.mod_id, "^journeymap.*");
+
+        let rule: CompatRule = "warn re:create".parse().unwrap();
+        assert_eq!(
+            (rule.rule_type, rule.pattern),
+            (RuleType::Warn, PatternKind::Regex)
+        );
+
+        let rule: CompatRule = "require jei".parse().unwrap();
+        assert_eq!(rule.pattern, PatternKind::Exact);
+
+        let rule: CompatRule = "ignore /^a#b$/ # not a reason yet".parse().unwrap();
+        assert_eq!(rule.mod_id, "^a#b$");
+        assert_eq!(rule.reason, "not a reason yet");
+
+        let rule: CompatRule = r"ignore /^a \#b$/".parse().unwrap();
+        assert_eq!(rule.mod_id, "^a #b$");
+        assert_eq!(rule.reason, "");
+
+        assert!("remove jei".parse::<CompatRule>().is_err());
+        assert!("ignore".parse::<CompatRule>().is_err());
+    }
+
+    #[test]
+    fn tolerate_rules_accept_drift_within_tolerance() {
+        let version = |id: &str, v: &str| ModInfo {
+            version: Some(v.to_string()),
+            ..client_mod(id)
+        };
+        let config = CompatConfig {
+            custom_rules: vec![
+                CompatRule::new("jei", RuleType::Tolerate(VersionTolerance::SameMinor), ""),
+                CompatRule::new(
+                    "create",
+                    RuleType::Tolerate(VersionTolerance::SameMinor),
+                    "",
+                ),
+                "tolerate sodium >=0.5 <0.7".parse().unwrap(),
+                "tolerate ftb-*".parse().unwrap(),
+            ],
+            ..Default::default()
+        };
+        let client = [
+            version("jei", "19.21.0"),
+            version("create", "6.0.0"),
+            version("sodium", "0.6.1"),
+            version("ftb-library", "2001.2.4"),
+        ];
+        let server = [
+            version("jei", "19.21.2"),
+            version("create", "6.1.0"),
+            version("sodium", "0.5.8"),
+            version("ftb-library", "2001.2.9"),
+        ];
+
+        let result = check_compatibility(&client, &server, &config).unwrap();
+        let mismatched: Vec<_> = result
+            .version_mismatches
+            .iter()
+            .map(|m| m.mod_id.as_str())
+            .collect();
+        assert_eq!(mismatched, ["create"]);
+        let jei: Vec<_> = result.explain("jei").collect();
+        assert_eq!(jei[0].outcome, DecisionOutcome::Compatible);
+        assert!(matches!(jei[0].cause, DecisionCause::Rule { .. }));
+
+        let rule: CompatRule = "tolerate jei any # protocol stable".parse().unwrap();
+        assert_eq!(rule.rule_type, RuleType::Tolerate(VersionTolerance::Any));
+        assert_eq!(rule.reason, "protocol stable");
+    }
+
+    #[test]
+    fn warn_rules_do_not_cancel_tolerance() {
+        let version = |v: &str| ModInfo {
+            version: Some(v.to_string()),
+            ..client_mod("jei")
+        };
+        let config = CompatConfig {
+            custom_rules: vec![
+                "warn jei # noisy".parse().unwrap(),
+                "tolerate jei minor".parse().unwrap(),
+            ],
+            ..Default::default()
+        };
+
+        let result =
+            check_compatibility(&[version("19.21.0")], &[version("19.21.2")], &config).unwrap();
+        assert!(result.version_mismatches.is_empty());
+        assert_eq!(result.warnings.len(), 1);
+        let outcomes: Vec<_> = result.explain("jei").map(|d| &d.outcome).collect();
+        assert_eq!(
+            outcomes,
+            [&DecisionOutcome::Warned, &DecisionOutcome::Compatible]
+        );
+    }
+
+    #[test]
+    fn warns_before_adding_mod_with_unreliable_side_guess() {
+        use mc_link_core::{SideConfidence, SideGuess};
+
+        let guessed = ModInfo {
+            side_guess: Some(SideGuess {
+                side: ModSide::Both,
+                confidence: SideConfidence::Low,
+                evidence: Vec::new(),
+            }),
+            ..client_mod("mystery")
+        };
+        let confident = ModInfo {
+            side_guess: Some(SideGuess {
+                side: ModSide::Both,
+                confidence: SideConfidence::High,
+                evidence: Vec::new(),
+            }),
+            ..client_mod("create")
+        };
+
+        let result =
+            check_compatibility(&[guessed, confident], &[], &CompatConfig::default()).unwrap();
+        assert_eq!(result.missing_on_server.len(), 2);
+        assert_eq!(result.warnings.len(), 1);
+        assert_eq!(result.warnings[0].mod_id, "mystery");
+        let outcomes: Vec<_> = result.explain("mystery").map(|d| &d.outcome).collect();
+        assert_eq!(
+            outcomes,
+            [&DecisionOutcome::Warned, &DecisionOutcome::MissingOnServer]
+        );
+    }
+
+    #[test]
+    fn only_server_duplicates_affect_compatibility() {
+        let mut stale = client_mod("create");
+        stale.version = Some("0.5.0".to_string());
+        stale.file_path = PathBuf::from("create-0.5.0.jar");
+        let duplicated = [client_mod("create"), stale];
+        let single = [client_mod("create")];
+
+        let result = check_compatibility(&duplicated, &single, &CompatConfig::default()).unwrap();
+        assert!(result.is_compatible);
+        assert_eq!(result.client_duplicates.len(), 1);
+        assert_eq!(result.warnings.len(), 1);
+        assert_eq!(result.warnings[0].mod_id, "create");
+
+        let result = check_compatibility(&single, &duplicated, &CompatConfig::default()).unwrap();
+        assert!(!result.is_compatible);
+        assert_eq!(result.server_duplicates.len(), 1);
+    }
+
+    #[test]
+    fn aliases_pair_mods_with_different_ids() {
+        let config = CompatConfig {
+            aliases: HashMap::from([("sodium".to_string(), "embeddium".to_string())]),
+            ..Default::default()
+        };
+        let client = [client_mod("embeddium")];
+        let server = [client_mod("sodium")];
+
+        let result = check_compatibility(&client, &server, &config).unwrap();
+        assert!(result.is_compatible);
+        assert_eq!(result.explain("embeddium").count(), 1);
+    }
+
+    #[test]
+    fn blocks_additions_that_introduce_a_conflict() {
+        use mc_link_core::{DependencyKind, ModDependency};
+
+        let mut sodium = client_mod("sodium");
+        sodium.dependencies.push(ModDependency {
+            mod_id: "optifabric".to_string(),
+            version_range: None,
+            kind: DependencyKind::Incompatible,
+        });
+        let client = [sodium, client_mod("create")];
+        let server = [client_mod("optifabric"), client_mod("create")];
+        let config = CompatConfig {
+            custom_rules: vec![CompatRule::new("optifabric", RuleType::ServerOnly, "")],
+            ..Default::default()
+        };
+
+        let result = check_compatibility(&client, &server, &config).unwrap();
+        assert!(!result.is_compatible);
+        assert!(result.client_conflicts.is_empty());
+        assert_eq!(result.blocked_additions.len(), 1);
+        assert!(result.blocked_additions[0].involves("optifabric"));
+        let sodium: Vec<_> = result.explain("sodium").collect();
+        assert_eq!(sodium.last().unwrap().outcome, DecisionOutcome::Blocked);
+    }
+
+    #[test]
+    fn detects_loader_mismatch_between_instances() {
+        let fabric = |id: &str| ModInfo {
+            loader: ModLoader::Fabric,
+            ..client_mod(id)
+        };
+        let client = [fabric("sodium"), fabric("lithium"), client_mod("create")];
+        let server = [client_mod("create")];
+        let config = CompatConfig {
+            server_environment: GameEnvironment::new(
+                Some("1.21.1".to_string()),
+                Some(ModLoader::NeoForge),
+            ),
+            ..Default::default()
+        };
+
+        let result = check_compatibility(&client, &server, &config).unwrap();
+        assert_eq!(
+            result.loader_mismatch,
+            Some(LoaderMismatch {
+                client_loader: ModLoader::Fabric,
+                server_loader: ModLoader::NeoForge,
+            })
+        );
+        let unloadable: Vec<_> = result
+            .unloadable_additions
+            .iter()
+            .map(|issue| issue.mod_id.as_str())
+            .collect();
+        assert_eq!(unloadable, ["sodium", "lithium"]);
+    }
+
+    #[test]
+    fn invalid_regex_is_an_error() {
+        let config = CompatConfig {
+            custom_rules: vec![
+                CompatRule::new("(", RuleType::AlwaysIgnore, "").with_pattern(PatternKind::Regex),
+            ],
+            ..Default::default()
+        };
+        assert!(matches!(
+            check_compatibility(&[], &[], &config),
+            Err(CompatError::InvalidRule { .. })
+        ));
+    }
+}
@@ -0,0 +1,522 @@
+//! Heuristic side detection for mods that do not declare one.
+//!
+//! The classifier looks at two kinds of evidence: which arrays of the mod's
+//! mixin configs are populated, and which classes reference client code
+//! (`net/minecraft/client`, `com/mojang/blaze3d`) or are marked client-only
+//! as a whole by `@OnlyIn(Dist.CLIENT)` or `@Environment(EnvType.CLIENT)`.
+//! Markers on single fields or methods do not make a class client-only.
+
+use mc_link_core::{ModSide, SideConfidence, SideGuess};
+use std::io::{Read, Seek};
+use tracing::trace;
+use zip::ZipArchive;
+
+/// Annotation descriptors that mark a class or member as belonging to one side.
+///
+/// NeoForge's `@Mod` is left out: it sits on every main class, and those
+/// often reference `Dist.CLIENT` without being client-only.
+const SIDE_MARKERS: [&str; 3] = [
+    "Lnet/minecraftforge/api/distmarker/OnlyIn;",
+    "Lnet/neoforged/api/distmarker/OnlyIn;",
+    "Lnet/fabricmc/api/Environment;",
+];
+
+/// Package prefixes that only exist on the client.
+const CLIENT_PACKAGES: [&str; 2] = ["net/minecraft/client/", "com/mojang/blaze3d/"];
+
+/// Share of Minecraft-referencing classes above which a mod looks client-only.
+const CLIENT_SHARE: f64 = 0.9;
+
+/// Share of Minecraft-referencing classes below which a mod clearly has common code.
+const COMMON_SHARE: f64 = 0.5;
+
+/// What one kind of evidence points to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Signal {
+    Client,
+    Both,
+    Ambiguous,
+    Absent,
+}
+
+/// Counts gathered from a mod's classes and mixin configs.
+#[derive(Debug, Default)]
+struct SideEvidence {
+    client_classes: usize,
+    common_classes: usize,
+    client_mixins: usize,
+    common_mixins: usize,
+}
+
+impl SideEvidence {
+    fn class_signal(&self) -> Signal {
+        let total = self.client_classes + self.common_classes;
+        if total == 0 {
+            return Signal::Absent;
+        }
+        let share = self.client_classes as f64 / total as f64;
+        if share >= CLIENT_SHARE {
+            Signal::Client
+        } else if share <= COMMON_SHARE {
+            Signal::Both
+        } else {
+            Signal::Ambiguous
+        }
+    }
+
+    fn mixin_signal(&self) -> Signal {
+        match (self.client_mixins, self.common_mixins) {
+            (0, 0) => Signal::Absent,
+            (_, 0) => Signal::Client,
+            _ => Signal::Both,
+        }
+    }
+
+    fn describe(&self) -> Vec<String> {
+        let mut evidence = Vec::new();
+        let total = self.client_classes + self.common_classes;
+        if total > 0 {
+            evidence.push(format!(
+                "{} of {} Minecraft-referencing classes are client-only",
+                self.client_classes, total
+            ));
+        }
+        if self.client_mixins + self.common_mixins > 0 {
+            evidence.push(format!(
+                "{} client and {} common/server mixins",
+                self.client_mixins, self.common_mixins
+            ));
+        }
+        evidence
+    }
+}
+
+/// Guesses which side a mod runs on from the contents of its jar.
+pub fn classify_side<R: Read + Seek>(archive: &mut ZipArchive<R>) -> SideGuess {
+    let mut evidence = SideEvidence::default();
+
+    for i in 0..archive.len() {
+        let Ok(mut entry) = archive.by_index(i) else {
+            continue;
+        };
+        let name = entry.name().to_string();
+        let mut bytes = Vec::new();
+        if name.ends_with(".class") {
+            if entry.read_to_end(&mut bytes).is_ok() {
+                record_class(&bytes, &mut evidence);
+            }
+        } else if is_mixin_config(&name) && entry.read_to_end(&mut bytes).is_ok() {
+            record_mixin_config(&bytes, &mut evidence);
+        }
+    }
+    trace!(?evidence, "Collected side evidence");
+
+    let (side, confidence) = match (evidence.class_signal(), evidence.mixin_signal()) {
+        (Signal::Client, Signal::Client) => (ModSide::Client, SideConfidence::High),
+        (Signal::Client, Signal::Absent) | (Signal::Absent, Signal::Client) => {
+            (ModSide::Client, SideConfidence::Medium)
+        }
+        (Signal::Ambiguous, Signal::Client) => (ModSide::Client, SideConfidence::Low),
+        (Signal::Both, Signal::Both) => (ModSide::Both, SideConfidence::High),
+        (Signal::Both, Signal::Absent | Signal::Ambiguous) | (Signal::Ambiguous, Signal::Both) => {
+            (ModSide::Both, SideConfidence::Medium)
+        }
+        // No Minecraft code at all, e.g. a library
+        (Signal::Absent, Signal::Absent) => (ModSide::Both, SideConfidence::Medium),
+        // Contradicting or weak evidence
+        _ => (ModSide::Both, SideConfidence::Low),
+    };
+
+    SideGuess {
+        side,
+        confidence,
+        evidence: evidence.describe(),
+    }
+}
+
+fn is_mixin_config(name: &str) -> bool {
+    !name.contains('/') && name.ends_with(".json") && name.contains("mixins")
+}
+
+fn record_mixin_config(bytes: &[u8], evidence: &mut SideEvidence) {
+    let Ok(config) = serde_json::from_slice::<serde_json::Value>(bytes) else {
+        return;
+    };
+    let count = |key: &str| {
+        config
+            .get(key)
+            .and_then(|v| v.as_array())
+            .map_or(0, Vec::len)
+    };
+    evidence.client_mixins += count("client");
+    evidence.common_mixins += count("mixins") + count("server");
+}
+
+fn record_class(bytes: &[u8], evidence: &mut SideEvidence) {
+    let Some(class) = ClassFile::parse(bytes) else {
+        return;
+    };
+    let references = |prefix: &str| class.strings().any(|s| s.contains(prefix));
+
+    let client = class.marked_client() || CLIENT_PACKAGES.iter().any(|package| references(package));
+    if client {
+        evidence.client_classes += 1;
+    } else if references("net/minecraft/") {
+        evidence.common_classes += 1;
+    }
+}
+
+/// The parts of a class file the classifier looks at.
+struct ClassFile<'a> {
+    /// UTF-8 constant pool entries, by constant pool index
+    pool: Vec<Option<&'a str>>,
+    /// Annotations on the class itself, leaving out those on its members
+    annotations: Vec<Annotation<'a>>,
+}
+
+/// An annotation and the enum constants among its values.
+struct Annotation<'a> {
+    descriptor: &'a str,
+    enum_constants: Vec<&'a str>,
+}
+
+impl<'a> ClassFile<'a> {
+    /// Parses the constant pool and class annotations of a class file.
+    ///
+    /// Returns `None` if the constant pool is malformed. A class whose
+    /// members or attributes cannot be read is returned without annotations.
+    fn parse(bytes: &'a [u8]) -> Option<Self> {
+        let mut reader = Reader { bytes, pos: 0 };
+        if reader.take(4)? != [0xCA, 0xFE, 0xBA, 0xBE] {
+            return None;
+        }
+        // Minor and major version
+        reader.skip(4)?;
+        let pool = read_constant_pool(&mut reader)?;
+        let annotations = read_class_annotations(&mut reader, &pool).unwrap_or_default();
+        Some(Self { pool, annotations })
+    }
+
+    fn strings(&self) -> impl Iterator<Item = &'a str> + '_ {
+        self.pool.iter().flatten().copied()
+    }
+
+    /// Whether the class as a whole is marked client-only, as opposed to
+    /// one of its fields or methods.
+    fn marked_client(&self) -> bool {
+        self.annotations.iter().any(|annotation| {
+            SIDE_MARKERS.contains(&annotation.descriptor)
+                && annotation.enum_constants.contains(&"CLIENT")
+        })
+    }
+}
+
+/// Reads big-endian values from a class file.
+struct Reader<'a> {
+    bytes: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn take(&mut self, len: usize) -> Option<&'a [u8]> {
+        let data = self.bytes.get(self.pos..self.pos.checked_add(len)?)?;
+        self.pos += len;
+        Some(data)
+    }
+
+    fn skip(&mut self, len: usize) -> Option<()> {
+        self.take(len).map(|_| ())
+    }
+
+    fn u8(&mut self) -> Option<u8> {
+        self.take(1).map(|data| data[0])
+    }
+
+    fn u16(&mut self) -> Option<u16> {
+        Some(u16::from_be_bytes(self.take(2)?.try_into().ok()?))
+    }
+
+    fn u32(&mut self) -> Option<u32> {
+        Some(u32::from_be_bytes(self.take(4)?.try_into().ok()?))
+    }
+}
+
+/// Reads the constant pool, keeping its UTF-8 entries.
+fn read_constant_pool<'a>(reader: &mut Reader<'a>) -> Option<Vec<Option<&'a str>>> {
+    let count = usize::from(reader.u16()?);
+    let mut pool = vec![None; count.max(1)];
+    let mut index = 1;
+    while index < count {
+        match reader.u8()? {
+            // Utf8
+            1 => {
+                let len = usize::from(reader.u16()?);
+                pool[index] = std::str::from_utf8(reader.take(len)?).ok();
+            }
+            // Class, String, MethodType, Module, Package
+            7 | 8 | 16 | 19 | 20 => reader.skip(2)?,
+            // MethodHandle
+            15 => reader.skip(3)?,
+            // Integer, Float, refs, NameAndType, Dynamic, InvokeDynamic
+            3 | 4 | 9 | 10 | 11 | 12 | 17 | 18 => reader.skip(4)?,
+            // Long and Double take two slots
+            5 | 6 => {
+                reader.skip(8)?;
+                index += 1;
+            }
+            _ => return None,
+        }
+        index += 1;
+    }
+    Some(pool)
+}
+
+/// Skips to the class attributes and reads the annotations among them.
+///
+/// `@OnlyIn` is retained at runtime and Fabric's `@Environment` only in the
+/// class file, so both visible and invisible annotations are read.
+fn read_class_annotations<'a>(
+    reader: &mut Reader<'a>,
+    pool: &[Option<&'a str>],
+) -> Option<Vec<Annotation<'a>>> {
+    // Access flags, this class and super class
+    reader.skip(6)?;
+    let interfaces = usize::from(reader.u16()?);
+    reader.skip(2 * interfaces)?;
+    // Fields, then methods, along with any annotations on them
+    for _ in 0..2 {
+        for _ in 0..reader.u16()? {
+            // Access flags, name and descriptor
+            reader.skip(6)?;
+            for _ in 0..reader.u16()? {
+                reader.skip(2)?;
+                let len = reader.u32()?;
+                reader.skip(usize::try_from(len).ok()?)?;
+            }
+        }
+    }
+
+    let mut annotations = Vec::new();
+    for _ in 0..reader.u16()? {
+        let name = constant(pool, reader.u16()?);
+        let len = usize::try_from(reader.u32()?).ok()?;
+        let mut body = Reader {
+            bytes: reader.take(len)?,
+            pos: 0,
+        };
+        if matches!(
+            name,
+            Some("RuntimeVisibleAnnotations" | "RuntimeInvisibleAnnotations")
+        ) {
+            for _ in 0..body.u16()? {
+                annotations.push(read_annotation(&mut body, pool)?);
+            }
+        }
+    }
+    Some(annotations)
+}
+
+fn read_annotation<'a>(
+    reader: &mut Reader<'a>,
+    pool: &[Option<&'a str>],
+) -> Option<Annotation<'a>> {
+    let descriptor = constant(pool, reader.u16()?)?;
+    let mut enum_constants = Vec::new();
+    for _ in 0..reader.u16()? {
+        // Element name
+        reader.skip(2)?;
+        read_element_value(reader, pool, &mut enum_constants)?;
+    }
+    Some(Annotation {
+        descriptor,
+        enum_constants,
+    })
+}
+
+/// Reads an annotation element value, collecting the enum constants in it.
+fn read_element_value<'a>(
+    reader: &mut Reader<'a>,
+    pool: &[Option<&'a str>],
+    enum_constants: &mut Vec<&'a str>,
+) -> Option<()> {
+    match reader.u8()? {
+        b'B' | b'C' | b'D' | b'F' | b'I' | b'J' | b'S' | b'Z' | b's' | b'c' => reader.skip(2),
+        b'e' => {
+            // Enum type, then constant name
+            reader.skip(2)?;
+            enum_constants.push(constant(pool, reader.u16()?)?);
+            Some(())
+        }
+        b'@' => read_annotation(reader, pool).map(|_| ()),
+        b'[' => {
+            for _ in 0..reader.u16()? {
+                read_element_value(reader, pool, enum_constants)?;
+            }
+            Some(())
+        }
+        _ => None,
+    }
+}
+
+fn constant<'a>(pool: &[Option<&'a str>], index: u16) -> Option<&'a str> {
+    pool.get(usize::from(index)).copied().flatten()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::{Cursor, Write};
+    use zip::write::{SimpleFileOptions, ZipWriter};
+
+    /// Where a test class carries `@OnlyIn(Dist.CLIENT)`.
+    #[derive(PartialEq)]
+    enum Marker {
+        None,
+        Class,
+        Method,
+    }
+
+    /// Builds a class file whose constant pool holds the given strings.
+    fn class_file(strings: &[&str]) -> Vec<u8> {
+        annotated_class_file(strings, Marker::None)
+    }
+
+    /// Builds a class file whose constant pool holds the given strings, with
+    /// `@OnlyIn(Dist.CLIENT)` on the class or on a method.
+    fn annotated_class_file(strings: &[&str], marker: Marker) -> Vec<u8> {
+        let mut pool = strings.to_vec();
+        let base = pool.len() as u16 + 1;
+        pool.extend([
+            "RuntimeVisibleAnnotations",
+            "Lnet/neoforged/api/distmarker/OnlyIn;",
+            "value",
+            "Lnet/neoforged/api/distmarker/Dist;",
+            "CLIENT",
+            "render",
+            "()V",
+        ]);
+        let index = |offset: u16| (base + offset).to_be_bytes();
+
+        let mut bytes = vec![0xCA, 0xFE, 0xBA, 0xBE, 0, 0, 0, 65];
+        bytes.extend_from_slice(&(pool.len() as u16 + 1).to_be_bytes());
+        for s in &pool {
+            bytes.push(1);
+            bytes.extend_from_slice(&(s.len() as u16).to_be_bytes());
+            bytes.extend_from_slice(s.as_bytes());
+        }
+
+        let mut attribute = Vec::new();
+        attribute.extend_from_slice(&index(0));
+        attribute.extend_from_slice(&13u32.to_be_bytes());
+        attribute.extend_from_slice(&[0, 1]);
+        attribute.extend_from_slice(&index(1));
+        attribute.extend_from_slice(&[0, 1]);
+        attribute.extend_from_slice(&index(2));
+        attribute.push(b'e');
+        attribute.extend_from_slice(&index(3));
+        attribute.extend_from_slice(&index(4));
+
+        // Access flags, this class, super class, interfaces and fields
+        bytes.extend_from_slice(&[0, 0x21, 0, 0, 0, 0, 0, 0, 0, 0]);
+        if marker == Marker::Method {
+            bytes.extend_from_slice(&[0, 1, 0, 1]);
+            bytes.extend_from_slice(&index(5));
+            bytes.extend_from_slice(&index(6));
+            bytes.extend_from_slice(&[0, 1]);
+            bytes.extend_from_slice(&attribute);
+        } else {
+            bytes.extend_from_slice(&[0, 0]);
+        }
+        if marker == Marker::Class {
+            bytes.extend_from_slice(&[0, 1]);
+            bytes.extend_from_slice(&attribute);
+        } else {
+            bytes.extend_from_slice(&[0, 0]);
+        }
+        bytes
+    }
+
+    fn jar(entries: &[(&str, Vec<u8>)]) -> ZipArchive<Cursor<Vec<u8>>> {
+        let mut writer = ZipWriter::new(Cursor::new(Vec::new()));
+        for (name, data) in entries {
+            writer
+                .start_file(*name, SimpleFileOptions::default())
+                .unwrap();
+            writer.write_all(data).unwrap();
+        }
+        ZipArchive::new(writer.finish().unwrap()).unwrap()
+    }
+
+    #[test]
+    fn client_classes_and_mixins_are_client_only() {
+        let mut archive = jar(&[
+            (
+                "example/Hud.class",
+                class_file(&["net/minecraft/client/gui/GuiGraphics"]),
+            ),
+            (
+                "example/Config.class",
+                annotated_class_file(&["net/minecraft/world/item/Item"], Marker::Class),
+            ),
+            (
+                "example.mixins.json",
+                br#"{"package": "example.mixin", "client": ["HudMixin"]}"#.to_vec(),
+            ),
+        ]);
+
+        let guess = classify_side(&mut archive);
+        assert_eq!(guess.side, ModSide::Client);
+        assert_eq!(guess.confidence, SideConfidence::High);
+    }
+
+    #[test]
+    fn common_code_is_both_sides() {
+        let mut archive = jar(&[
+            (
+                "example/Block.class",
+                class_file(&["net/minecraft/world/level/block/Block"]),
+            ),
+            (
+                "example/Renderer.class",
+                class_file(&["net/minecraft/client/renderer/RenderType"]),
+            ),
+            (
+                "example.mixins.json",
+                br#"{"mixins": ["LevelMixin"], "client": ["RendererMixin"]}"#.to_vec(),
+            ),
+        ]);
+
+        let guess = classify_side(&mut archive);
+        assert_eq!(guess.side, ModSide::Both);
+        assert_eq!(guess.confidence, SideConfidence::High);
+    }
+
+    #[test]
+    fn mod_main_class_referencing_client_dist_is_common() {
+        let mut archive = jar(&[(
+            "example/ExampleMod.class",
+            class_file(&[
+                "Lnet/neoforged/fml/common/Mod;",
+                "Lnet/neoforged/api/distmarker/Dist;",
+                "CLIENT",
+                "net/minecraft/world/item/Item",
+            ]),
+        )]);
+
+        let guess = classify_side(&mut archive);
+        assert_eq!(guess.side, ModSide::Both);
+        assert_eq!(guess.confidence, SideConfidence::Medium);
+    }
+
+    #[test]
+    fn client_marker_on_a_method_leaves_the_class_common() {
+        let mut archive = jar(&[(
+            "example/Item.class",
+            annotated_class_file(&["net/minecraft/world/item/Item"], Marker::Method),
+        )]);
+
+        let guess = classify_side(&mut archive);
+        assert_eq!(guess.side, ModSide::Both);
+        assert_eq!(guess.confidence, SideConfidence::Medium);
+    }
+}
@@ -0,0 +1,777 @@
+//! Version parsing, ordering and range matching for mod version strings.
+//!
+//! Mod versions in the wild follow several conventions: Maven-style versions
+//! (used by Forge and NeoForge), semantic versions (used by Fabric) and
+//! Minecraft-prefixed formats such as `1.21.1-2.3.4`. [`ModVersion`] orders all
+//! of them using the Maven `ComparableVersion` algorithm, with semver build
+//! metadata (`+...`) excluded from precedence. [`VersionRange`] understands
+//! both Maven range specifications (`[1.20,1.21)`) and Fabric/semver predicates
+//! (`>=1.20.1 <1.21`, `~1.20`, `1.20.x`).
+
+use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
+use std::fmt;
+
+/// Qualifiers in ascending order of precedence; the empty string is a release.
+const QUALIFIERS: &[&str] = &["alpha", "beta", "milestone", "rc", "snapshot", "", "sp"];
+
+/// A single component of a parsed version.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Item {
+    /// Numeric component with leading zeros stripped
+    Int(String),
+    /// Textual qualifier (already lowercased and aliased)
+    Str(String),
+    /// Nested list started by a `-` or a digit/letter transition
+    List(Vec<Item>),
+}
+
+impl Item {
+    fn is_null(&self) -> bool {
+        match self {
+            Item::Int(digits) => digits.is_empty(),
+            Item::Str(qualifier) => qualifier.is_empty(),
+            Item::List(items) => items.is_empty(),
+        }
+    }
+}
+
+/// A parsed, orderable mod version.
+///
+/// Equality and ordering follow version precedence rather than the raw string,
+/// so `1.0` and `1.0.0` compare equal. The original string is kept for display.
+#[derive(Debug, Clone)]
+pub struct ModVersion {
+    raw: String,
+    build: Option<String>,
+    items: Vec<Item>,
+}
+
+impl ModVersion {
+    /// Parses a version string.
+    ///
+    /// Returns `None` for placeholders that carry no ordering information,
+    /// such as `unknown` or an unexpanded `${file.jarVersion}`.
+    pub fn parse(version: &str) -> Option<Self> {
+        let trimmed = version.trim();
+        if trimmed.is_empty() || trimmed.eq_ignore_ascii_case("unknown") || trimmed.contains("${") {
+            return None;
+        }
+
+        let (core, build) = match trimmed.split_once('+') {
+            Some((core, build)) => (core, Some(build.to_string())),
+            None => (trimmed, None),
+        };
+        let core = core
+            .strip_prefix('v')
+            .or_else(|| core.strip_prefix('V'))
+            .filter(|rest| rest.starts_with(|c: char| c.is_ascii_digit()))
+            .unwrap_or(core);
+
+        Some(Self {
+            raw: trimmed.to_string(),
+            build,
+            items: parse_items(core),
+        })
+    }
+
+    /// Returns the original version string.
+    pub fn as_str(&self) -> &str {
+        &self.raw
+    }
+
+    /// Returns the build metadata (the part after `+`), if any.
+    pub fn build_metadata(&self) -> Option<&str> {
+        self.build.as_deref()
+    }
+
+    /// Returns the leading numeric components, e.g. `[1, 21, 1]` for `1.21.1-beta`.
+    pub fn numeric_prefix(&self) -> Vec<u64> {
+        let core = self.raw.split('+').next().unwrap_or_default();
+        let core = core.trim_start_matches(['v', 'V']);
+
+        let mut numbers = Vec::new();
+        for part in core.split('.') {
+            let digits: String = part.chars().take_while(char::is_ascii_digit).collect();
+            match digits.parse() {
+                Ok(number) => numbers.push(number),
+                Err(_) => break,
+            }
+            if digits.len() != part.len() {
+                break;
+            }
+        }
+        numbers
+    }
+}
+
+impl PartialEq for ModVersion {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for ModVersion {}
+
+impl PartialOrd for ModVersion {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for ModVersion {
+    fn cmp(&self, other: &Self) -> Ordering {
+        cmp_lists(&self.items, &other.items)
+    }
+}
+
+impl fmt::Display for ModVersion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.raw)
+    }
+}
+
+/// Direction of a version change from a current version to a new one.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum VersionDirection {
+    /// The new version is newer than the current one
+    Upgrade,
+    /// The new version is older than the current one
+    Downgrade,
+    /// Same precedence but a different build (e.g. `1.0+mc1.20` vs `1.0+mc1.21`)
+    Sidegrade,
+    /// At least one version could not be ordered
+    Unknown,
+}
+
+impl fmt::Display for VersionDirection {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            VersionDirection::Upgrade => "upgrade",
+            VersionDirection::Downgrade => "downgrade",
+            VersionDirection::Sidegrade => "sidegrade",
+            VersionDirection::Unknown => "unknown",
+        };
+        f.write_str(name)
+    }
+}
+
+/// Compares two version strings by precedence.
+///
+/// Returns `None` when either version cannot be parsed.
+pub fn compare_versions(a: &str, b: &str) -> Option<Ordering> {
+    Some(ModVersion::parse(a)?.cmp(&ModVersion::parse(b)?))
+}
+
+/// Returns true if two version strings refer to the same release.
+///
+/// Versions are equivalent when they have equal precedence and the same build
+/// metadata, e.g. `1.0` and `1.0.0`. Unparseable versions fall back to string equality.
+pub fn versions_equivalent(a: &str, b: &str) -> bool {
+    match (ModVersion::parse(a), ModVersion::parse(b)) {
+        (Some(a), Some(b)) => a == b && a.build_metadata() == b.build_metadata(),
+        _ => a == b,
+    }
+}
+
+/// Returns true if two versions share their major and minor components.
+///
+/// A missing minor component counts as `0`, so `5` and `5.0.3` match. A
+/// leading Minecraft version is skipped, so `1.21.1-6.0.0` compares as `6.0.0`.
+/// Versions without a leading number only match when they are equivalent.
+pub fn same_major_minor(a: &str, b: &str) -> bool {
+    let major_minor = |version: &str| {
+        let numbers = ModVersion::parse(strip_minecraft_prefix(version))?.numeric_prefix();
+        let major = *numbers.first()?;
+        Some((major, numbers.get(1).copied().unwrap_or(0)))
+    };
+    match (major_minor(a), major_minor(b)) {
+        (Some(a), Some(b)) => a == b,
+        _ => versions_equivalent(a, b),
+    }
+}
+
+/// Returns the mod's own version from one led by a Minecraft version, as in
+/// `1.21.1-6.0.0` or `mc1.21.1-6.0.0`; other versions are returned unchanged.
+fn strip_minecraft_prefix(version: &str) -> &str {
+    let Some((prefix, rest)) = version.split_once('-') else {
+        return version;
+    };
+    let prefix = prefix.strip_prefix("mc").unwrap_or(prefix);
+    let is_minecraft = prefix.starts_with("1.")
+        && prefix
+            .split('.')
+            .all(|part| !part.is_empty() && part.bytes().all(|b| b.is_ascii_digit()));
+    let is_version = rest.starts_with(|c: char| c.is_ascii_digit()) && rest.contains('.');
+    if is_minecraft && is_version {
+        rest
+    } else {
+        version
+    }
+}
+
+/// Classifies the change from `current` to `new`.
+pub fn classify_version_change(current: &str, new: &str) -> VersionDirection {
+    match (ModVersion::parse(current), ModVersion::parse(new)) {
+        (Some(current), Some(new)) => match new.cmp(&current) {
+            Ordering::Greater => VersionDirection::Upgrade,
+            Ordering::Less => VersionDirection::Downgrade,
+            Ordering::Equal => VersionDirection::Sidegrade,
+        },
+        _ => VersionDirection::Unknown,
+    }
+}
+
+/// A single comparison against a version.
+#[derive(Debug, Clone)]
+enum Constraint {
+    Gt(ModVersion),
+    Gte(ModVersion),
+    Lt(ModVersion),
+    Lte(ModVersion),
+    Eq(ModVersion),
+}
+
+impl Constraint {
+    fn matches(&self, version: &ModVersion) -> bool {
+        match self {
+            Constraint::Gt(bound) => version > bound,
+            Constraint::Gte(bound) => version >= bound,
+            Constraint::Lt(bound) => version < bound,
+            Constraint::Lte(bound) => version <= bound,
+            Constraint::Eq(bound) => version == bound,
+        }
+    }
+}
+
+/// A version requirement, as declared in mod metadata.
+///
+/// A range is a disjunction of alternatives, each of which is a conjunction of
+/// constraints. An alternative without constraints matches every version.
+#[derive(Debug, Clone)]
+pub struct VersionRange {
+    raw: String,
+    alternatives: Vec<Vec<Constraint>>,
+}
+
+impl VersionRange {
+    /// Creates a range that matches any version.
+    pub fn any() -> Self {
+        Self {
+            raw: "*".to_string(),
+            alternatives: vec![Vec::new()],
+        }
+    }
+
+    /// Parses a range, detecting Maven syntax by a leading `[` or `(`.
+    pub fn parse(spec: &str) -> Option<Self> {
+        let trimmed = spec.trim();
+        if trimmed.starts_with('[') || trimmed.starts_with('(') {
+            Self::parse_maven(trimmed)
+        } else {
+            Self::parse_semver(trimmed)
+        }
+    }
+
+    /// Parses a Maven version range as used by Forge and NeoForge `mods.toml`.
+    ///
+    /// Supports `[a,b)`, `(a,]`, `[a]` and comma-separated unions. A bare
+    /// version is a soft requirement in Maven and matches any version, as does
+    /// an empty string or `*`.
+    pub fn parse_maven(spec: &str) -> Option<Self> {
+        let trimmed = spec.trim();
+        if trimmed.is_empty() || trimmed == "*" {
+            return Some(Self::any_with_raw(trimmed));
+        }
+        if !trimmed.starts_with('[') && !trimmed.starts_with('(') {
+            ModVersion::parse(trimmed)?;
+            return Some(Self::any_with_raw(trimmed));
+        }
+
+        let mut alternatives = Vec::new();
+        let mut rest = trimmed;
+        while !rest.is_empty() {
+            let lower_inclusive = match rest.as_bytes()[0] {
+                b'[' => true,
+                b'(' => false,
+                _ => return None,
+            };
+            let end = rest.find([']', ')'])?;
+            let upper_inclusive = rest.as_bytes()[end] == b']';
+            let body = &rest[1..end];
+
+            let mut constraints = Vec::new();
+            match body.split_once(',') {
+                None => {
+                    if !lower_inclusive || !upper_inclusive {
+                        return None;
+                    }
+                    constraints.push(Constraint::Eq(ModVersion::parse(body)?));
+                }
+                Some((lower, upper)) => {
+                    if !lower.trim().is_empty() {
+                        let bound = ModVersion::parse(lower)?;
+                        constraints.push(if lower_inclusive {
+                            Constraint::Gte(bound)
+                        } else {
+                            Constraint::Gt(bound)
+                        });
+                    }
+                    if !upper.trim().is_empty() {
+                        let bound = ModVersion::parse(upper)?;
+                        constraints.push(if upper_inclusive {
+                            Constraint::Lte(bound)
+                        } else {
+                            Constraint::Lt(bound)
+                        });
+                    }
+                }
+            }
+            alternatives.push(constraints);
+
+            rest = rest[end + 1..].trim_start();
+            if let Some(next) = rest.strip_prefix(',') {
+                rest = next.trim_start();
+            }
+        }
+
+        Some(Self {
+            raw: trimmed.to_string(),
+            alternatives,
+        })
+    }
+
+    /// Parses a semver-style predicate as used by Fabric `fabric.mod.json`.
+    ///
+    /// Supports `*`, comparison operators (`>=`, `>`, `<=`, `<`, `=`), `~` and
+    /// `^` prefixes, `x`/`*` wildcards (`1.20.x`), whitespace-separated
+    /// conjunctions and `||` disjunctions. A bare version must match exactly.
+    pub fn parse_semver(spec: &str) -> Option<Self> {
+        let trimmed = spec.trim();
+        let mut alternatives = Vec::new();
+
+        for alternative in trimmed.split("||") {
+            let mut constraints = Vec::new();
+            for predicate in alternative.split_whitespace() {
+                constraints.extend(parse_semver_predicate(predicate)?);
+            }
+            alternatives.push(constraints);
+        }
+
+        Some(Self {
+            raw: trimmed.to_string(),
+            alternatives,
+        })
+    }
+
+    /// Combines several ranges into one that matches if any of them matches.
+    ///
+    /// Fabric allows a dependency to list an array of alternative predicates.
+    pub fn union(ranges: impl IntoIterator<Item = VersionRange>) -> Option<Self> {
+        let ranges: Vec<_> = ranges.into_iter().collect();
+        if ranges.is_empty() {
+            return None;
+        }
+        Some(Self {
+            raw: ranges
+                .iter()
+                .map(|range| range.raw.as_str())
+                .collect::<Vec<_>>()
+                .join(" || "),
+            alternatives: ranges
+                .into_iter()
+                .flat_map(|range| range.alternatives)
+                .collect(),
+        })
+    }
+
+    /// Returns true if the version satisfies this range.
+    pub fn contains(&self, version: &ModVersion) -> bool {
+        self.alternatives.iter().any(|constraints| {
+            constraints
+                .iter()
+                .all(|constraint| constraint.matches(version))
+        })
+    }
+
+    /// Parses `version` and checks it against this range.
+    ///
+    /// Returns `None` when the version cannot be parsed.
+    pub fn contains_str(&self, version: &str) -> Option<bool> {
+        ModVersion::parse(version).map(|version| self.contains(&version))
+    }
+
+    /// Returns true if this range accepts every version.
+    pub fn is_any(&self) -> bool {
+        self.alternatives.iter().any(Vec::is_empty)
+    }
+
+    /// Returns the original range specification.
+    pub fn as_str(&self) -> &str {
+        &self.raw
+    }
+
+    fn any_with_raw(raw: &str) -> Self {
+        Self {
+            raw: if raw.is_empty() { "*" } else { raw }.to_string(),
+            alternatives: vec![Vec::new()],
+        }
+    }
+}
+
+impl fmt::Display for VersionRange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.raw)
+    }
+}
+
+fn parse_semver_predicate(predicate: &str) -> Option<Vec<Constraint>> {
+    if predicate == "*" || predicate.eq_ignore_ascii_case("x") {
+        return Some(Vec::new());
+    }
+
+    let operators = [">=", "<=", ">", "<", "=", "~", "^"];
+    let (operator, version) = operators
+        .iter()
+        .find_map(|op| predicate.strip_prefix(op).map(|rest| (*op, rest)))
+        .unwrap_or(("", predicate));
+
+    // Wildcards such as `1.20.x` or `1.*` expand to a half-open range.
+    let parts: Vec<&str> = version.split('.').collect();
+    if let Some(wildcard) = parts
+        .iter()
+        .position(|part| matches!(*part, "x" | "X" | "*"))
+    {
+        if wildcard == 0 {
+            return Some(Vec::new());
+        }
+        let fixed: Vec<u64> = parts[..wildcard]
+            .iter()
+            .map(|part| part.parse().ok())
+            .collect::<Option<_>>()?;
+        return Some(prefix_range(&fixed));
+    }
+
+    let parsed = ModVersion::parse(version)?;
+    let constraints = match operator {
+        ">=" => vec![Constraint::Gte(parsed)],
+        "<=" => vec![Constraint::Lte(parsed)],
+        ">" => vec![Constraint::Gt(parsed)],
+        "<" => vec![Constraint::Lt(parsed)],
+        "~" => {
+            let numbers = parsed.numeric_prefix();
+            let upper = match numbers.as_slice() {
+                [] => return Some(vec![Constraint::Gte(parsed)]),
+                [major] => format!("{}", major + 1),
+                [major, minor, ..] => format!("{}.{}", major, minor + 1),
+            };
+            vec![
+                Constraint::Gte(parsed),
+                Constraint::Lt(upper_bound(&upper)?),
+            ]
+        }
+        "^" => match parsed.numeric_prefix().first() {
+            Some(major) => {
+                let upper = upper_bound(&format!("{}", major + 1))?;
+                vec![Constraint::Gte(parsed), Constraint::Lt(upper)]
+            }
+            None => vec![Constraint::Gte(parsed)],
+        },
+        _ => vec![Constraint::Eq(parsed)],
+    };
+
+    Some(constraints)
+}
+
+/// Builds `>= prefix` and `< prefix with the last component bumped`.
+fn prefix_range(fixed: &[u64]) -> Vec<Constraint> {
+    let lower = fixed
+        .iter()
+        .map(u64::to_string)
+        .collect::<Vec<_>>()
+        .join(".");
+    let mut bumped = fixed.to_vec();
+    if let Some(last) = bumped.last_mut() {
+        *last += 1;
+    }
+    let upper = bumped
+        .iter()
+        .map(u64::to_string)
+        .collect::<Vec<_>>()
+        .join(".");
+
+    let mut constraints = Vec::new();
+    if let Some(lower) = upper_bound(&lower) {
+        constraints.push(Constraint::Gte(lower));
+    }
+    if let Some(upper) = upper_bound(&upper) {
+        constraints.push(Constraint::Lt(upper));
+    }
+    constraints
+}
+
+/// Creates an exclusive upper bound that also excludes pre-releases of `version`.
+///
+/// Without this `1.21-alpha` would satisfy `< 1.21`, which is rarely intended.
+fn upper_bound(version: &str) -> Option<ModVersion> {
+    ModVersion::parse(&format!("{}-alpha", version)).map(|mut bound| {
+        bound.raw = version.to_string();
+        bound
+    })
+}
+
+/// Parses a version core into Maven `ComparableVersion` items.
+fn parse_items(version: &str) -> Vec<Item> {
+    let version = version.to_lowercase();
+    let chars: Vec<char> = version.chars().collect();
+
+    // Stack of open lists; each `-` or digit/letter transition opens a new one.
+    let mut stack: Vec<Vec<Item>> = vec![Vec::new()];
+    let mut start = 0;
+    let mut is_digit = false;
+
+    let finish =
+        |stack: &mut Vec<Vec<Item>>, token: &str, is_digit: bool, followed_by_digit: bool| {
+            let item = if is_digit {
+                Item::Int(token.trim_start_matches('0').to_string())
+            } else {
+                Item::Str(canonical_qualifier(token, followed_by_digit))
+            };
+            stack
+                .last_mut()
+                .expect("version stack is never empty")
+                .push(item);
+        };
+
+    for (i, &c) in chars.iter().enumerate() {
+        if c == '.' || c == '-' || c == '_' {
+            if i == start {
+                stack
+                    .last_mut()
+                    .expect("version stack is never empty")
+                    .push(Item::Int(String::new()));
+            } else {
+                let token: String = chars[start..i].iter().collect();
+                finish(&mut stack, &token, is_digit, false);
+            }
+            start = i + 1;
+            if c != '.' {
+                stack.push(Vec::new());
+            }
+        } else if c.is_ascii_digit() {
+            if !is_digit && i > start {
+                let token: String = chars[start..i].iter().collect();
+                finish(&mut stack, &token, false, true);
+                start = i;
+                stack.push(Vec::new());
+            }
+            is_digit = true;
+        } else {
+            if is_digit && i > start {
+                let token: String = chars[start..i].iter().collect();
+                finish(&mut stack, &token, true, false);
+                start = i;
+                stack.push(Vec::new());
+            }
+            is_digit = false;
+        }
+    }
+
+    if chars.len() > start {
+        let token: String = chars[start..].iter().collect();
+        finish(&mut stack, &token, is_digit, false);
+    }
+
+    // Fold the stack back into nested lists, normalizing each level.
+    let mut current = stack.pop().expect("version stack is never empty");
+    normalize(&mut current);
+    while let Some(mut parent) = stack.pop() {
+        parent.push(Item::List(current));
+        normalize(&mut parent);
+        current = parent;
+    }
+    current
+}
+
+/// Removes trailing null items (`0`, release qualifiers, empty lists).
+fn normalize(items: &mut Vec<Item>) {
+    let mut i = items.len();
+    while i > 0 {
+        i -= 1;
+        if items[i].is_null() {
+            items.remove(i);
+        } else if !matches!(items[i], Item::List(_)) {
+            break;
+        }
+    }
+}
+
+fn canonical_qualifier(token: &str, followed_by_digit: bool) -> String {
+    let aliased = match token {
+        "a" if followed_by_digit => "alpha",
+        "b" if followed_by_digit => "beta",
+        "m" if followed_by_digit => "milestone",
+        "ga" | "final" | "release" => "",
+        "cr" | "pre" => "rc",
+        other => other,
+    };
+    aliased.to_string()
+}
+
+fn qualifier_key(qualifier: &str) -> String {
+    match QUALIFIERS.iter().position(|known| *known == qualifier) {
+        Some(index) => index.to_string(),
+        None => format!("{}-{}", QUALIFIERS.len(), qualifier),
+    }
+}
+
+fn cmp_lists(a: &[Item], b: &[Item]) -> Ordering {
+    let len = a.len().max(b.len());
+    for i in 0..len {
+        let ordering = cmp_items(a.get(i), b.get(i));
+        if ordering != Ordering::Equal {
+            return ordering;
+        }
+    }
+    Ordering::Equal
+}
+
+/// Compares two items, where `None` stands for a missing component.
+fn cmp_items(a: Option<&Item>, b: Option<&Item>) -> Ordering {
+    match (a, b) {
+        (None, None) => Ordering::Equal,
+        (Some(item), None) => cmp_with_null(item),
+        (None, Some(item)) => cmp_with_null(item).reverse(),
+        (Some(Item::Int(a)), Some(Item::Int(b))) => a.len().cmp(&b.len()).then_with(|| a.cmp(b)),
+        (Some(Item::Int(_)), Some(_)) => Ordering::Greater,
+        (Some(Item::Str(_)), Some(Item::Int(_))) => Ordering::Less,
+        (Some(Item::Str(a)), Some(Item::Str(b))) => qualifier_key(a).cmp(&qualifier_key(b)),
+        (Some(Item::Str(_)), Some(Item::List(_))) => Ordering::Less,
+        (Some(Item::List(_)), Some(Item::Int(_))) => Ordering::Less,
+        (Some(Item::List(_)), Some(Item::Str(_))) => Ordering::Greater,
+        (Some(Item::List(a)), Some(Item::List(b))) => cmp_lists(a, b),
+    }
+}
+
+fn cmp_with_null(item: &Item) -> Ordering {
+    match item {
+        Item::Int(digits) if digits.is_empty() => Ordering::Equal,
+        Item::Int(_) => Ordering::Greater,
+        Item::Str(qualifier) => qualifier_key(qualifier).cmp(&qualifier_key("")),
+        Item::List(items) => items.first().map_or(Ordering::Equal, cmp_with_null),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn v(version: &str) -> ModVersion {
+        ModVersion::parse(version).unwrap()
+    }
+
+    #[test]
+    fn test_numeric_ordering() {
+        assert!(v("1.10") > v("1.9"));
+        assert!(v("1.0.1") > v("1.0"));
+        assert_eq!(v("1.0"), v("1.0.0"));
+        assert_eq!(v("1.0"), v("1.0-final"));
+        assert!(v("2.0") > v("1.99.99"));
+    }
+
+    #[test]
+    fn test_qualifier_ordering() {
+        assert!(v("1.0-alpha") < v("1.0-beta"));
+        assert!(v("1.0-beta.11") < v("1.0-rc.1"));
+        assert!(v("1.0-rc.1") < v("1.0"));
+        assert!(v("1.0-snapshot") < v("1.0"));
+        assert!(v("1.0") < v("1.0-sp1"));
+        assert!(v("1.0a1") < v("1.0b1"));
+    }
+
+    #[test]
+    fn test_minecraft_prefixed_versions() {
+        assert!(v("1.21.1-2.3.5") > v("1.21.1-2.3.4"));
+        assert!(v("1.21.1-2.4.0") > v("1.21.1-2.3.10"));
+        assert!(v("mc1.21.1-2.3.5") > v("mc1.21.1-2.3.4"));
+        assert!(v("2.3.5-mc1.21.1") > v("2.3.4-mc1.21.1"));
+    }
+
+    #[test]
+    fn test_same_major_minor_skips_minecraft_prefix() {
+        assert!(same_major_minor("1.21.1-6.0.0", "1.21.1-6.0.3"));
+        assert!(!same_major_minor("1.21.1-6.0.0", "1.21.1-7.2.0"));
+        assert!(!same_major_minor("mc1.21.1-6.0.0", "mc1.21.1-7.2.0"));
+        assert!(same_major_minor("1.21.1-6.0.0", "6.0.2"));
+        assert!(same_major_minor("1.2.3-beta", "1.2.0"));
+    }
+
+    #[test]
+    fn test_build_metadata_is_ignored_for_precedence() {
+        assert_eq!(v("0.92.2+1.20.1"), v("0.92.2+1.21"));
+        assert!(!versions_equivalent("0.92.2+1.20.1", "0.92.2+1.21"));
+        assert!(versions_equivalent("1.0", "1.0.0"));
+    }
+
+    #[test]
+    fn test_classify_version_change() {
+        assert_eq!(
+            classify_version_change("1.2.0", "1.3.0"),
+            VersionDirection::Upgrade
+        );
+        assert_eq!(
+            classify_version_change("1.3.0", "1.2.0"),
+            VersionDirection::Downgrade
+        );
+        assert_eq!(
+            classify_version_change("1.0+a", "1.0+b"),
+            VersionDirection::Sidegrade
+        );
+        assert_eq!(
+            classify_version_change("unknown", "1.0"),
+            VersionDirection::Unknown
+        );
+    }
+
+    #[test]
+    fn test_maven_ranges() {
+        let range = VersionRange::parse_maven("[1.21,1.21.2)").unwrap();
+        assert_eq!(range.contains_str("1.21"), Some(true));
+        assert_eq!(range.contains_str("1.21.1"), Some(true));
+        assert_eq!(range.contains_str("1.21.2"), Some(false));
+        assert_eq!(range.contains_str("1.20.1"), Some(false));
+
+        let open = VersionRange::parse_maven("[20.4,)").unwrap();
+        assert_eq!(open.contains_str("21.1.5"), Some(true));
+
+        let union = VersionRange::parse_maven("[1.0,1.2),[1.3,)").unwrap();
+        assert_eq!(union.contains_str("1.2.5"), Some(false));
+        assert_eq!(union.contains_str("1.4"), Some(true));
+
+        assert!(VersionRange::parse_maven("*").unwrap().is_any());
+        assert!(VersionRange::parse_maven("1.20.1").unwrap().is_any());
+    }
+
+    #[test]
+    fn test_semver_ranges() {
+        let range = VersionRange::parse_semver(">=1.20.1 <1.21").unwrap();
+        assert_eq!(range.contains_str("1.20.4"), Some(true));
+        assert_eq!(range.contains_str("1.21"), Some(false));
+
+        let tilde = VersionRange::parse_semver("~1.20.1").unwrap();
+        assert_eq!(tilde.contains_str("1.20.6"), Some(true));
+        assert_eq!(tilde.contains_str("1.21"), Some(false));
+        assert_eq!(tilde.contains_str("1.21-pre1"), Some(false));
+
+        let wildcard = VersionRange::parse_semver("1.21.x").unwrap();
+        assert_eq!(wildcard.contains_str("1.21.4"), Some(true));
+        assert_eq!(wildcard.contains_str("1.22"), Some(false));
+
+        let either = VersionRange::parse_semver("1.20.1 || >=1.21").unwrap();
+        assert_eq!(either.contains_str("1.20.1"), Some(true));
+        assert_eq!(either.contains_str("1.20.2"), Some(false));
+        assert_eq!(either.contains_str("1.21.4"), Some(true));
+
+        assert!(VersionRange::parse("*").unwrap().is_any());
+    }
+}
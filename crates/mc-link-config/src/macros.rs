@@ -59,7 +59,7 @@ macro_rules! config_struct {
 /// - The enum definition with Debug, Clone, PartialEq, Eq, Serialize, Deserialize
 /// - Default implementation using the specified default variant
 /// - Display implementation for string conversion
-/// - From<T> for config::Value implementation
+/// - From<T> for config::ValueKind implementation, which also converts to config::Value
 #[macro_export]
 macro_rules! config_enum {
     (
@@ -87,15 +87,14 @@ macro_rules! config_enum {
             }
         }
 
-        impl From<$name> for config::Value {
+        impl From<$name> for config::ValueKind {
             fn from(val: $name) -> Self {
-                use config::{Value, ValueKind};
                 let s = match val {
                     $(
                         $name::$variant => stringify!($variant).to_lowercase(),
                     )*
                 };
-                Value::new(None, ValueKind::String(s))
+                config::ValueKind::String(s)
             }
         }
 
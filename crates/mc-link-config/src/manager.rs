@@ -44,6 +44,15 @@ config_struct! {
         pub jar_cache_global: bool = true,
         /// whether to invalidate all caches on startup
         pub cache_invalidate_on_startup: bool = false,
+        /// whether to identify scanned mods by hash on Modrinth
+        #[serde(default)]
+        pub modrinth_lookup: bool = false,
+        /// base URL of the Modrinth API, if not the public one
+        pub modrinth_api_url: Option<String> = None,
+        /// CurseForge API key; mods are identified by fingerprint on CurseForge when set
+        pub curseforge_api_key: Option<String> = None,
+        /// base URL of the CurseForge API, if not the public one
+        pub curseforge_api_url: Option<String> = None,
     }
 }
 
@@ -16,7 +16,8 @@ pub use crate::servers::{
 
 // Profile management
 pub use crate::profiles::{
-    CompatibilityProfile, CompatibilityRule, ProfileIndex, ProfileManager, ProfileType, RuleAction,
+    CompatibilityAlias, CompatibilityProfile, CompatibilityRule, ProfileIndex, ProfileManager,
+    ProfileType, RuleAction,
 };
 
 // Configuration macros
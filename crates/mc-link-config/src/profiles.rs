@@ -23,6 +23,7 @@ config_enum! {
         ClientOnly,
         ServerOnly,
         Warn,
+        Tolerate,
     }
     default = Ignore
 }
@@ -38,6 +39,20 @@ config_struct! {
         pub reason: Option<String> = None,
         /// Whether this rule uses regex patterns
         pub is_regex: bool = false,
+        /// Accepted version drift for `tolerate` rules: `minor`, `any` or a version range
+        pub version_tolerance: Option<String> = None,
+    }
+}
+
+config_struct! {
+    /// Set of mod IDs that refer to the same logical mod, e.g. across loaders or renames.
+    pub struct CompatibilityAlias {
+        /// ID the mod is compared under
+        pub mod_id: String = String::new(),
+        /// Other IDs the same mod is published under
+        pub aliases: Vec<String> = vec![],
+        /// Reason for this alias (for documentation)
+        pub reason: Option<String> = None,
     }
 }
 
@@ -60,6 +75,9 @@ config_struct! {
         pub tags: Vec<String> = vec![],
         /// Compatibility rules
         pub rules: Vec<CompatibilityRule> = vec![],
+        /// Mod ID aliases applied before comparison
+        #[serde(default)]
+        pub aliases: Vec<CompatibilityAlias> = vec![],
         /// Whether this is a system profile (read-only)
         pub system: bool = false,
         /// Creation timestamp
@@ -262,6 +280,79 @@ impl ProfileManager {
         Ok(())
     }
 
+    /// Installs a profile shared as a JSON file and returns its name.
+    ///
+    /// Imported profiles are never system profiles, and cannot replace one.
+    pub fn import_profile(&mut self, path: &Path) -> Result<String> {
+        let content = std::fs::read_to_string(path).map_err(|e| {
+            ConfigError::io_error(
+                "read profile file",
+                format!("Failed to read {}: {}", path.display(), e),
+                Some(e),
+            )
+        })?;
+
+        let mut profile: CompatibilityProfile = serde_json::from_str(&content).map_err(|e| {
+            ConfigError::serialization_error(
+                "JSON",
+                format!("Failed to parse profile file: {}", e),
+                Some(Box::new(e)),
+            )
+        })?;
+
+        let valid_name = !profile.name.is_empty()
+            && profile
+                .name
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'))
+            && !profile.name.starts_with('.');
+        if !valid_name {
+            return Err(ConfigError::invalid_config(
+                "profile.name",
+                format!("Invalid profile name '{}'", profile.name),
+                None,
+            ));
+        }
+        if self.get_profile(&profile.name).is_some_and(|p| p.system) {
+            return Err(ConfigError::invalid_config(
+                "profile.system",
+                "Cannot replace system profile",
+                None,
+            ));
+        }
+
+        profile.system = false;
+        let name = profile.name.clone();
+        self.add_profile(profile)?;
+        Ok(name)
+    }
+
+    /// Writes a profile to a JSON file that can be shared and imported elsewhere.
+    pub fn export_profile(&self, name: &str, path: &Path) -> Result<()> {
+        let profile = self
+            .get_profile(name)
+            .ok_or_else(|| ConfigError::ProfileNotFound {
+                profile_name: name.to_string(),
+                cause: None,
+            })?;
+
+        let content = serde_json::to_string_pretty(profile).map_err(|e| {
+            ConfigError::serialization_error(
+                "JSON",
+                format!("Failed to serialize profile: {}", e),
+                Some(Box::new(e)),
+            )
+        })?;
+
+        std::fs::write(path, content).map_err(|e| {
+            ConfigError::io_error(
+                "write profile",
+                format!("Failed to write {}: {}", path.display(), e),
+                Some(e),
+            )
+        })
+    }
+
     /// Scans profiles directory and updates index.
     pub fn scan_and_update(&mut self) -> Result<()> {
         self.scan_profiles()?;
@@ -354,14 +445,17 @@ impl ProfileManager {
                     action: RuleAction::ClientOnly,
                     reason: Some("OptiFine is client-only".to_string()),
                     is_regex: false,
+                    version_tolerance: None,
                 },
                 CompatibilityRule {
                     mod_pattern: ".*-client$".to_string(),
                     action: RuleAction::ClientOnly,
                     reason: Some("Mods ending with -client are typically client-only".to_string()),
                     is_regex: true,
+                    version_tolerance: None,
                 },
             ],
+            aliases: vec![],
             system: true,
             created_at: Some(chrono::Utc::now().to_rfc3339()),
             last_modified: Some(chrono::Utc::now().to_rfc3339()),
@@ -153,8 +153,11 @@ config_struct! {
     pub struct ServerSettings {
         /// Minecraft version (e.g., "1.20.1")
         pub minecraft_version: Option<String> = None,
-        /// Mod loader type
-        pub mod_loader: ModLoader = ModLoader::default(),
+        /// Mod loader type, or `None` to detect it from the installed mods
+        pub mod_loader: Option<ModLoader> = None,
+        /// Mod loader version (e.g., "21.1.77")
+        #[serde(default)]
+        pub loader_version: Option<String> = None,
         /// Server type description
         pub server_type: Option<String> = None,
         /// Whether this is a development server
@@ -163,6 +166,9 @@ config_struct! {
         pub properties: HashMap<String, String> = HashMap::new(),
         /// Server tags for organization
         pub tags: Vec<String> = vec![],
+        /// Mods held at a version and left out of update checks (mod ID -> version)
+        #[serde(default)]
+        pub pinned_mods: HashMap<String, String> = HashMap::new(),
     }
 }
 
@@ -177,7 +183,9 @@ config_struct! {
         pub auto_ignore_server_only: bool = true,
         /// Default compatibility profile to use
         pub default_profile: Option<String> = None,
-        /// Custom compatibility rules
+        /// Custom compatibility rules, each written as `<action> <pattern> [# reason]`;
+        /// the reason starts at a `#` after whitespace, and `\#` is a literal `#`
+        /// (e.g. `client-only *-client # UI only`)
         pub custom_rules: Vec<String> = vec![],
     }
 }
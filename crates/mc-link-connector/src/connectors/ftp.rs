@@ -1,7 +1,7 @@
 use mc_link_config::FtpConnection;
 use mc_link_core::traits::PathExt;
 use mc_link_core::{
-    CoreError, ProgressCallback, Result, ServerConnector, ServerInfo, ServerStatus,
+    CoreError, ProgressCallback, RemoteEntry, Result, ServerConnector, ServerInfo, ServerStatus,
 };
 use std::collections::HashMap;
 use std::path::PathBuf;
@@ -346,6 +346,45 @@ impl ServerConnector for FtpConnector {
         }
     }
 
+    fn list_entries(
+        &self,
+        remote_path: &PathBuf,
+    ) -> impl std::future::Future<Output = Result<Vec<RemoteEntry>>> + Send {
+        let remote_path = remote_path.clone();
+        let ftp_stream = self.ftp_stream.clone();
+        let connected = self.connected.clone();
+
+        async move {
+            if !*connected.lock().await {
+                return Err(CoreError::ConnectionFailed {
+                    message: "Not connected to FTP server".to_string(),
+                });
+            }
+
+            let mut ftp_stream = ftp_stream.lock().await;
+            let stream = ftp_stream.as_mut().ok_or(CoreError::ConnectionFailed {
+                message: "FTP stream is not initialized".to_string(),
+            })?;
+
+            let entries = stream
+                .list(Some(&remote_path.to_slash_lossy()))
+                .await
+                .map_err(|e| CoreError::NetworkError {
+                    message: format!("Failed to list files: {}", e),
+                })?;
+
+            Ok(entries
+                .iter()
+                .filter_map(|entry| File::try_from(entry.as_str()).ok())
+                .filter(|file| file.name() != "." && file.name() != "..")
+                .map(|file| RemoteEntry {
+                    path: remote_path.join(file.name()),
+                    is_dir: file.is_directory(),
+                })
+                .collect())
+        }
+    }
+
     fn delete_file(
         &self,
         remote_path: &PathBuf,
@@ -384,6 +423,41 @@ impl ServerConnector for FtpConnector {
         }
     }
 
+    fn rename_file(
+        &self,
+        from: &PathBuf,
+        to: &PathBuf,
+    ) -> impl std::future::Future<Output = Result<()>> + Send {
+        let from = from.clone();
+        let to = to.clone();
+        let ftp_stream = self.ftp_stream.clone();
+        let connected = self.connected.clone();
+
+        async move {
+            if !*connected.lock().await {
+                return Err(CoreError::ConnectionFailed {
+                    message: "Not connected to FTP server".to_string(),
+                });
+            }
+
+            let mut ftp_stream = ftp_stream.lock().await;
+            let stream = ftp_stream.as_mut().ok_or(CoreError::ConnectionFailed {
+                message: "FTP stream is not initialized".to_string(),
+            })?;
+
+            // RNTO does not replace an existing file on every server
+            let _ = stream.rm(&to.to_slash_lossy()).await;
+
+            stream
+                .rename(from.to_slash_lossy(), to.to_slash_lossy())
+                .await
+                .map_err(|e| CoreError::FileOperationFailed {
+                    operation: "rename file".to_string(),
+                    reason: format!("Failed to rename: {}", e),
+                })
+        }
+    }
+
     fn create_directory(
         &self,
         remote_path: &PathBuf,
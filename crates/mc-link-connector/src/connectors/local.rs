@@ -1,7 +1,7 @@
 use mc_link_config::LocalConnection;
 use mc_link_core::{
-    CoreError, ModInfo, ProgressCallback, Result, ServerConnector, ServerInfo, ServerStatus,
-    ensure_connected,
+    CoreError, ModInfo, ProgressCallback, RemoteEntry, Result, ServerConnector, ServerInfo,
+    ServerStatus, ensure_connected,
 };
 use std::collections::HashMap;
 use std::future::Future;
@@ -116,16 +116,7 @@ impl LocalConnector {
                         .unwrap_or("unknown")
                         .to_string();
 
-                    mods.push(ModInfo {
-                        id: name.clone(),
-                        name,
-                        version: None,
-                        file_path: path,
-                        enabled: true,
-                        side: mc_link_core::ModSide::Unknown,
-                        loader: mc_link_core::ModLoader::Unknown,
-                        raw_metadata: std::collections::HashMap::new(),
-                    });
+                    mods.push(ModInfo::new(name, path));
                 }
             }
         }
@@ -391,6 +382,60 @@ impl ServerConnector for LocalConnector {
         }
     }
 
+    fn list_entries(
+        &self,
+        remote_path: &PathBuf,
+    ) -> impl Future<Output = Result<Vec<RemoteEntry>>> + Send {
+        let remote_path = remote_path.clone();
+        let server_path = self.server_path.clone();
+
+        async move {
+            ensure_connected!(self);
+
+            let full_path = server_path.join(&remote_path);
+
+            if !full_path.exists() {
+                return Ok(Vec::new());
+            }
+
+            let mut listed = Vec::new();
+            let mut entries =
+                fs::read_dir(&full_path)
+                    .await
+                    .map_err(|e| CoreError::FileOperationFailed {
+                        operation: "read directory".to_string(),
+                        reason: e.to_string(),
+                    })?;
+
+            while let Some(entry) =
+                entries
+                    .next_entry()
+                    .await
+                    .map_err(|e| CoreError::FileOperationFailed {
+                        operation: "read directory entry".to_string(),
+                        reason: e.to_string(),
+                    })?
+            {
+                let is_dir = entry
+                    .file_type()
+                    .await
+                    .map_err(|e| CoreError::FileOperationFailed {
+                        operation: "read directory entry type".to_string(),
+                        reason: e.to_string(),
+                    })?
+                    .is_dir();
+                if let Ok(relative_path) = entry.path().strip_prefix(&server_path) {
+                    listed.push(RemoteEntry {
+                        path: relative_path.to_path_buf(),
+                        is_dir,
+                    });
+                }
+            }
+
+            Ok(listed)
+        }
+    }
+
     fn delete_file(&self, remote_path: &PathBuf) -> impl Future<Output = Result<()>> + Send {
         let remote_path = remote_path.clone();
         let server_path = self.server_path.clone();
@@ -414,6 +459,24 @@ impl ServerConnector for LocalConnector {
         }
     }
 
+    fn rename_file(&self, from: &PathBuf, to: &PathBuf) -> impl Future<Output = Result<()>> + Send {
+        let from = self.server_path.join(from);
+        let to = self.server_path.join(to);
+
+        async move {
+            ensure_connected!(self);
+
+            fs::rename(&from, &to)
+                .await
+                .map_err(|e| CoreError::FileOperationFailed {
+                    operation: "rename file".to_string(),
+                    reason: e.to_string(),
+                })?;
+
+            Ok(())
+        }
+    }
+
     fn create_directory(&self, remote_path: &PathBuf) -> impl Future<Output = Result<()>> + Send {
         let remote_path = remote_path.clone();
         let server_path = self.server_path.clone();
@@ -443,3 +506,67 @@ impl ServerConnector for LocalConnector {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    async fn connected(dir: &std::path::Path) -> LocalConnector {
+        let mut connector = LocalConnector::new(&LocalConnection {
+            path: dir.display().to_string(),
+        });
+        connector.connect().await.unwrap();
+        connector
+    }
+
+    #[tokio::test]
+    async fn rename_replaces_destination() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir(dir.path().join("mods")).unwrap();
+        std::fs::write(dir.path().join("mods/jei.jar"), "new").unwrap();
+        std::fs::write(dir.path().join("mods/jei-19.21.0.jar"), "old").unwrap();
+
+        let connector = connected(dir.path()).await;
+        connector
+            .rename_file(
+                &PathBuf::from("mods/jei.jar"),
+                &PathBuf::from("mods/jei-19.21.0.jar"),
+            )
+            .await
+            .unwrap();
+
+        assert!(!dir.path().join("mods/jei.jar").exists());
+        assert_eq!(
+            std::fs::read_to_string(dir.path().join("mods/jei-19.21.0.jar")).unwrap(),
+            "new"
+        );
+    }
+
+    #[tokio::test]
+    async fn lists_entries_with_their_type() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir_all(dir.path().join("config/jei")).unwrap();
+        std::fs::write(dir.path().join("config/create.toml"), "").unwrap();
+
+        let connector = connected(dir.path()).await;
+        let mut entries = connector
+            .list_entries(&PathBuf::from("config"))
+            .await
+            .unwrap();
+        entries.sort_by(|a, b| a.path.cmp(&b.path));
+
+        assert_eq!(
+            entries,
+            [
+                RemoteEntry {
+                    path: PathBuf::from("config/create.toml"),
+                    is_dir: false,
+                },
+                RemoteEntry {
+                    path: PathBuf::from("config/jei"),
+                    is_dir: true,
+                },
+            ]
+        );
+    }
+}
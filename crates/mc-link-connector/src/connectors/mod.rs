@@ -3,7 +3,7 @@ pub mod local;
 
 pub use ftp::*;
 pub use local::*;
-use mc_link_core::{ProgressCallback, ServerConnector, ServerInfo};
+use mc_link_core::{ProgressCallback, RemoteEntry, ServerConnector, ServerInfo};
 use std::path::PathBuf;
 
 pub enum Connector {
@@ -125,6 +125,18 @@ impl ServerConnector for Connector {
         }
     }
 
+    fn list_entries(
+        &self,
+        remote_path: &PathBuf,
+    ) -> impl Future<Output = mc_link_core::Result<Vec<RemoteEntry>>> + Send {
+        async move {
+            match self {
+                Connector::Local(connector) => connector.list_entries(remote_path).await,
+                Connector::Ftp(connector) => connector.list_entries(remote_path).await,
+            }
+        }
+    }
+
     fn delete_file(
         &self,
         remote_path: &PathBuf,
@@ -137,6 +149,19 @@ impl ServerConnector for Connector {
         }
     }
 
+    fn rename_file(
+        &self,
+        from: &PathBuf,
+        to: &PathBuf,
+    ) -> impl Future<Output = mc_link_core::Result<()>> + Send {
+        async move {
+            match self {
+                Connector::Local(connector) => connector.rename_file(from, to).await,
+                Connector::Ftp(connector) => connector.rename_file(from, to).await,
+            }
+        }
+    }
+
     fn create_directory(
         &self,
         remote_path: &PathBuf,
@@ -1,8 +1,9 @@
 //! Caching infrastructure for Minecraft server management.
 
-use crate::{CoreError, ModInfo, Result};
+use crate::{CoreError, FileHashes, ModInfo, Platform, PlatformInfo, Result};
 use serde::{Deserialize, Serialize};
-use sha2::{Digest, Sha256};
+use sha1::Sha1;
+use sha2::{Digest, Sha256, Sha512};
 use std::collections::HashMap;
 use std::path::{Path, PathBuf};
 use std::time::{Duration, SystemTime, UNIX_EPOCH};
@@ -22,6 +23,9 @@ pub struct CachedJarInfo {
     pub cached_at: u64,
     /// Last time this entry was accessed (for LRU eviction)
     pub last_accessed: u64,
+    /// Identities of the JAR on mod hosting platforms
+    #[serde(default)]
+    pub platforms: Vec<PlatformInfo>,
 }
 
 impl CachedJarInfo {
@@ -35,6 +39,7 @@ impl CachedJarInfo {
             mod_info,
             cached_at: now,
             last_accessed: now,
+            platforms: Vec::new(),
         }
     }
 
@@ -96,6 +101,27 @@ impl GlobalJarCache {
         Ok(format!("{:x}", hasher.finalize()))
     }
 
+    /// Computes the SHA-1, SHA-256 and SHA-512 hashes and the CurseForge
+    /// fingerprint of file contents.
+    pub fn compute_file_hashes(file_path: &Path) -> Result<FileHashes> {
+        let contents = std::fs::read(file_path).map_err(|e| CoreError::FileOperationFailed {
+            operation: "read file for hashing".to_string(),
+            reason: format!("Failed to read file {}: {}", file_path.display(), e),
+        })?;
+
+        Ok(FileHashes {
+            sha1: Some(format!("{:x}", Sha1::digest(&contents))),
+            sha256: Some(format!("{:x}", Sha256::digest(&contents))),
+            sha512: Some(format!("{:x}", Sha512::digest(&contents))),
+            fingerprint: Some(curseforge_fingerprint(&contents)),
+        })
+    }
+
+    /// Returns the CurseForge fingerprint stored for a cached JAR.
+    pub fn fingerprint(&self, hash: &str) -> Option<u32> {
+        self.entries.get(hash)?.mod_info.hashes.fingerprint
+    }
+
     /// Checks if a JAR with the given hash is cached and valid.
     /// Returns a clone of the ModInfo to avoid borrowing issues.
     pub fn get(&mut self, hash: &str, ttl_hours: u32) -> Option<ModInfo> {
@@ -148,6 +174,31 @@ impl GlobalJarCache {
         Ok(())
     }
 
+    /// Returns what a platform is known to say about a cached JAR.
+    pub fn platform_info(&self, hash: &str, platform: Platform) -> Option<&PlatformInfo> {
+        self.entries
+            .get(hash)?
+            .platforms
+            .iter()
+            .find(|info| info.platform == platform)
+    }
+
+    /// Records a JAR's identity on a platform, replacing any earlier one.
+    ///
+    /// Does nothing if the JAR is not cached.
+    pub fn set_platform_info(&mut self, hash: &str, info: PlatformInfo) -> Result<()> {
+        let Some(entry) = self.entries.get_mut(hash) else {
+            return Ok(());
+        };
+        entry
+            .platforms
+            .retain(|known| known.platform != info.platform);
+        entry.platforms.push(info);
+
+        let entry = entry.clone();
+        self.save_cache_entry(hash, &entry)
+    }
+
     /// Removes the least recently used entry.
     fn evict_lru(&mut self) -> Result<()> {
         let oldest_hash = self
@@ -380,6 +431,42 @@ impl ServerStructureCache {
     }
 }
 
+/// Computes the fingerprint CurseForge identifies files by.
+///
+/// This is 32-bit MurmurHash2 with seed 1 over the contents with all tab,
+/// line feed, carriage return and space bytes removed.
+pub fn curseforge_fingerprint(contents: &[u8]) -> u32 {
+    const M: u32 = 0x5bd1_e995;
+
+    let data: Vec<u8> = contents
+        .iter()
+        .copied()
+        .filter(|b| !matches!(b, b'\t' | b'\n' | b'\r' | b' '))
+        .collect();
+
+    let mut h = 1 ^ data.len() as u32;
+    let mut chunks = data.chunks_exact(4);
+    for chunk in &mut chunks {
+        let mut k = u32::from_le_bytes([chunk[0], chunk[1], chunk[2], chunk[3]]);
+        k = k.wrapping_mul(M);
+        k ^= k >> 24;
+        k = k.wrapping_mul(M);
+        h = h.wrapping_mul(M) ^ k;
+    }
+
+    let tail = chunks.remainder();
+    if !tail.is_empty() {
+        for (i, byte) in tail.iter().enumerate() {
+            h ^= u32::from(*byte) << (8 * i);
+        }
+        h = h.wrapping_mul(M);
+    }
+
+    h ^= h >> 13;
+    h = h.wrapping_mul(M);
+    h ^ (h >> 15)
+}
+
 /// Helper function to get current timestamp in seconds since Unix epoch.
 fn current_timestamp() -> u64 {
     SystemTime::now()
@@ -414,14 +501,10 @@ mod tests {
     #[test]
     fn test_cached_jar_info_expiration() {
         let mod_info = ModInfo {
-            id: "test".to_string(),
             name: "Test Mod".to_string(),
             version: Some("1.0.0".to_string()),
-            file_path: PathBuf::from("test.jar"),
-            enabled: true,
             side: crate::ModSide::Both,
-            loader: crate::ModLoader::Unknown,
-            raw_metadata: HashMap::new(),
+            ..ModInfo::new("test", "test.jar")
         };
 
         let mut cached_info = CachedJarInfo::new(
@@ -452,6 +535,14 @@ mod tests {
         assert_eq!(stats.max_size_formatted(), "10.0 MB");
     }
 
+    #[test]
+    fn test_curseforge_fingerprint_ignores_whitespace() {
+        assert_eq!(curseforge_fingerprint(b""), 1540447798);
+        assert_eq!(curseforge_fingerprint(b"abc"), 1621425345);
+        assert_eq!(curseforge_fingerprint(b"Hello, world!"), 2173663876);
+        assert_eq!(curseforge_fingerprint(b"Hello,\n world!\r\n"), 2173663876);
+    }
+
     #[test]
     fn test_format_bytes() {
         assert_eq!(format_bytes(0), "0 B");
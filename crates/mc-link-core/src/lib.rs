@@ -8,12 +8,16 @@
 pub mod cache;
 pub mod error;
 pub mod logging;
+pub mod platform;
 pub mod prelude;
 pub mod progress;
 pub mod server;
 pub mod traits;
 
-pub use cache::{CacheStats, CachedJarInfo, GlobalJarCache, ServerStructureCache};
+pub use cache::{
+    CacheStats, CachedJarInfo, GlobalJarCache, ServerStructureCache, curseforge_fingerprint,
+};
 pub use error::*;
+pub use platform::*;
 pub use progress::*;
 pub use server::*;
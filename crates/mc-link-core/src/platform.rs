@@ -0,0 +1,121 @@
+//! Identities of mod files on mod hosting platforms.
+
+use crate::{ModInfo, ModLoader, ModSide};
+use serde::{Deserialize, Serialize};
+
+/// A mod hosting platform that can identify mod files.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Platform {
+    /// modrinth.com, which identifies files by SHA-1 or SHA-512
+    Modrinth,
+    /// curseforge.com, which identifies files by a murmur2 fingerprint
+    CurseForge,
+}
+
+/// Whether a project runs on one side, as declared on its platform page.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SideSupport {
+    /// The project has to be installed on this side
+    Required,
+    /// The project works with or without being installed on this side
+    Optional,
+    /// The project must not be installed on this side
+    Unsupported,
+    /// The platform does not say
+    #[default]
+    #[serde(other)]
+    Unknown,
+}
+
+impl SideSupport {
+    fn runs(self) -> Option<bool> {
+        match self {
+            SideSupport::Required | SideSupport::Optional => Some(true),
+            SideSupport::Unsupported => Some(false),
+            SideSupport::Unknown => None,
+        }
+    }
+}
+
+/// What a platform knows about one mod file.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PlatformInfo {
+    /// Platform the file was found on
+    pub platform: Platform,
+    /// Stable project ID on the platform
+    pub project_id: String,
+    /// Human-readable project slug, if the platform has one
+    pub slug: Option<String>,
+    /// Project title
+    pub title: Option<String>,
+    /// ID of this file's version (Modrinth) or file (CurseForge)
+    pub file_id: String,
+    /// Version number of the file
+    pub version_number: Option<String>,
+    /// Whether the project runs on the client
+    #[serde(default)]
+    pub client_side: SideSupport,
+    /// Whether the project runs on a dedicated server
+    #[serde(default)]
+    pub server_side: SideSupport,
+    /// Loaders the file supports, as named by the platform
+    #[serde(default)]
+    pub loaders: Vec<String>,
+    /// Minecraft versions the file supports
+    #[serde(default)]
+    pub game_versions: Vec<String>,
+}
+
+impl PlatformInfo {
+    /// Returns the side implied by the project's client and server support.
+    pub fn side(&self) -> ModSide {
+        match (self.client_side.runs(), self.server_side.runs()) {
+            (Some(true), Some(false)) => ModSide::Client,
+            (Some(false), Some(true)) => ModSide::Server,
+            (Some(true), Some(true)) => ModSide::Both,
+            _ => ModSide::Unknown,
+        }
+    }
+
+    /// Returns the first supported loader mc-link knows about.
+    pub fn loader(&self) -> ModLoader {
+        self.loaders
+            .iter()
+            .find_map(|loader| match loader.to_ascii_lowercase().as_str() {
+                "neoforge" => Some(ModLoader::NeoForge),
+                "fabric" | "quilt" => Some(ModLoader::Fabric),
+                "forge" => Some(ModLoader::Forge),
+                _ => None,
+            })
+            .unwrap_or(ModLoader::Unknown)
+    }
+
+    /// Fills in what the mod's own metadata left out.
+    ///
+    /// Mods whose loader could not be read from the jar are named after their
+    /// file, so their ID and name are replaced with the project's slug and
+    /// title. Sides guessed from the jar's contents give way to the side the
+    /// platform declares.
+    pub fn fill_gaps(&self, mod_info: &mut ModInfo) {
+        if mod_info.loader == ModLoader::Unknown {
+            mod_info.id = self.slug.clone().unwrap_or_else(|| self.project_id.clone());
+            if let Some(title) = &self.title {
+                mod_info.name = title.clone();
+            }
+            mod_info.loader = self.loader();
+        }
+
+        if matches!(mod_info.version.as_deref(), None | Some("unknown")) {
+            mod_info.version = self.version_number.clone();
+        }
+
+        let side = self.side();
+        if side != ModSide::Unknown
+            && (mod_info.side == ModSide::Unknown || mod_info.side_guess.is_some())
+        {
+            mod_info.side = side;
+            mod_info.side_guess = None;
+        }
+    }
+}
@@ -3,7 +3,13 @@
 //! This module re-exports the most commonly used items from the crate,
 //! allowing users to quickly import everything they need with `use mc_link_core::prelude::*;`.
 
-pub use crate::cache::{CacheStats, CachedJarInfo, GlobalJarCache, ServerStructureCache};
+pub use crate::cache::{
+    CacheStats, CachedJarInfo, GlobalJarCache, ServerStructureCache, curseforge_fingerprint,
+};
 pub use crate::error::{CoreError, Result};
+pub use crate::platform::{Platform, PlatformInfo, SideSupport};
 pub use crate::progress::{ProgressAware, ProgressReporter, ProgressStage, ProgressUpdate};
-pub use crate::server::{ModInfo, ProgressCallback, ServerConnector, ServerInfo, ServerStatus};
+pub use crate::server::{
+    DependencyKind, FileHashes, ModDependency, ModInfo, ModMetadata, ProgressCallback,
+    ServerConnector, ServerInfo, ServerStatus, SideConfidence, SideGuess,
+};
@@ -82,6 +82,39 @@ pub enum ModSide {
     Unknown,
 }
 
+/// How much evidence backs a [`SideGuess`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum SideConfidence {
+    /// Weak or contradicting evidence
+    Low,
+    /// One clear kind of evidence
+    Medium,
+    /// Several kinds of evidence agree
+    High,
+}
+
+/// Side inferred from a mod's contents when its metadata does not declare one.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SideGuess {
+    /// Side the mod most likely runs on
+    pub side: ModSide,
+    /// How reliable the guess is
+    pub confidence: SideConfidence,
+    /// Human-readable evidence the guess was based on
+    pub evidence: Vec<String>,
+}
+
+impl SideGuess {
+    /// The side to act on: the guess if it is reliable enough, otherwise [`ModSide::Both`].
+    pub fn effective_side(&self) -> ModSide {
+        if self.confidence >= SideConfidence::Medium {
+            self.side.clone()
+        } else {
+            ModSide::Both
+        }
+    }
+}
+
 /// Supported mod loaders.
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub enum ModLoader {
@@ -95,6 +128,64 @@ pub enum ModLoader {
     Unknown,
 }
 
+/// How a mod relates to one of its declared dependencies.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum DependencyKind {
+    /// The dependency must be present
+    Required,
+    /// The dependency is used when present
+    Optional,
+    /// The mods must not be installed together
+    Incompatible,
+    /// The mods can be installed together but are known to misbehave
+    Discouraged,
+}
+
+/// A dependency declared in a mod's metadata.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ModDependency {
+    /// ID of the mod (or `minecraft`, `neoforge`, `fabricloader`, ...) depended on
+    pub mod_id: String,
+    /// Version range exactly as declared (Maven range or semver predicate)
+    pub version_range: Option<String>,
+    /// Kind of relationship
+    pub kind: DependencyKind,
+}
+
+/// Content hashes of a mod file.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FileHashes {
+    /// Hex-encoded SHA-1 digest
+    pub sha1: Option<String>,
+    /// Hex-encoded SHA-256 digest
+    pub sha256: Option<String>,
+    /// Hex-encoded SHA-512 digest
+    pub sha512: Option<String>,
+    /// CurseForge fingerprint, see [`curseforge_fingerprint`](crate::cache::curseforge_fingerprint)
+    pub fingerprint: Option<u32>,
+}
+
+/// Descriptive metadata declared by a mod, for display.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ModMetadata {
+    /// Short description of the mod
+    pub description: Option<String>,
+    /// Author names
+    pub authors: Vec<String>,
+    /// License name or SPDX identifier
+    pub license: Option<String>,
+    /// Homepage URL
+    pub homepage: Option<String>,
+    /// Issue tracker URL
+    pub issues: Option<String>,
+    /// Source code URL
+    pub sources: Option<String>,
+    /// URL of the Forge update checker JSON
+    pub update_json_url: Option<String>,
+    /// Path of the logo image inside the mod file
+    pub logo_path: Option<String>,
+}
+
 /// Information about a single mod installed on the server.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ModInfo {
@@ -110,10 +201,53 @@ pub struct ModInfo {
     pub enabled: bool,
     /// Which side(s) the mod runs on
     pub side: ModSide,
+    /// Guess `side` was derived from, when the metadata did not declare one
+    #[serde(default)]
+    pub side_guess: Option<SideGuess>,
     /// Mod loader type
     pub loader: ModLoader,
     /// Raw metadata for advanced processing
     pub raw_metadata: std::collections::HashMap<String, serde_json::Value>,
+    /// Dependencies declared in the mod metadata
+    #[serde(default)]
+    pub dependencies: Vec<ModDependency>,
+    /// Descriptive metadata for display
+    #[serde(default)]
+    pub metadata: ModMetadata,
+    /// Hashes of the mod file, filled in when the file is scanned
+    #[serde(default)]
+    pub hashes: FileHashes,
+}
+
+impl ModInfo {
+    /// Creates an enabled mod known only by its ID and file, such as one
+    /// whose metadata could not be read. The name is set to the ID.
+    pub fn new(id: impl Into<String>, file_path: impl Into<PathBuf>) -> Self {
+        let id = id.into();
+        Self {
+            name: id.clone(),
+            id,
+            version: None,
+            file_path: file_path.into(),
+            enabled: true,
+            side: ModSide::Unknown,
+            side_guess: None,
+            loader: ModLoader::Unknown,
+            raw_metadata: std::collections::HashMap::new(),
+            dependencies: Vec::new(),
+            metadata: ModMetadata::default(),
+            hashes: FileHashes::default(),
+        }
+    }
+}
+
+/// An entry of a remote directory listing.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RemoteEntry {
+    /// Path of the entry, relative to the server root
+    pub path: PathBuf,
+    /// Whether the entry is a directory
+    pub is_dir: bool,
 }
 
 /// Callback function for tracking file transfer progress.
@@ -160,9 +294,18 @@ pub trait ServerConnector: Send + Sync {
         remote_path: &PathBuf,
     ) -> impl Future<Output = Result<Vec<PathBuf>>> + Send;
 
+    /// List the files and directories in a remote directory, telling them apart
+    fn list_entries(
+        &self,
+        remote_path: &PathBuf,
+    ) -> impl Future<Output = Result<Vec<RemoteEntry>>> + Send;
+
     /// Delete a file on the server
     fn delete_file(&self, remote_path: &PathBuf) -> impl Future<Output = Result<()>> + Send;
 
+    /// Rename or move a file on the server, replacing the destination if it exists
+    fn rename_file(&self, from: &PathBuf, to: &PathBuf) -> impl Future<Output = Result<()>> + Send;
+
     /// Create a directory on the server
     fn create_directory(&self, remote_path: &PathBuf) -> impl Future<Output = Result<()>> + Send;
 
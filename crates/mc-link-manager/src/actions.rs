@@ -1,3 +1,4 @@
+use mc_link_compat::{CompatWarning, Decision, LoaderMismatch, ModConflict, VersionDirection};
 use mc_link_core::ModInfo;
 use serde::{Deserialize, Serialize};
 use std::path::PathBuf;
@@ -17,6 +18,18 @@ pub enum SyncAction {
         current_path: PathBuf,
         /// Path to the new mod file
         new_path: PathBuf,
+        /// Whether this update moves the mod forwards or backwards
+        direction: VersionDirection,
+    },
+
+    /// Rename a mod file whose contents already match the source
+    RenameMod {
+        /// Mod identifier
+        mod_id: String,
+        /// Current path of the mod file
+        current_path: PathBuf,
+        /// Path the mod file should have
+        new_path: PathBuf,
     },
 
     /// Add a new mod that doesn't exist on the target
@@ -64,6 +77,30 @@ pub struct SyncPlan {
     pub summary: SyncSummary,
     /// Whether the sync would result in compatibility
     pub will_be_compatible: bool,
+    /// Non-fatal compatibility warnings to show alongside the plan
+    #[serde(default)]
+    pub warnings: Vec<CompatWarning>,
+    /// Compatibility decisions the plan was built from
+    #[serde(default)]
+    pub decisions: Vec<Decision>,
+    /// Set when the two instances run loaders that cannot share mods
+    #[serde(default)]
+    pub loader_mismatch: Option<LoaderMismatch>,
+    /// Declared incompatibilities already present on either side
+    #[serde(default)]
+    pub conflicts: Vec<ModConflict>,
+    /// Actions left out of the plan because applying them would break the target
+    #[serde(default)]
+    pub blocked: Vec<BlockedAction>,
+}
+
+/// An action that was withheld from a sync plan.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BlockedAction {
+    /// The action that would have been performed
+    pub action: SyncAction,
+    /// Why it was withheld
+    pub reason: String,
 }
 
 /// Summary of planned sync operations.
@@ -71,10 +108,16 @@ pub struct SyncPlan {
 pub struct SyncSummary {
     /// Number of mods to be updated
     pub mods_to_update: usize,
+    /// Number of updates that downgrade the target (included in `mods_to_update`)
+    #[serde(default)]
+    pub mods_to_downgrade: usize,
     /// Number of mods to be added
     pub mods_to_add: usize,
     /// Number of mods to be removed
     pub mods_to_remove: usize,
+    /// Number of mod files to be renamed
+    #[serde(default)]
+    pub mods_to_rename: usize,
     /// Number of mods to keep as-is
     pub mods_to_keep: usize,
     /// Total number of mods processed
@@ -88,31 +131,86 @@ impl SyncPlan {
             actions: Vec::new(),
             summary: SyncSummary {
                 mods_to_update: 0,
+                mods_to_downgrade: 0,
                 mods_to_add: 0,
                 mods_to_remove: 0,
+                mods_to_rename: 0,
                 mods_to_keep: 0,
                 total_mods: 0,
             },
             will_be_compatible: true,
+            warnings: Vec::new(),
+            decisions: Vec::new(),
+            loader_mismatch: None,
+            conflicts: Vec::new(),
+            blocked: Vec::new(),
         }
     }
 
     /// Adds an action to the sync plan and updates the summary.
     pub fn add_action(&mut self, action: SyncAction) {
         match &action {
-            SyncAction::UpdateMod { .. } => self.summary.mods_to_update += 1,
+            SyncAction::UpdateMod { direction, .. } => {
+                self.summary.mods_to_update += 1;
+                if *direction == VersionDirection::Downgrade {
+                    self.summary.mods_to_downgrade += 1;
+                }
+            }
             SyncAction::AddMod { .. } => self.summary.mods_to_add += 1,
             SyncAction::RemoveMod { .. } => self.summary.mods_to_remove += 1,
+            SyncAction::RenameMod { .. } => self.summary.mods_to_rename += 1,
             SyncAction::KeepAsIs { .. } => self.summary.mods_to_keep += 1,
         }
         self.summary.total_mods += 1;
         self.actions.push(action);
     }
 
+    /// Returns the planned updates that would downgrade the target.
+    pub fn downgrades(&self) -> impl Iterator<Item = &SyncAction> {
+        self.actions.iter().filter(|action| {
+            matches!(
+                action,
+                SyncAction::UpdateMod {
+                    direction: VersionDirection::Downgrade,
+                    ..
+                }
+            )
+        })
+    }
+
+    /// Replaces every planned downgrade with a [`SyncAction::KeepAsIs`].
+    ///
+    /// Used when a downgrade required confirmation and was declined.
+    pub fn drop_downgrades(&mut self, reason: &str) {
+        let actions = std::mem::take(&mut self.actions);
+        self.summary = SyncPlan::new().summary;
+
+        for action in actions {
+            match action {
+                SyncAction::UpdateMod {
+                    mod_id,
+                    direction: VersionDirection::Downgrade,
+                    ..
+                } => self.add_action(SyncAction::KeepAsIs {
+                    mod_id,
+                    reason: reason.to_string(),
+                }),
+                action => self.add_action(action),
+            }
+        }
+        self.will_be_compatible = false;
+    }
+
+    /// Returns the compatibility decisions made for one mod, in order.
+    pub fn explain<'a>(&'a self, mod_id: &'a str) -> impl Iterator<Item = &'a Decision> {
+        self.decisions.iter().filter(move |d| d.mod_id == mod_id)
+    }
+
     /// Returns true if the sync plan has any actions to perform.
     pub fn has_changes(&self) -> bool {
         self.summary.mods_to_update > 0
             || self.summary.mods_to_add > 0
             || self.summary.mods_to_remove > 0
+            || self.summary.mods_to_rename > 0
     }
 }
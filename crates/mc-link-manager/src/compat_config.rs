@@ -0,0 +1,194 @@
+//! Layered construction of the compatibility configuration for a server.
+
+use crate::{Result, aliases_from_profile, resolve_profile, rule_from_profile};
+use mc_link_compat::{CompatConfig, CompatRule, DowngradePolicy, RuleSource, RuleType};
+use mc_link_config::{ConfigManager, ServerConfig};
+use std::collections::HashMap;
+
+/// Builds the effective [`CompatConfig`] for comparing against a server.
+///
+/// Settings are layered global, then profile, then server, then command line,
+/// with later layers taking precedence, so a server can override a profile it
+/// shares with others. Rules are matched first-match-wins, so they are ordered
+/// command line, server custom rules, server ignore list, profile, global
+/// ignore list. The auto-ignore flags come from the server, and mod ID
+/// aliases from the profile.
+#[derive(Debug)]
+pub struct CompatConfigBuilder<'c> {
+    config: &'c ConfigManager,
+    server: &'c ServerConfig,
+    profile: Option<&'c str>,
+    cli_rules: Vec<CompatRule>,
+    downgrade_policy: DowngradePolicy,
+}
+
+impl<'c> CompatConfigBuilder<'c> {
+    /// Creates a builder for comparing against `server`.
+    pub fn new(config: &'c ConfigManager, server: &'c ServerConfig) -> Self {
+        Self {
+            config,
+            server,
+            profile: None,
+            cli_rules: Vec::new(),
+            downgrade_policy: DowngradePolicy::default(),
+        }
+    }
+
+    /// Uses a specific profile instead of the server or global default.
+    pub fn with_profile(mut self, profile: Option<&'c str>) -> Self {
+        self.profile = profile;
+        self
+    }
+
+    /// Adds command line rules, which take precedence over every other layer.
+    pub fn with_rules(mut self, rules: impl IntoIterator<Item = CompatRule>) -> Self {
+        self.cli_rules.extend(
+            rules
+                .into_iter()
+                .map(|rule| rule.with_source(RuleSource::CommandLine)),
+        );
+        self
+    }
+
+    /// Sets how downgrades are handled.
+    pub fn with_downgrade_policy(mut self, policy: DowngradePolicy) -> Self {
+        self.downgrade_policy = policy;
+        self
+    }
+
+    /// Resolves the profile and parses the server's rules into a config.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the selected profile does not exist or a server
+    /// custom rule cannot be parsed.
+    pub fn build(self) -> Result<CompatConfig> {
+        let server_settings = &self.server.compatibility;
+        let global_settings = &self.config.servers().global_settings;
+
+        let mut rules = self.cli_rules;
+        let mut aliases = HashMap::new();
+
+        for (i, text) in server_settings.custom_rules.iter().enumerate() {
+            let rule: CompatRule = text.parse()?;
+            rules.push(rule.with_source(RuleSource::ServerRule { index: i + 1 }));
+        }
+
+        rules.extend(server_settings.ignore_mods.iter().map(|mod_id| {
+            CompatRule::new(mod_id.clone(), RuleType::AlwaysIgnore, "")
+                .with_source(RuleSource::ServerIgnoreList)
+        }));
+
+        if let Some(profile) = resolve_profile(self.profile, self.server, self.config)? {
+            aliases.extend(aliases_from_profile(profile));
+            rules.extend(profile.rules.iter().enumerate().map(|(i, rule)| {
+                rule_from_profile(rule).with_source(RuleSource::Profile {
+                    name: profile.name.clone(),
+                    index: i + 1,
+                })
+            }));
+        }
+
+        rules.extend(global_settings.global_ignore_mods.iter().map(|mod_id| {
+            CompatRule::new(mod_id.clone(), RuleType::AlwaysIgnore, "")
+                .with_source(RuleSource::GlobalIgnoreList)
+        }));
+
+        Ok(CompatConfig {
+            custom_rules: rules,
+            auto_ignore_client_only: server_settings.auto_ignore_client_only,
+            auto_ignore_server_only: server_settings.auto_ignore_server_only,
+            downgrade_policy: self.downgrade_policy,
+            aliases,
+            ..Default::default()
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use mc_link_config::{CompatibilityProfile, CompatibilityRule, RuleAction};
+
+    fn config_with_profile(dir: &std::path::Path) -> ConfigManager {
+        let mut config = ConfigManager::from_dir(Some(dir)).unwrap();
+        config
+            .profiles_mut()
+            .add_profile(CompatibilityProfile {
+                name: "pack".to_string(),
+                rules: vec![CompatibilityRule {
+                    mod_pattern: "jei".to_string(),
+                    action: RuleAction::Tolerate,
+                    ..Default::default()
+                }],
+                ..Default::default()
+            })
+            .unwrap();
+        config.servers_mut().global_settings.global_ignore_mods = vec!["jei".to_string()];
+        config
+    }
+
+    fn server() -> ServerConfig {
+        let mut server = ServerConfig::new("survival".to_string(), "Survival".to_string());
+        server.compatibility.default_profile = Some("pack".to_string());
+        server.compatibility.custom_rules = vec!["warn jei # noisy".to_string()];
+        server.compatibility.ignore_mods = vec!["jei".to_string()];
+        server
+    }
+
+    #[test]
+    fn orders_rules_by_layer_precedence() {
+        let dir = tempfile::tempdir().unwrap();
+        let config = config_with_profile(dir.path());
+        let server = server();
+
+        let compat = CompatConfigBuilder::new(&config, &server)
+            .with_rules(["client-only jei".parse().unwrap()])
+            .build()
+            .unwrap();
+
+        let layers: Vec<_> = compat
+            .custom_rules
+            .iter()
+            .map(|rule| (rule.rule_type.clone(), rule.source.clone()))
+            .collect();
+        assert_eq!(
+            layers,
+            [
+                (RuleType::ClientOnly, RuleSource::CommandLine),
+                (RuleType::Warn, RuleSource::ServerRule { index: 1 }),
+                (RuleType::AlwaysIgnore, RuleSource::ServerIgnoreList),
+                (
+                    RuleType::Tolerate(mc_link_compat::VersionTolerance::SameMinor),
+                    RuleSource::Profile {
+                        name: "pack".to_string(),
+                        index: 1,
+                    },
+                ),
+                (RuleType::AlwaysIgnore, RuleSource::GlobalIgnoreList),
+            ]
+        );
+    }
+
+    #[test]
+    fn explicit_profile_must_exist() {
+        let dir = tempfile::tempdir().unwrap();
+        let config = config_with_profile(dir.path());
+        let server = server();
+
+        let result = CompatConfigBuilder::new(&config, &server)
+            .with_profile(Some("missing"))
+            .build();
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn unparsable_server_rule_is_an_error() {
+        let dir = tempfile::tempdir().unwrap();
+        let config = config_with_profile(dir.path());
+        let mut server = server();
+        server.compatibility.custom_rules = vec!["remove jei".to_string()];
+
+        assert!(CompatConfigBuilder::new(&config, &server).build().is_err());
+    }
+}
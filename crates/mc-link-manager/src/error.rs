@@ -11,6 +11,18 @@ pub enum ManagerError {
     #[error(transparent)]
     Compat(#[from] mc_link_compat::CompatError),
 
+    /// Configuration error, such as a missing profile
+    #[error(transparent)]
+    Config(#[from] mc_link_config::ConfigError),
+
+    /// Mod hosting platform error
+    #[error(transparent)]
+    Platform(#[from] mc_link_platform::PlatformError),
+
+    /// Modpack error
+    #[error(transparent)]
+    Pack(#[from] mc_link_pack::PackError),
+
     /// Failed to execute update action
     #[error("Update action failed: {action} - {reason}")]
     UpdateFailed { action: String, reason: String },
@@ -25,6 +37,14 @@ pub enum ManagerError {
 
     #[error("Failed to perform file operation {operation} - {reason}")]
     FileOperationFailed { operation: String, reason: String },
+
+    /// A manifest is invalid or does not resolve
+    #[error("Invalid manifest: {reason}")]
+    InvalidManifest { reason: String },
+
+    /// A platform API key needed for the operation is missing
+    #[error("{platform} API key is not configured")]
+    MissingApiKey { platform: String },
 }
 
 /// Result type for manager operations.
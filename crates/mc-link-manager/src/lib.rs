@@ -6,13 +6,26 @@
 #![cfg_attr(not(debug_assertions), forbid(missing_docs))]
 
 pub mod actions;
+pub mod compat_config;
 pub mod error;
+pub mod lock;
 pub mod manager;
+pub mod manifest;
+pub mod packs;
+pub mod platforms;
 pub mod prelude;
+pub mod profiles;
 pub mod scanning;
 pub mod structure;
+pub mod updates;
 
 pub use actions::*;
+pub use compat_config::*;
 pub use error::*;
+pub use lock::*;
 pub use manager::*;
+pub use manifest::*;
+pub use packs::*;
+pub use profiles::*;
 pub use structure::*;
+pub use updates::*;
@@ -0,0 +1,494 @@
+//! Lockfiles recording the exact files of an instance, and drift from them.
+
+use crate::{ManagerError, MinecraftManager, Result};
+use mc_link_core::traits::PathExt;
+use mc_link_core::{GlobalJarCache, ServerConnector, ServerStructureCache};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::path::{Component, Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+use tracing::debug;
+
+/// Default name of a lockfile.
+pub const LOCK_FILE: &str = "mc-link.lock";
+
+/// Version of the lockfile format written.
+const LOCK_VERSION: u32 = 1;
+
+/// The files of an instance at the time it was locked.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Lockfile {
+    /// Version of the lockfile format
+    pub version: u32,
+    /// ID of the locked server
+    pub server_id: String,
+    /// When the instance was locked, in seconds since the Unix epoch
+    pub locked_at: u64,
+    /// Whether files under `config/` are recorded
+    #[serde(default)]
+    pub includes_config: bool,
+    /// Recorded files, sorted by path
+    #[serde(default, rename = "file")]
+    pub files: Vec<LockedFile>,
+}
+
+/// A file recorded in a lockfile.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LockedFile {
+    /// Path relative to the instance root, with forward slashes
+    pub path: String,
+    /// Hex-encoded SHA-256 of the file
+    pub sha256: String,
+    /// ID of the mod in the file, for mod jars
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub mod_id: Option<String>,
+    /// Version of the mod in the file, for mod jars
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub version: Option<String>,
+}
+
+impl Lockfile {
+    /// Reads a lockfile.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file cannot be read, is not a lockfile, or
+    /// was written in a format version this build does not know.
+    pub fn load(path: &Path) -> Result<Self> {
+        let content =
+            std::fs::read_to_string(path).map_err(|e| ManagerError::FileOperationFailed {
+                operation: format!("read lockfile {}", path.display()),
+                reason: e.to_string(),
+            })?;
+        let lockfile: Self =
+            toml::from_str(&content).map_err(|e| ManagerError::FileOperationFailed {
+                operation: format!("parse lockfile {}", path.display()),
+                reason: e.to_string(),
+            })?;
+        if lockfile.version != LOCK_VERSION {
+            return Err(ManagerError::FileOperationFailed {
+                operation: format!("parse lockfile {}", path.display()),
+                reason: format!(
+                    "unsupported lockfile version {} (expected {LOCK_VERSION})",
+                    lockfile.version
+                ),
+            });
+        }
+        Ok(lockfile)
+    }
+
+    /// Writes the lockfile to `path`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file cannot be written.
+    pub fn save(&self, path: &Path) -> Result<()> {
+        let content = toml::to_string(self).map_err(|e| ManagerError::FileOperationFailed {
+            operation: "serialize lockfile".to_string(),
+            reason: e.to_string(),
+        })?;
+        std::fs::write(path, content).map_err(|e| ManagerError::FileOperationFailed {
+            operation: format!("write lockfile {}", path.display()),
+            reason: e.to_string(),
+        })
+    }
+
+    /// Returns how `current` drifted from the locked files, sorted by path.
+    ///
+    /// A file removed and another added for the same mod are reported as
+    /// the mod's file being replaced.
+    pub fn diff(&self, current: &Lockfile) -> Vec<Drift> {
+        let locked: HashMap<&str, &LockedFile> =
+            self.files.iter().map(|f| (f.path.as_str(), f)).collect();
+        let now: HashMap<&str, &LockedFile> =
+            current.files.iter().map(|f| (f.path.as_str(), f)).collect();
+
+        let mut drift = Vec::new();
+        for file in &current.files {
+            if let Some(locked_file) = locked.get(file.path.as_str())
+                && locked_file.sha256 != file.sha256
+            {
+                drift.push(Drift::Modified {
+                    locked: (*locked_file).clone(),
+                    current: file.clone(),
+                });
+            }
+        }
+
+        let mut added: Vec<&LockedFile> = current
+            .files
+            .iter()
+            .filter(|f| !locked.contains_key(f.path.as_str()))
+            .collect();
+        for file in self
+            .files
+            .iter()
+            .filter(|f| !now.contains_key(f.path.as_str()))
+        {
+            let replacement = file
+                .mod_id
+                .as_ref()
+                .and_then(|mod_id| added.iter().position(|a| a.mod_id.as_ref() == Some(mod_id)));
+            match replacement {
+                Some(i) => drift.push(Drift::Replaced {
+                    locked: file.clone(),
+                    current: added.remove(i).clone(),
+                }),
+                None => drift.push(Drift::Removed { file: file.clone() }),
+            }
+        }
+        drift.extend(
+            added
+                .into_iter()
+                .map(|file| Drift::Added { file: file.clone() }),
+        );
+
+        drift.sort_by(|a, b| a.path().cmp(b.path()));
+        drift
+    }
+}
+
+/// A difference between a lockfile and the instance.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum Drift {
+    /// A file that is not in the lockfile
+    Added { file: LockedFile },
+    /// A locked file that is gone
+    Removed { file: LockedFile },
+    /// A locked file whose contents no longer match its hash
+    Modified {
+        locked: LockedFile,
+        current: LockedFile,
+    },
+    /// A locked mod jar that is gone, with another file of the same mod in its place
+    Replaced {
+        locked: LockedFile,
+        current: LockedFile,
+    },
+}
+
+impl Drift {
+    /// Returns the path of the file on the instance, or of the locked file if it is gone.
+    pub fn path(&self) -> &str {
+        match self {
+            Drift::Added { file } | Drift::Removed { file } => &file.path,
+            Drift::Modified { current, .. } | Drift::Replaced { current, .. } => &current.path,
+        }
+    }
+}
+
+/// How an instance drifted from its lockfile.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DriftReport {
+    /// ID of the locked server
+    pub server_id: String,
+    /// When the instance was locked, in seconds since the Unix epoch
+    pub locked_at: u64,
+    /// Differences found, sorted by path
+    pub drift: Vec<Drift>,
+}
+
+impl DriftReport {
+    /// Returns whether the instance matches its lockfile.
+    pub fn is_clean(&self) -> bool {
+        self.drift.is_empty()
+    }
+}
+
+impl<'a, C> MinecraftManager<'a, C>
+where
+    C: ServerConnector + Send + Sync + 'static,
+{
+    /// Returns the structure cache of the server, whose jar hashes are the
+    /// SHA-256 hashes [`lock`](Self::lock) records for the scanned mods.
+    pub fn structure_cache(&self) -> Option<&ServerStructureCache> {
+        self.structure_cache.as_ref()
+    }
+
+    /// Records every file under `mods/` of the scanned instance, and under
+    /// `config/` if `include_config` is set.
+    ///
+    /// Mod jars are recorded with the SHA-256 the scan computed, along with
+    /// their mod ID and version. Other files are downloaded and hashed.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the instance has not been scanned, a directory
+    /// cannot be listed, or a file cannot be downloaded. A listing error is
+    /// never taken for missing files, which would show up as drift.
+    pub async fn lock(&mut self, include_config: bool) -> Result<Lockfile> {
+        if !self.connector.is_connected().await {
+            self.connector.connect().await?;
+        }
+        let structure = self
+            .structure()
+            .ok_or_else(|| ManagerError::InvalidStructure {
+                reason: "instance has not been scanned".to_string(),
+            })?;
+
+        let mut files = Vec::new();
+        let mut hashed = HashSet::new();
+        for mod_info in &structure.mods.mods {
+            let Some(sha256) = &mod_info.hashes.sha256 else {
+                continue;
+            };
+            let path = normalize(&mod_info.file_path);
+            files.push(LockedFile {
+                path: path.to_slash_lossy(),
+                sha256: sha256.clone(),
+                mod_id: Some(mod_info.id.clone()),
+                version: mod_info.version.clone(),
+            });
+            hashed.insert(path);
+        }
+
+        let mut dirs = vec![structure.mods.path.clone()];
+        if include_config && structure.config.exists {
+            dirs.push(structure.config.path.clone());
+        }
+        let temp_dir = self.create_temp_directory("mc-link-lock").await?;
+        let result: Result<()> = async {
+            for dir in &dirs {
+                for remote_path in self.list_files_recursive(dir).await? {
+                    if hashed.contains(&normalize(&remote_path)) {
+                        continue;
+                    }
+                    files.push(self.hash_remote_file(remote_path, &temp_dir).await?);
+                }
+            }
+            Ok(())
+        }
+        .await;
+        self.cleanup_temp_directory(&temp_dir).await;
+        result?;
+
+        files.sort_by(|a, b| a.path.cmp(&b.path));
+        debug!(files = files.len(), include_config, "Locked instance");
+        Ok(Lockfile {
+            version: LOCK_VERSION,
+            server_id: self
+                .server_config
+                .map(|config| config.id.clone())
+                .unwrap_or_default(),
+            locked_at: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map(|d| d.as_secs())
+                .unwrap_or_default(),
+            includes_config: include_config,
+            files,
+        })
+    }
+
+    /// Compares the scanned instance with a lockfile.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the instance has not been scanned, a directory
+    /// cannot be listed, or a file cannot be downloaded.
+    pub async fn verify(&mut self, lockfile: &Lockfile) -> Result<DriftReport> {
+        let current = self.lock(lockfile.includes_config).await?;
+        Ok(DriftReport {
+            server_id: lockfile.server_id.clone(),
+            locked_at: lockfile.locked_at,
+            drift: lockfile.diff(&current),
+        })
+    }
+
+    /// Downloads a file of the instance into `temp_dir` and records its hash.
+    async fn hash_remote_file(&self, remote_path: PathBuf, temp_dir: &Path) -> Result<LockedFile> {
+        let local_path = temp_dir.join(&remote_path);
+        self.connector
+            .download_file(&remote_path, &local_path, None)
+            .await
+            .map_err(|e| ManagerError::FileOperationFailed {
+                operation: format!("download {} for hashing", remote_path.display()),
+                reason: e.to_string(),
+            })?;
+        Ok(LockedFile {
+            path: normalize(&remote_path).to_slash_lossy(),
+            sha256: GlobalJarCache::compute_file_hash(&local_path)?,
+            mod_id: None,
+            version: None,
+        })
+    }
+}
+
+/// Drops `.` components, so the scan's paths and listed paths compare equal.
+fn normalize(path: &Path) -> PathBuf {
+    path.components()
+        .filter(|component| !matches!(component, Component::CurDir))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::MinecraftStructure;
+    use mc_link_config::LocalConnection;
+    use mc_link_connector::LocalConnector;
+    use mc_link_core::{FileHashes, ModInfo};
+
+    fn lockfile(files: Vec<LockedFile>) -> Lockfile {
+        Lockfile {
+            version: LOCK_VERSION,
+            server_id: "survival".to_string(),
+            locked_at: 1_700_000_000,
+            includes_config: true,
+            files,
+        }
+    }
+
+    fn file(path: &str, sha256: &str) -> LockedFile {
+        LockedFile {
+            path: path.to_string(),
+            sha256: sha256.to_string(),
+            mod_id: None,
+            version: None,
+        }
+    }
+
+    fn jar(path: &str, sha256: &str, mod_id: &str, version: &str) -> LockedFile {
+        LockedFile {
+            mod_id: Some(mod_id.to_string()),
+            version: Some(version.to_string()),
+            ..file(path, sha256)
+        }
+    }
+
+    #[test]
+    fn reports_added_removed_and_modified_files_by_path() {
+        let locked = lockfile(vec![
+            file("config/create.toml", "aa"),
+            file("config/jei.toml", "bb"),
+            jar("mods/spark.jar", "cc", "spark", "1.10"),
+        ]);
+        let current = lockfile(vec![
+            file("config/create.toml", "ab"),
+            jar("mods/ae2.jar", "dd", "ae2", "19.0"),
+            jar("mods/spark.jar", "cc", "spark", "1.10"),
+        ]);
+
+        let drift = locked.diff(&current);
+        let paths: Vec<_> = drift.iter().map(Drift::path).collect();
+        assert_eq!(
+            paths,
+            ["config/create.toml", "config/jei.toml", "mods/ae2.jar"]
+        );
+        assert!(matches!(&drift[0], Drift::Modified { locked, current }
+            if locked.sha256 == "aa" && current.sha256 == "ab"));
+        assert!(matches!(&drift[1], Drift::Removed { .. }));
+        assert!(matches!(&drift[2], Drift::Added { .. }));
+        assert!(locked.diff(&locked).is_empty());
+    }
+
+    #[test]
+    fn pairs_jars_of_the_same_mod_as_replaced() {
+        let locked = lockfile(vec![jar("mods/jei-19.20.jar", "aa", "jei", "19.20")]);
+        let current = lockfile(vec![jar("mods/jei-19.21.jar", "bb", "jei", "19.21")]);
+
+        let drift = locked.diff(&current);
+        let [Drift::Replaced { locked, current }] = drift.as_slice() else {
+            panic!("expected a single replacement, got {drift:?}");
+        };
+        assert_eq!(locked.path, "mods/jei-19.20.jar");
+        assert_eq!(current.path, "mods/jei-19.21.jar");
+    }
+
+    #[test]
+    fn round_trips_through_toml() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join(LOCK_FILE);
+        let locked = lockfile(vec![
+            file("config/create.toml", "aa"),
+            jar("mods/jei.jar", "bb", "jei", "19.21"),
+        ]);
+
+        locked.save(&path).unwrap();
+        let loaded = Lockfile::load(&path).unwrap();
+        assert_eq!(loaded.server_id, "survival");
+        assert_eq!(loaded.locked_at, locked.locked_at);
+        assert!(loaded.includes_config);
+        assert_eq!(loaded.files, locked.files);
+    }
+
+    #[test]
+    fn rejects_unknown_format_version() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join(LOCK_FILE);
+        Lockfile {
+            version: LOCK_VERSION + 1,
+            ..lockfile(Vec::new())
+        }
+        .save(&path)
+        .unwrap();
+
+        assert!(Lockfile::load(&path).is_err());
+    }
+
+    /// Returns a manager for the instance in `dir`, scanned as holding
+    /// `mods/create.jar` with the SHA-256 `scanned`.
+    async fn scanned_instance(dir: &Path) -> MinecraftManager<'static, LocalConnector> {
+        let mut connector = LocalConnector::new(&LocalConnection {
+            path: dir.display().to_string(),
+        });
+        connector.connect().await.unwrap();
+        let mut manager = MinecraftManager::new(connector);
+        let mut structure = MinecraftStructure::new(PathBuf::from("."));
+        structure.mods.mods = vec![ModInfo {
+            version: Some("6.0.0".to_string()),
+            hashes: FileHashes {
+                sha256: Some("scanned".to_string()),
+                ..FileHashes::default()
+            },
+            ..ModInfo::new("create", "./mods/create.jar")
+        }];
+        structure.mods.exists = true;
+        structure.config.exists = true;
+        manager.structure = Some(structure);
+        manager
+    }
+
+    #[tokio::test]
+    async fn locks_scanned_jars_once_and_hashes_other_files() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir_all(dir.path().join("mods")).unwrap();
+        std::fs::create_dir_all(dir.path().join("config")).unwrap();
+        std::fs::write(dir.path().join("mods/create.jar"), "create").unwrap();
+        std::fs::write(dir.path().join("mods/patch.jar"), "patch").unwrap();
+        std::fs::write(dir.path().join("config/create.toml"), "").unwrap();
+        let mut manager = scanned_instance(dir.path()).await;
+
+        let lockfile = manager.lock(true).await.unwrap();
+
+        let paths: Vec<_> = lockfile.files.iter().map(|f| f.path.as_str()).collect();
+        assert_eq!(
+            paths,
+            ["config/create.toml", "mods/create.jar", "mods/patch.jar"]
+        );
+        let create = &lockfile.files[1];
+        assert_eq!(create.sha256, "scanned");
+        assert_eq!(create.mod_id.as_deref(), Some("create"));
+        assert_eq!(
+            lockfile.files[2].sha256,
+            GlobalJarCache::compute_file_hash(&dir.path().join("mods/patch.jar")).unwrap()
+        );
+        assert!(lockfile.files[2].mod_id.is_none());
+
+        let without_config = manager.lock(false).await.unwrap();
+        assert_eq!(without_config.files.len(), 2);
+    }
+
+    #[tokio::test]
+    async fn fails_to_lock_directory_that_cannot_be_listed() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir_all(dir.path().join("mods")).unwrap();
+        std::fs::write(dir.path().join("mods/create.jar"), "create").unwrap();
+        // The scan saw a config directory, but it can no longer be listed
+        std::fs::write(dir.path().join("config"), "").unwrap();
+        let mut manager = scanned_instance(dir.path()).await;
+
+        assert!(manager.lock(true).await.is_err());
+        assert!(manager.lock(false).await.is_ok());
+    }
+}
@@ -1,12 +1,22 @@
-use crate::{ManagerError, MinecraftStructure, Result, SyncAction, SyncPlan, SyncTarget};
-use mc_link_compat::{CompatConfig, check_compatibility};
-use mc_link_config::{CONFIG_MANAGER, ConnectionType, ServerConfig};
+use crate::{
+    BlockedAction, InstalledPack, ManagerError, MinecraftStructure, Result, ScanFinding,
+    SyncAction, SyncPlan, SyncTarget,
+};
+use mc_link_compat::{
+    CompatConfig, DowngradePolicy, GameEnvironment, VersionDirection, check_compatibility,
+    check_environment, deduplicate, find_conflicts, find_duplicates,
+};
+use mc_link_config::{CONFIG_MANAGER, ConnectionType, ServerConfig, ServerSettings};
 use mc_link_connector::{Connector, FtpConnector, LocalConnector};
 use mc_link_core::{
-    GlobalJarCache, ProgressCallback, ProgressReporter, ProgressStage, ProgressUpdate,
-    ServerConnector, ServerStructureCache,
+    GlobalJarCache, ModInfo, ModLoader, ProgressCallback, ProgressReporter, ProgressStage,
+    ProgressUpdate, ServerConnector, ServerStructureCache,
+};
+use mc_link_platform::{CurseForgeClient, ModrinthClient};
+use std::{
+    collections::{HashMap, HashSet},
+    path::{Path, PathBuf},
 };
-use std::{collections::HashMap, path::PathBuf};
 use tracing::{debug, info};
 
 /// High-level manager for Minecraft server instances.
@@ -20,9 +30,9 @@ where
 {
     /// The underlying connector for server communication
     pub(crate) connector: C,
-    server_config: Option<&'a ServerConfig>,
+    pub(crate) server_config: Option<&'a ServerConfig>,
     /// Cached server structure (None = not scanned yet)
-    structure: Option<MinecraftStructure>,
+    pub(crate) structure: Option<MinecraftStructure>,
     /// Whether to enable parallel processing (default: true)
     pub(crate) parallel_enabled: bool,
     /// Global JAR cache for mod metadata
@@ -31,6 +41,12 @@ where
     pub(crate) structure_cache: Option<ServerStructureCache>,
     /// Progress reporter for long-running operations
     pub(crate) progress_reporter: Option<ProgressReporter>,
+    /// Modrinth client for identifying mods by hash
+    pub(crate) modrinth: Option<ModrinthClient>,
+    /// CurseForge client for identifying mods by fingerprint
+    pub(crate) curseforge: Option<CurseForgeClient>,
+    /// Modpack the instance was installed from
+    pub(crate) pack: Option<InstalledPack>,
 }
 
 impl<'a> MinecraftManager<'a, Connector> {
@@ -55,6 +71,9 @@ impl<'a> MinecraftManager<'a, Connector> {
             ServerStructureCache::load(server_config.id.clone(), &CONFIG_MANAGER.cache_dir())
                 .unwrap_or_else(|_| ServerStructureCache::new(server_config.id.clone()));
 
+        let modrinth = CONFIG_MANAGER.manager.modrinth_lookup.then(modrinth_client);
+        let curseforge = curseforge_client();
+
         Self {
             connector,
             server_config: Some(server_config),
@@ -63,6 +82,9 @@ impl<'a> MinecraftManager<'a, Connector> {
             jar_cache: None,
             structure_cache: Some(structure_cache),
             progress_reporter: None,
+            modrinth,
+            curseforge,
+            pack: None,
         }
     }
 }
@@ -96,6 +118,9 @@ where
             jar_cache: None,
             structure_cache: None,
             progress_reporter: None,
+            modrinth: None,
+            curseforge: None,
+            pack: None,
         }
     }
 
@@ -109,6 +134,9 @@ where
             jar_cache: None,
             structure_cache: None,
             progress_reporter: None,
+            modrinth: None,
+            curseforge: None,
+            pack: None,
         }
     }
 
@@ -136,6 +164,18 @@ where
         self
     }
 
+    /// Identifies scanned mods by hash on Modrinth, filling gaps in their metadata.
+    pub fn with_modrinth(mut self, client: ModrinthClient) -> Self {
+        self.modrinth = Some(client);
+        self
+    }
+
+    /// Identifies scanned mods by fingerprint on CurseForge, filling gaps in their metadata.
+    pub fn with_curseforge(mut self, client: CurseForgeClient) -> Self {
+        self.curseforge = Some(client);
+        self
+    }
+
     /// Reports progress if a reporter is set.
     pub(crate) fn report_progress(&self, update: ProgressUpdate) {
         if let Some(ref reporter) = self.progress_reporter {
@@ -205,6 +245,26 @@ where
             );
         }
 
+        structure.findings.extend(
+            find_duplicates(&structure.mods.mods)
+                .into_iter()
+                .map(ScanFinding::Duplicate),
+        );
+        structure.findings.extend(
+            find_conflicts(&structure.mods.mods)
+                .into_iter()
+                .map(ScanFinding::Conflict),
+        );
+        if let Some(server_config) = self.server_config {
+            let env = game_environment(&server_config.settings)
+                .with_detected_loader(&structure.mods.mods);
+            structure.findings.extend(
+                check_environment(&structure.mods.mods, &env)
+                    .into_iter()
+                    .map(ScanFinding::Environment),
+            );
+        }
+
         self.report_progress(ProgressUpdate::new(ProgressStage::Listing, 90, 100));
 
         // Scan other directories
@@ -224,7 +284,7 @@ where
                 .mods
                 .mods
                 .iter()
-                .filter_map(|mod_info| GlobalJarCache::compute_file_hash(&mod_info.file_path).ok())
+                .filter_map(|mod_info| mod_info.hashes.sha256.clone())
                 .collect();
 
             let mut directory_structure = HashMap::new();
@@ -249,6 +309,13 @@ where
             }
         }
 
+        // Mods of an installed pack are uploaded from where the pack was installed
+        if let Some(pack) = &self.pack {
+            for mod_info in &mut structure.mods.mods {
+                mod_info.file_path = pack.dir.join(&mod_info.file_path);
+            }
+        }
+
         self.report_progress(ProgressUpdate::new(ProgressStage::Completed, 100, 100));
 
         self.structure = Some(structure);
@@ -301,6 +368,21 @@ where
             other_structure.mods.mods.len()
         );
 
+        // Fill in instance environments the caller did not set from the
+        // server configs or packs, so loader mismatches are caught
+        let mut compat_config = compat_config.clone();
+        if let Some(environment) = self.environment()
+            && compat_config.client_environment == GameEnvironment::default()
+        {
+            compat_config.client_environment = environment;
+        }
+        if let Some(environment) = other.environment()
+            && compat_config.server_environment == GameEnvironment::default()
+        {
+            compat_config.server_environment = environment;
+        }
+        let compat_config = &compat_config;
+
         let compat_result = check_compatibility(
             &self_structure.mods.mods,
             &other_structure.mods.mods,
@@ -337,17 +419,64 @@ where
         // Build sync plan based on compatibility results
         let mut plan = SyncPlan::new();
         plan.will_be_compatible = compat_result.is_compatible;
-
-        // Handle missing mods on target (other)
+        plan.warnings = compat_result.warnings.clone();
+        plan.decisions = compat_result.decisions.clone();
+
+        plan.loader_mismatch = compat_result.loader_mismatch.clone();
+        plan.conflicts = compat_result
+            .client_conflicts
+            .iter()
+            .chain(&compat_result.server_conflicts)
+            .cloned()
+            .collect();
+
+        // Use the same aliased IDs and copy of duplicated mods that the
+        // compatibility check compared
+        let aliased_source = compat_config.apply_aliases(&self_structure.mods.mods);
+        let aliased_target = compat_config.apply_aliases(&other_structure.mods.mods);
+        let source_mods = deduplicate(&aliased_source);
+        let target_mods = deduplicate(&aliased_target);
+
+        // Pair files with identical contents under a new name. IDs may differ
+        // when they were derived from the file name, so the pair replaces the
+        // add and remove the compatibility check asked for.
+        let renames = plan_renames(&source_mods, &aliased_target, &compat_result.ignored_mods);
+        let renamed_source: HashSet<&str> = renames.iter().map(|r| r.source_id).collect();
+        let renamed_target: HashSet<&str> = renames.iter().map(|r| r.target_id).collect();
+
+        // Handle missing mods on target (other), withholding those the
+        // target cannot load or that would introduce a declared incompatibility
         for missing_mod in &compat_result.missing_on_server {
-            plan.add_action(SyncAction::AddMod {
+            if renamed_source.contains(missing_mod.id.as_str()) {
+                continue;
+            }
+            let action = SyncAction::AddMod {
                 mod_info: missing_mod.clone(),
                 target: SyncTarget::Server,
-            });
+            };
+            let unloadable = compat_result
+                .unloadable_additions
+                .iter()
+                .find(|issue| issue.mod_id == missing_mod.id)
+                .map(ToString::to_string);
+            let conflict = || {
+                compat_result
+                    .blocked_additions
+                    .iter()
+                    .find(|c| c.involves(&missing_mod.id))
+                    .map(ToString::to_string)
+            };
+            match unloadable.or_else(conflict) {
+                Some(reason) => plan.blocked.push(BlockedAction { action, reason }),
+                None => plan.add_action(action),
+            }
         }
 
         // Handle missing mods on source (self) - these should be removed from target
         for extra_mod in &compat_result.missing_on_client {
+            if renamed_target.contains(extra_mod.id.as_str()) {
+                continue;
+            }
             plan.add_action(SyncAction::RemoveMod {
                 mod_id: extra_mod.name.clone(), // Use the mod name as ID
                 mod_info: extra_mod.clone(),
@@ -358,29 +487,53 @@ where
         // Handle version mismatches
         for version_mismatch in &compat_result.version_mismatches {
             // Find the source mod for the update
-            if let Some(source_mod) = self_structure
-                .mods
-                .mods
-                .iter()
-                .find(|m| m.name == version_mismatch.mod_name)
-            {
-                if let Some(target_mod) = other_structure
-                    .mods
-                    .mods
-                    .iter()
-                    .find(|m| m.name == version_mismatch.mod_name)
-                {
+            if let Some(source_mod) = source_mods.get(version_mismatch.mod_id.as_str()) {
+                if let Some(target_mod) = target_mods.get(version_mismatch.mod_id.as_str()) {
+                    if version_mismatch.direction == VersionDirection::Downgrade
+                        && compat_config.downgrade_policy == DowngradePolicy::Refuse
+                    {
+                        plan.add_action(SyncAction::KeepAsIs {
+                            mod_id: version_mismatch.mod_id.clone(),
+                            reason: format!(
+                                "Downgrade from {} to {} refused by policy",
+                                version_mismatch.server_version, version_mismatch.client_version
+                            ),
+                        });
+                        continue;
+                    }
+
                     plan.add_action(SyncAction::UpdateMod {
                         mod_id: version_mismatch.mod_id.clone(),
                         from_version: version_mismatch.server_version.clone(),
                         to_version: version_mismatch.client_version.clone(),
                         current_path: target_mod.file_path.clone(),
                         new_path: source_mod.file_path.clone(),
+                        direction: version_mismatch.direction,
                     });
                 }
             }
         }
 
+        // Handle renamed files, withholding renames onto an existing file
+        for rename in renames {
+            let occupied = aliased_target
+                .iter()
+                .any(|m| m.file_path == rename.new_path);
+            let action = SyncAction::RenameMod {
+                mod_id: rename.source_id.to_string(),
+                current_path: rename.current_path,
+                new_path: rename.new_path,
+            };
+            if occupied {
+                plan.blocked.push(BlockedAction {
+                    action,
+                    reason: "Another file already has the new name".to_string(),
+                });
+            } else {
+                plan.add_action(action);
+            }
+        }
+
         // Handle ignored mods
         for ignored_mod_id in &compat_result.ignored_mods {
             plan.add_action(SyncAction::KeepAsIs {
@@ -457,14 +610,10 @@ where
                     // Replace the mod file
                     let remote_path =
                         PathBuf::from("mods").join(current_path.file_name().unwrap_or_default());
-
-                    // Delete old version
-                    let _ = self.connector.delete_file(&remote_path).await;
-
-                    // Upload new version
                     let new_remote_path =
                         PathBuf::from("mods").join(new_path.file_name().unwrap_or_default());
 
+                    // Upload the new version first so a failed upload leaves the old one in place
                     self.connector
                         .upload_file(
                             new_path,
@@ -476,6 +625,31 @@ where
                             action: format!("Update mod {}", mod_id),
                             reason: e.to_string(),
                         })?;
+
+                    // A renamed jar would otherwise leave both versions installed
+                    if new_remote_path != remote_path {
+                        self.connector
+                            .delete_file(&remote_path)
+                            .await
+                            .map_err(|e| ManagerError::UpdateFailed {
+                                action: format!("Remove old version of mod {}", mod_id),
+                                reason: e.to_string(),
+                            })?;
+                    }
+                }
+
+                SyncAction::RenameMod {
+                    mod_id,
+                    current_path,
+                    new_path,
+                } => {
+                    self.connector
+                        .rename_file(current_path, new_path)
+                        .await
+                        .map_err(|e| ManagerError::UpdateFailed {
+                            action: format!("Rename mod {}", mod_id),
+                            reason: e.to_string(),
+                        })?;
                 }
 
                 SyncAction::KeepAsIs { .. } => {
@@ -505,6 +679,44 @@ where
         Ok(())
     }
 
+    /// Adds actions to `plan` that remove stale duplicate copies from this instance.
+    ///
+    /// For each mod installed more than once, the copy with the highest version
+    /// is kept. Does nothing if the instance has not been scanned.
+    pub fn plan_duplicate_removal(&self, plan: &mut SyncPlan) {
+        let Some(structure) = &self.structure else {
+            return;
+        };
+
+        // A file can appear in both an ID group and a content group
+        let mut removed = std::collections::HashSet::new();
+        for finding in &structure.findings {
+            let ScanFinding::Duplicate(group) = finding else {
+                continue;
+            };
+            for stale in group.stale() {
+                if !removed.insert(&stale.file_path) {
+                    continue;
+                }
+                plan.add_action(SyncAction::RemoveMod {
+                    mod_id: stale.id.clone(),
+                    mod_info: stale.clone(),
+                    target: SyncTarget::Server,
+                });
+            }
+        }
+    }
+
+    /// Returns the Minecraft version and loader of the instance, from its
+    /// server config or the pack it was installed from.
+    pub fn environment(&self) -> Option<GameEnvironment> {
+        match (self.server_config, &self.pack) {
+            (Some(server_config), _) => Some(game_environment(&server_config.settings)),
+            (None, Some(pack)) => Some(pack.environment.clone()),
+            (None, None) => None,
+        }
+    }
+
     /// Returns a reference to the cached structure, if available.
     pub fn structure(&self) -> Option<&MinecraftStructure> {
         self.structure.as_ref()
@@ -533,3 +745,398 @@ where
             .finish_non_exhaustive()
     }
 }
+
+/// Creates a Modrinth client for the API configured in the manager settings.
+pub fn modrinth_client() -> ModrinthClient {
+    let config = &CONFIG_MANAGER.manager;
+    let client = ModrinthClient::new();
+    match &config.modrinth_api_url {
+        Some(url) => client.with_base_url(url),
+        None => client,
+    }
+}
+
+/// Creates a CurseForge client if an API key is configured in the manager settings.
+pub fn curseforge_client() -> Option<CurseForgeClient> {
+    let config = &CONFIG_MANAGER.manager;
+    let client = CurseForgeClient::new(config.curseforge_api_key.as_ref()?);
+    Some(match &config.curseforge_api_url {
+        Some(url) => client.with_base_url(url),
+        None => client,
+    })
+}
+
+/// A target file holding the same contents as a source file under another name.
+struct PlannedRename<'a> {
+    source_id: &'a str,
+    target_id: &'a str,
+    current_path: PathBuf,
+    new_path: PathBuf,
+}
+
+/// Pairs source mods with target files of identical contents but another name.
+///
+/// Pairs are found by SHA-256 regardless of mod ID, in file path order so
+/// that plans are stable. Target files are paired at most once, and files
+/// already present under the source name need no rename.
+fn plan_renames<'a>(
+    source_mods: &HashMap<&'a str, &'a ModInfo>,
+    target_mods: &'a [ModInfo],
+    ignored_mods: &[String],
+) -> Vec<PlannedRename<'a>> {
+    let mut sources: Vec<&ModInfo> = source_mods.values().copied().collect();
+    sources.sort_by(|a, b| a.file_path.cmp(&b.file_path));
+    let mut targets: Vec<&ModInfo> = target_mods.iter().collect();
+    targets.sort_by(|a, b| a.file_path.cmp(&b.file_path));
+
+    let mut paired: HashSet<&Path> = HashSet::new();
+    let mut renames = Vec::new();
+    for source_mod in sources {
+        if ignored_mods.contains(&source_mod.id) {
+            continue;
+        }
+        let Some(source_hash) = source_mod.hashes.sha256.as_deref() else {
+            continue;
+        };
+        let Some(file_name) = source_mod.file_path.file_name() else {
+            continue;
+        };
+        let identical: Vec<&ModInfo> = targets
+            .iter()
+            .copied()
+            .filter(|m| m.hashes.sha256.as_deref() == Some(source_hash))
+            .filter(|m| !paired.contains(m.file_path.as_path()))
+            .collect();
+        if identical
+            .iter()
+            .any(|m| m.file_path.file_name() == Some(file_name))
+        {
+            continue;
+        }
+        let Some(target_mod) = identical
+            .iter()
+            .find(|m| m.id == source_mod.id)
+            .or(identical.first())
+        else {
+            continue;
+        };
+        paired.insert(&target_mod.file_path);
+        renames.push(PlannedRename {
+            source_id: &source_mod.id,
+            target_id: &target_mod.id,
+            current_path: target_mod.file_path.clone(),
+            new_path: target_mod.file_path.with_file_name(file_name),
+        });
+    }
+    renames
+}
+
+/// Builds the game environment described by a server's settings.
+///
+/// Only a loader set in the settings is used; an unset or unknown loader is
+/// left for [`GameEnvironment::with_detected_loader`] to fill in.
+pub fn game_environment(settings: &ServerSettings) -> GameEnvironment {
+    use mc_link_config::ModLoader as ConfigLoader;
+
+    let loader = settings
+        .mod_loader
+        .as_ref()
+        .and_then(|loader| match loader {
+            ConfigLoader::NeoForge => Some(ModLoader::NeoForge),
+            ConfigLoader::Fabric => Some(ModLoader::Fabric),
+            ConfigLoader::Forge => Some(ModLoader::Forge),
+            ConfigLoader::Vanilla | ConfigLoader::Unknown => None,
+        });
+    GameEnvironment::new(settings.minecraft_version.clone(), loader)
+        .with_loader_version(settings.loader_version.clone())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use mc_link_core::{CoreError, FileHashes, ModSide, RemoteEntry, ServerInfo};
+    use std::sync::{Arc, Mutex};
+
+    /// Connector that records the file operations performed on it.
+    #[derive(Default)]
+    struct RecordingConnector {
+        operations: Arc<Mutex<Vec<String>>>,
+        fail_uploads: bool,
+    }
+
+    impl RecordingConnector {
+        fn record(&self, operation: String) {
+            self.operations.lock().unwrap().push(operation);
+        }
+    }
+
+    impl ServerConnector for RecordingConnector {
+        async fn connect(&mut self) -> mc_link_core::Result<()> {
+            Ok(())
+        }
+
+        async fn disconnect(&mut self) -> mc_link_core::Result<()> {
+            Ok(())
+        }
+
+        async fn is_connected(&self) -> bool {
+            true
+        }
+
+        async fn get_server_info(&self) -> mc_link_core::Result<ServerInfo> {
+            Err(unsupported("get_server_info"))
+        }
+
+        async fn upload_file(
+            &self,
+            _local_path: &PathBuf,
+            remote_path: &PathBuf,
+            _progress: Option<ProgressCallback>,
+        ) -> mc_link_core::Result<()> {
+            if self.fail_uploads {
+                return Err(CoreError::NetworkError {
+                    message: "upload refused".to_string(),
+                });
+            }
+            self.record(format!("upload {}", remote_path.display()));
+            Ok(())
+        }
+
+        async fn download_file(
+            &self,
+            _remote_path: &PathBuf,
+            _local_path: &PathBuf,
+            _progress: Option<ProgressCallback>,
+        ) -> mc_link_core::Result<()> {
+            Err(unsupported("download_file"))
+        }
+
+        async fn list_files(&self, _remote_path: &PathBuf) -> mc_link_core::Result<Vec<PathBuf>> {
+            Ok(Vec::new())
+        }
+
+        async fn list_entries(
+            &self,
+            _remote_path: &PathBuf,
+        ) -> mc_link_core::Result<Vec<RemoteEntry>> {
+            Ok(Vec::new())
+        }
+
+        async fn delete_file(&self, remote_path: &PathBuf) -> mc_link_core::Result<()> {
+            self.record(format!("delete {}", remote_path.display()));
+            Ok(())
+        }
+
+        async fn rename_file(&self, from: &PathBuf, to: &PathBuf) -> mc_link_core::Result<()> {
+            self.record(format!("rename {} {}", from.display(), to.display()));
+            Ok(())
+        }
+
+        async fn create_directory(&self, _remote_path: &PathBuf) -> mc_link_core::Result<()> {
+            Ok(())
+        }
+
+        async fn execute_command(&self, _command: &str) -> mc_link_core::Result<String> {
+            Err(unsupported("execute_command"))
+        }
+    }
+
+    fn unsupported(operation: &str) -> CoreError {
+        CoreError::ServerOperationFailed {
+            operation: operation.to_string(),
+            reason: "not supported by the recording connector".to_string(),
+        }
+    }
+
+    fn jar(id: &str, version: &str, path: &str, sha256: &str) -> ModInfo {
+        ModInfo {
+            version: Some(version.to_string()),
+            side: ModSide::Both,
+            loader: ModLoader::NeoForge,
+            hashes: FileHashes {
+                sha256: Some(sha256.to_string()),
+                ..FileHashes::default()
+            },
+            ..ModInfo::new(id, path)
+        }
+    }
+
+    fn scanned<'a>(
+        connector: RecordingConnector,
+        mods: Vec<ModInfo>,
+    ) -> MinecraftManager<'a, RecordingConnector> {
+        let mut manager = MinecraftManager::new(connector);
+        let mut structure = MinecraftStructure::new(PathBuf::from("."));
+        structure.mods.mods = mods;
+        structure.mods.exists = true;
+        manager.structure = Some(structure);
+        manager
+    }
+
+    #[tokio::test]
+    async fn renames_identical_jar_under_new_name() {
+        let mut source = scanned(
+            RecordingConnector::default(),
+            vec![jar("jei", "19.21.0", "/packs/a/mods/jei-19.21.0.jar", "aa")],
+        );
+        let target_connector = RecordingConnector::default();
+        let operations = target_connector.operations.clone();
+        let mut target = scanned(
+            target_connector,
+            vec![jar("jei", "19.21.0", "mods/jei.jar", "aa")],
+        );
+
+        let plan = source
+            .compare_with(&mut target, &CompatConfig::default())
+            .await
+            .unwrap();
+        let [
+            SyncAction::RenameMod {
+                mod_id,
+                current_path,
+                new_path,
+            },
+        ] = plan.actions.as_slice()
+        else {
+            panic!("expected a single rename, got {:?}", plan.actions);
+        };
+        assert_eq!(mod_id, "jei");
+        assert_eq!(current_path, &PathBuf::from("mods/jei.jar"));
+        assert_eq!(new_path, &PathBuf::from("mods/jei-19.21.0.jar"));
+
+        target.execute_sync_plan(&plan, None).await.unwrap();
+        assert_eq!(
+            *operations.lock().unwrap(),
+            ["rename mods/jei.jar mods/jei-19.21.0.jar"]
+        );
+    }
+
+    #[tokio::test]
+    async fn renames_file_whose_id_came_from_its_name() {
+        let mut source = scanned(
+            RecordingConnector::default(),
+            vec![jar(
+                "mystery-1.1",
+                "1.1",
+                "/packs/a/mods/mystery-1.1.jar",
+                "aa",
+            )],
+        );
+        let mut target = scanned(
+            RecordingConnector::default(),
+            vec![jar("mystery", "1.1", "mods/mystery.jar", "aa")],
+        );
+
+        let plan = source
+            .compare_with(&mut target, &CompatConfig::default())
+            .await
+            .unwrap();
+        let [SyncAction::RenameMod { new_path, .. }] = plan.actions.as_slice() else {
+            panic!("expected a single rename, got {:?}", plan.actions);
+        };
+        assert_eq!(new_path, &PathBuf::from("mods/mystery-1.1.jar"));
+    }
+
+    #[tokio::test]
+    async fn withholds_rename_onto_existing_file() {
+        let mut source = scanned(
+            RecordingConnector::default(),
+            vec![jar("jei", "19.21.0", "/packs/a/mods/jei-19.21.0.jar", "aa")],
+        );
+        let mut target = scanned(
+            RecordingConnector::default(),
+            vec![
+                jar("jei", "19.21.0", "mods/jei.jar", "aa"),
+                jar("jei-19.21.0", "19.21.0", "mods/jei-19.21.0.jar", "bb"),
+            ],
+        );
+
+        let plan = source
+            .compare_with(&mut target, &CompatConfig::default())
+            .await
+            .unwrap();
+        assert!(
+            !plan
+                .actions
+                .iter()
+                .any(|a| matches!(a, SyncAction::RenameMod { .. }))
+        );
+        assert_eq!(plan.blocked.len(), 1);
+        assert!(matches!(
+            plan.blocked[0].action,
+            SyncAction::RenameMod { .. }
+        ));
+    }
+
+    #[tokio::test]
+    async fn detects_loader_of_server_without_configured_loader() {
+        let fabric = |id: &str, sha256: &str| ModInfo {
+            loader: ModLoader::Fabric,
+            ..jar(id, "1.0.0", &format!("mods/{id}.jar"), sha256)
+        };
+        let server = ServerConfig::new("fabric".to_string(), "Fabric".to_string());
+        assert_eq!(game_environment(&server.settings).loader, None);
+
+        let mut source = scanned(
+            RecordingConnector::default(),
+            vec![fabric("fabric-api", "aa"), fabric("lithium", "bb")],
+        );
+        let mut target = scanned(
+            RecordingConnector::default(),
+            vec![fabric("fabric-api", "aa")],
+        );
+        target.server_config = Some(&server);
+
+        let plan = source
+            .compare_with(&mut target, &CompatConfig::default())
+            .await
+            .unwrap();
+        assert!(plan.loader_mismatch.is_none());
+        assert!(plan.blocked.is_empty());
+        assert!(plan.actions.iter().any(|action| matches!(
+            action,
+            SyncAction::AddMod { mod_info, .. } if mod_info.id == "lithium"
+        )));
+    }
+
+    fn update(current_path: &str, new_path: &str) -> SyncPlan {
+        let mut plan = SyncPlan::new();
+        plan.add_action(SyncAction::UpdateMod {
+            mod_id: "jei".to_string(),
+            from_version: "19.20.0".to_string(),
+            to_version: "19.21.0".to_string(),
+            current_path: PathBuf::from(current_path),
+            new_path: PathBuf::from(new_path),
+            direction: VersionDirection::Upgrade,
+        });
+        plan
+    }
+
+    #[tokio::test]
+    async fn update_deletes_old_jar_after_upload() {
+        let connector = RecordingConnector::default();
+        let operations = connector.operations.clone();
+        let mut target = scanned(connector, Vec::new());
+
+        let plan = update("mods/jei-19.20.0.jar", "/packs/a/mods/jei-19.21.0.jar");
+        target.execute_sync_plan(&plan, None).await.unwrap();
+        assert_eq!(
+            *operations.lock().unwrap(),
+            ["upload mods/jei-19.21.0.jar", "delete mods/jei-19.20.0.jar"]
+        );
+    }
+
+    #[tokio::test]
+    async fn failed_update_keeps_old_jar() {
+        let connector = RecordingConnector {
+            fail_uploads: true,
+            ..RecordingConnector::default()
+        };
+        let operations = connector.operations.clone();
+        let mut target = scanned(connector, Vec::new());
+
+        let plan = update("mods/jei-19.20.0.jar", "/packs/a/mods/jei-19.21.0.jar");
+        assert!(target.execute_sync_plan(&plan, None).await.is_err());
+        assert!(operations.lock().unwrap().is_empty());
+    }
+}
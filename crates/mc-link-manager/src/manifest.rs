@@ -0,0 +1,647 @@
+//! Declarative manifests of the mods each server should run.
+//!
+//! A manifest is a TOML file, usually checked in next to a directory of mod
+//! jars it names:
+//!
+//! ```toml
+//! artifacts = "artifacts"
+//!
+//! [[mods]]
+//! id = "jei"
+//! version = "19.21.0.247"
+//! file = "jei-1.21.1-neoforge-19.21.0.247.jar"
+//! sha256 = "..."
+//!
+//! [[mods]]
+//! id = "spark"
+//! file = "spark-1.10.109-neoforge.jar"
+//! optional = true
+//!
+//! [groups.survival]
+//! servers = ["survival-1", "survival-2"]
+//! include = ["spark"]
+//!
+//! [servers.survival-2]
+//! exclude = ["jei"]
+//! ```
+//!
+//! Every server gets the mods that are not optional. Groups the server is
+//! in, then its own section, include optional mods, exclude mods, and
+//! override the file a mod is installed from. The resolved jars are staged
+//! into a local instance that serves as the source of a sync, so applying a
+//! manifest plans and executes the same [`SyncAction`](crate::SyncAction)s
+//! as syncing from another server.
+
+use crate::packs::prepare_pack_dir;
+use crate::{InstalledPack, ManagerError, MinecraftManager, Result, SyncAction, SyncPlan};
+use mc_link_compat::{GameEnvironment, VersionDirection};
+use mc_link_connector::Connector;
+use mc_link_core::{GlobalJarCache, ModInfo, ServerConnector};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Component, Path, PathBuf};
+use tracing::debug;
+
+/// The mods each server should run, resolved from a directory of jars.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Manifest {
+    /// Name of the manifest, shown as the source of syncs
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    /// Directory holding the jars, relative to the manifest
+    #[serde(default = "default_artifacts")]
+    pub artifacts: PathBuf,
+    /// Mods of the manifest
+    #[serde(default)]
+    pub mods: Vec<ManifestMod>,
+    /// Groups of servers sharing changes to the mod set, by name
+    #[serde(default)]
+    pub groups: BTreeMap<String, ManifestGroup>,
+    /// Changes to the mod set of single servers, by server ID
+    #[serde(default)]
+    pub servers: BTreeMap<String, ManifestTarget>,
+}
+
+fn default_artifacts() -> PathBuf {
+    PathBuf::from("artifacts")
+}
+
+/// A mod listed in a manifest.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ManifestMod {
+    /// Mod identifier
+    pub id: String,
+    /// Version the jar declares
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub version: Option<String>,
+    /// Jar file, relative to the artifact directory and inside it
+    pub file: String,
+    /// Hex-encoded SHA-256 the jar must match
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sha256: Option<String>,
+    /// Whether the mod is only installed on servers that include it
+    #[serde(default)]
+    pub optional: bool,
+}
+
+/// A group of servers in a manifest.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ManifestGroup {
+    /// IDs of the servers in the group
+    #[serde(default)]
+    pub servers: Vec<String>,
+    /// Changes to the mod set of the group's servers
+    #[serde(flatten)]
+    pub target: ManifestTarget,
+}
+
+/// Changes a group or server makes to the mods of a manifest.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ManifestTarget {
+    /// IDs of optional mods to install
+    #[serde(default)]
+    pub include: Vec<String>,
+    /// IDs of mods not to install
+    #[serde(default)]
+    pub exclude: Vec<String>,
+    /// Files to install mods from instead of those the manifest lists, by mod ID
+    #[serde(default)]
+    pub overrides: BTreeMap<String, ModOverride>,
+}
+
+/// Another file to install a mod from.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ModOverride {
+    /// Version the jar declares
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub version: Option<String>,
+    /// Jar file, relative to the artifact directory and inside it
+    pub file: String,
+    /// Hex-encoded SHA-256 the jar must match
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sha256: Option<String>,
+}
+
+/// A mod a server should run, with the jar it is installed from.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ResolvedMod {
+    /// Mod identifier
+    pub id: String,
+    /// Version the jar declares
+    pub version: Option<String>,
+    /// Path of the jar
+    pub path: PathBuf,
+    /// Hex-encoded SHA-256 the jar must match
+    pub sha256: Option<String>,
+}
+
+impl Manifest {
+    /// Reads a manifest, resolving its artifact directory relative to the file.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file cannot be read or is not a valid manifest.
+    pub fn load(path: &Path) -> Result<Self> {
+        let content =
+            std::fs::read_to_string(path).map_err(|e| ManagerError::FileOperationFailed {
+                operation: format!("read manifest {}", path.display()),
+                reason: e.to_string(),
+            })?;
+        let mut manifest: Self =
+            toml::from_str(&content).map_err(|e| ManagerError::InvalidManifest {
+                reason: e.to_string(),
+            })?;
+        if let Some(dir) = path.parent() {
+            manifest.artifacts = dir.join(&manifest.artifacts);
+        }
+        Ok(manifest)
+    }
+
+    /// Returns the IDs of every server the manifest names, sorted.
+    pub fn server_ids(&self) -> Vec<&str> {
+        let mut ids: Vec<&str> = self
+            .groups
+            .values()
+            .flat_map(|group| group.servers.iter().map(String::as_str))
+            .chain(self.servers.keys().map(String::as_str))
+            .collect();
+        ids.sort_unstable();
+        ids.dedup();
+        ids
+    }
+
+    /// Returns the changes that apply to a server: those of the groups it is
+    /// in, by group name, then its own.
+    fn targets_of(&self, server_id: &str) -> impl Iterator<Item = &ManifestTarget> {
+        self.groups
+            .values()
+            .filter(move |group| group.servers.iter().any(|id| id == server_id))
+            .map(|group| &group.target)
+            .chain(self.servers.get(server_id))
+    }
+
+    /// Resolves the mods a server should run, sorted by ID.
+    ///
+    /// Includes and excludes are applied for each group the server is in,
+    /// then for the server itself. Overrides are applied once the set of
+    /// mods is settled, in the same order, so a later include does not undo
+    /// an earlier override.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a group or server includes or overrides a mod the
+    /// manifest does not list, or a file is not a relative path inside the
+    /// artifact directory.
+    pub fn resolve(&self, server_id: &str) -> Result<Vec<ResolvedMod>> {
+        let listed = |id: &str| {
+            self.mods
+                .iter()
+                .find(|m| m.id == id)
+                .ok_or_else(|| ManagerError::InvalidManifest {
+                    reason: format!("mod {id} is not listed in the manifest"),
+                })
+        };
+        let resolved_mod = |m: &ManifestMod| -> Result<ResolvedMod> {
+            Ok(ResolvedMod {
+                id: m.id.clone(),
+                version: m.version.clone(),
+                path: self.artifact_path(&m.id, &m.file)?,
+                sha256: m.sha256.clone(),
+            })
+        };
+
+        let mut mods: BTreeMap<&str, ResolvedMod> = self
+            .mods
+            .iter()
+            .filter(|m| !m.optional)
+            .map(|m| Ok((m.id.as_str(), resolved_mod(m)?)))
+            .collect::<Result<_>>()?;
+        for target in self.targets_of(server_id) {
+            for id in &target.include {
+                let m = listed(id)?;
+                mods.insert(&m.id, resolved_mod(m)?);
+            }
+            for id in &target.exclude {
+                mods.remove(id.as_str());
+            }
+        }
+        for (id, replacement) in self.targets_of(server_id).flat_map(|t| &t.overrides) {
+            listed(id)?;
+            let path = self.artifact_path(id, &replacement.file)?;
+            if let Some(resolved) = mods.get_mut(id.as_str()) {
+                resolved.version = replacement.version.clone();
+                resolved.path = path;
+                resolved.sha256 = replacement.sha256.clone();
+            }
+        }
+        Ok(mods.into_values().collect())
+    }
+
+    /// Returns the path of a mod's jar, or an error if `file` is not a
+    /// relative path inside the artifact directory.
+    fn artifact_path(&self, id: &str, file: &str) -> Result<PathBuf> {
+        let relative = Path::new(file);
+        let inside = relative
+            .components()
+            .all(|component| matches!(component, Component::Normal(_)));
+        if !inside || file.is_empty() {
+            return Err(ManagerError::InvalidManifest {
+                reason: format!("{id}: file {file} is not inside the artifact directory"),
+            });
+        }
+        Ok(self.artifacts.join(relative))
+    }
+}
+
+impl ResolvedMod {
+    /// Returns the version a scanned jar of this mod declares instead of the
+    /// one the manifest expects, if they differ.
+    pub fn version_mismatch<'m>(&self, scanned: &'m [ModInfo]) -> Option<&'m str> {
+        let expected = self.version.as_deref()?;
+        let file_name = self.path.file_name()?;
+        let actual = scanned
+            .iter()
+            .find(|m| m.file_path.file_name() == Some(file_name))?
+            .version
+            .as_deref()?;
+        (actual != expected).then_some(actual)
+    }
+}
+
+impl MinecraftManager<'static, Connector> {
+    /// Creates a manager for the mods a manifest resolves for a server, and
+    /// returns it with the resolved mods.
+    ///
+    /// The jars are checked against their hashes and staged into the cache
+    /// directory, which is scanned like an installed pack, so the manager
+    /// can be the source of [`compare_with`](MinecraftManager::compare_with)
+    /// against the server. The resolved mods are the pins
+    /// [`plan_pinned_replacements`](MinecraftManager::plan_pinned_replacements)
+    /// takes.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the manifest does not resolve for the server, a
+    /// jar is missing or does not match its hash, or two jars have the same
+    /// file name and would be staged over each other.
+    pub async fn from_manifest(
+        manifest: &Manifest,
+        server_id: &str,
+    ) -> Result<(Self, Vec<ResolvedMod>)> {
+        let mods = manifest.resolve(server_id)?;
+        let mut staged_names = HashMap::new();
+        for resolved in &mods {
+            let invalid = |reason: String| ManagerError::InvalidManifest {
+                reason: format!("{}: {reason}", resolved.id),
+            };
+            let file_name = resolved.path.file_name().unwrap_or_default();
+            if let Some(other) = staged_names.insert(file_name, &resolved.id) {
+                return Err(invalid(format!(
+                    "file name {} is also used by {other}",
+                    file_name.to_string_lossy()
+                )));
+            }
+            if !resolved.path.is_file() {
+                return Err(invalid(format!("{} not found", resolved.path.display())));
+            }
+            if let Some(expected) = &resolved.sha256 {
+                let actual = GlobalJarCache::compute_file_hash(&resolved.path)?;
+                if !actual.eq_ignore_ascii_case(expected) {
+                    return Err(invalid(format!(
+                        "{} has SHA-256 {actual}, expected {expected}",
+                        resolved.path.display()
+                    )));
+                }
+            }
+        }
+
+        let dir = prepare_pack_dir("manifest", server_id)?;
+        let mods_dir = dir.join("mods");
+        std::fs::create_dir_all(&mods_dir).map_err(|e| ManagerError::FileOperationFailed {
+            operation: format!("create {}", mods_dir.display()),
+            reason: e.to_string(),
+        })?;
+        for resolved in &mods {
+            let file_name = resolved.path.file_name().unwrap_or_default();
+            std::fs::copy(&resolved.path, mods_dir.join(file_name)).map_err(|e| {
+                ManagerError::FileOperationFailed {
+                    operation: format!("stage {}", resolved.path.display()),
+                    reason: e.to_string(),
+                }
+            })?;
+        }
+        debug!(server = server_id, mods = mods.len(), "Staged manifest");
+
+        let manager = Self::from_installed_pack(InstalledPack {
+            name: manifest
+                .name
+                .clone()
+                .unwrap_or_else(|| "manifest".to_string()),
+            version: server_id.to_string(),
+            dir,
+            environment: GameEnvironment::default(),
+        });
+        Ok((manager, mods))
+    }
+}
+
+impl<'a, C> MinecraftManager<'a, C>
+where
+    C: ServerConnector + Send + Sync + 'static,
+{
+    /// Adds actions to `plan` that replace jars of `target` whose contents do
+    /// not match the hash the manifest pins for their mod.
+    ///
+    /// [`compare_with`](MinecraftManager::compare_with) only updates mods
+    /// whose versions differ, so a jar patched in place under the same
+    /// version would otherwise stay. Mods the plan already acts on are left
+    /// alone. Does nothing if either instance has not been scanned.
+    pub fn plan_pinned_replacements<D>(
+        &self,
+        pins: &[ResolvedMod],
+        target: &MinecraftManager<'_, D>,
+        plan: &mut SyncPlan,
+    ) where
+        D: ServerConnector + Send + Sync + 'static,
+    {
+        let (Some(source), Some(target)) = (self.structure(), target.structure()) else {
+            return;
+        };
+        for pin in pins {
+            let Some(expected) = &pin.sha256 else {
+                continue;
+            };
+            let Some(source_mod) = source
+                .mods
+                .mods
+                .iter()
+                .find(|m| m.file_path.file_name() == pin.path.file_name())
+            else {
+                continue;
+            };
+            let Some(target_mod) = target.mods.mods.iter().find(|m| m.id == source_mod.id) else {
+                continue;
+            };
+            let Some(actual) = &target_mod.hashes.sha256 else {
+                continue;
+            };
+            if actual.eq_ignore_ascii_case(expected) || plans_mod(plan, &source_mod.id) {
+                continue;
+            }
+            debug!(mod_id = %source_mod.id, path = %target_mod.file_path.display(), "Jar does not match its pinned hash");
+            let version = |m: &ModInfo| m.version.clone().unwrap_or_else(|| "unknown".to_string());
+            plan.add_action(SyncAction::UpdateMod {
+                mod_id: source_mod.id.clone(),
+                from_version: version(target_mod),
+                to_version: version(source_mod),
+                current_path: target_mod.file_path.clone(),
+                new_path: source_mod.file_path.clone(),
+                direction: VersionDirection::Sidegrade,
+            });
+        }
+    }
+}
+
+/// Returns whether a plan already has an action for a mod.
+fn plans_mod(plan: &SyncPlan, id: &str) -> bool {
+    plan.actions.iter().any(|action| match action {
+        SyncAction::UpdateMod { mod_id, .. }
+        | SyncAction::RenameMod { mod_id, .. }
+        | SyncAction::KeepAsIs { mod_id, .. } => mod_id == id,
+        SyncAction::AddMod { mod_info, .. } | SyncAction::RemoveMod { mod_info, .. } => {
+            mod_info.id == id
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::MinecraftStructure;
+    use mc_link_config::LocalConnection;
+    use mc_link_connector::LocalConnector;
+    use mc_link_core::{FileHashes, ModLoader, ModSide};
+
+    const MANIFEST: &str = r#"
+[[mods]]
+id = "jei"
+version = "19.21.0"
+file = "jei-19.21.0.jar"
+
+[[mods]]
+id = "spark"
+file = "spark.jar"
+optional = true
+
+[[mods]]
+id = "create"
+file = "create.jar"
+
+[groups.survival]
+servers = ["survival-1", "survival-2"]
+include = ["spark"]
+exclude = ["create"]
+
+[groups.survival.overrides.jei]
+version = "19.20.0"
+file = "jei-19.20.0.jar"
+
+[servers.survival-2]
+include = ["create", "jei"]
+exclude = ["spark"]
+"#;
+
+    fn resolved_ids(manifest: &Manifest, server_id: &str) -> Vec<String> {
+        manifest
+            .resolve(server_id)
+            .unwrap()
+            .into_iter()
+            .map(|m| m.id)
+            .collect()
+    }
+
+    #[test]
+    fn optional_mods_need_an_include() {
+        let manifest: Manifest = toml::from_str(MANIFEST).unwrap();
+        assert_eq!(resolved_ids(&manifest, "creative"), ["create", "jei"]);
+        assert_eq!(resolved_ids(&manifest, "survival-1"), ["jei", "spark"]);
+    }
+
+    #[test]
+    fn server_changes_take_precedence_over_groups() {
+        let manifest: Manifest = toml::from_str(MANIFEST).unwrap();
+        assert_eq!(resolved_ids(&manifest, "survival-2"), ["create", "jei"]);
+    }
+
+    #[test]
+    fn overrides_survive_later_includes() {
+        let manifest: Manifest = toml::from_str(MANIFEST).unwrap();
+        for server_id in ["survival-1", "survival-2"] {
+            let mods = manifest.resolve(server_id).unwrap();
+            let jei = mods.iter().find(|m| m.id == "jei").unwrap();
+            assert_eq!(jei.version.as_deref(), Some("19.20.0"), "{server_id}");
+            assert_eq!(jei.path, Path::new("artifacts/jei-19.20.0.jar"));
+        }
+    }
+
+    #[test]
+    fn unlisted_mods_are_an_error() {
+        let mut manifest: Manifest = toml::from_str(MANIFEST).unwrap();
+        manifest
+            .servers
+            .entry("creative".to_string())
+            .or_default()
+            .include
+            .push("ae2".to_string());
+        assert!(manifest.resolve("creative").is_err());
+
+        let mut manifest: Manifest = toml::from_str(MANIFEST).unwrap();
+        manifest
+            .servers
+            .entry("creative".to_string())
+            .or_default()
+            .overrides
+            .insert(
+                "ae2".to_string(),
+                ModOverride {
+                    version: None,
+                    file: "ae2.jar".to_string(),
+                    sha256: None,
+                },
+            );
+        assert!(manifest.resolve("creative").is_err());
+    }
+
+    #[test]
+    fn files_must_stay_inside_the_artifact_directory() {
+        for file in ["../jei.jar", "/opt/mods/jei.jar", "libs/../../jei.jar", ""] {
+            let mut manifest: Manifest = toml::from_str(MANIFEST).unwrap();
+            manifest.mods[0].file = file.to_string();
+            assert!(manifest.resolve("creative").is_err(), "{file}");
+
+            let mut manifest: Manifest = toml::from_str(MANIFEST).unwrap();
+            manifest
+                .groups
+                .get_mut("survival")
+                .unwrap()
+                .target
+                .overrides
+                .get_mut("jei")
+                .unwrap()
+                .file = file.to_string();
+            assert!(manifest.resolve("survival-1").is_err(), "{file}");
+        }
+
+        let mut manifest: Manifest = toml::from_str(MANIFEST).unwrap();
+        manifest.mods[0].file = "neoforge/jei.jar".to_string();
+        let mods = manifest.resolve("creative").unwrap();
+        assert_eq!(mods[1].path, Path::new("artifacts/neoforge/jei.jar"));
+    }
+
+    #[tokio::test]
+    async fn rejects_jars_staged_under_the_same_name() {
+        let dir = tempfile::tempdir().unwrap();
+        for sub in ["fabric", "neoforge"] {
+            std::fs::create_dir_all(dir.path().join("artifacts").join(sub)).unwrap();
+            std::fs::write(dir.path().join("artifacts").join(sub).join("lib.jar"), sub).unwrap();
+        }
+        let path = dir.path().join("manifest.toml");
+        std::fs::write(
+            &path,
+            "[[mods]]\nid = \"a\"\nfile = \"fabric/lib.jar\"\n\n\
+             [[mods]]\nid = \"b\"\nfile = \"neoforge/lib.jar\"\n",
+        )
+        .unwrap();
+
+        let manifest = Manifest::load(&path).unwrap();
+        let error = MinecraftManager::from_manifest(&manifest, "survival-1")
+            .await
+            .unwrap_err();
+        assert!(error.to_string().contains("lib.jar"), "{error}");
+    }
+
+    #[tokio::test]
+    async fn rejects_jars_not_matching_their_hash() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir(dir.path().join("artifacts")).unwrap();
+        std::fs::write(dir.path().join("artifacts/jei.jar"), "patched").unwrap();
+        let path = dir.path().join("manifest.toml");
+        std::fs::write(
+            &path,
+            format!(
+                "[[mods]]\nid = \"jei\"\nfile = \"jei.jar\"\nsha256 = \"{}\"\n",
+                "0".repeat(64)
+            ),
+        )
+        .unwrap();
+
+        let manifest = Manifest::load(&path).unwrap();
+        let error = MinecraftManager::from_manifest(&manifest, "survival-1")
+            .await
+            .unwrap_err();
+        assert!(
+            matches!(error, ManagerError::InvalidManifest { .. }),
+            "{error}"
+        );
+    }
+
+    fn scanned(mods: Vec<ModInfo>) -> MinecraftManager<'static, LocalConnector> {
+        let mut manager = MinecraftManager::new(LocalConnector::new(&LocalConnection::default()));
+        let mut structure = MinecraftStructure::new(PathBuf::from("."));
+        structure.mods.mods = mods;
+        manager.structure = Some(structure);
+        manager
+    }
+
+    fn jar(path: &str, sha256: &str) -> ModInfo {
+        ModInfo {
+            name: "JEI".to_string(),
+            version: Some("19.21.0".to_string()),
+            side: ModSide::Both,
+            loader: ModLoader::NeoForge,
+            hashes: FileHashes {
+                sha256: Some(sha256.to_string()),
+                ..FileHashes::default()
+            },
+            ..ModInfo::new("jei", path)
+        }
+    }
+
+    #[test]
+    fn replaces_jars_not_matching_their_pin() {
+        let pins = [ResolvedMod {
+            id: "jei".to_string(),
+            version: Some("19.21.0".to_string()),
+            path: PathBuf::from("artifacts/jei.jar"),
+            sha256: Some("AA".to_string()),
+        }];
+        let source = scanned(vec![jar("/staged/mods/jei.jar", "aa")]);
+
+        let mut plan = SyncPlan::new();
+        source.plan_pinned_replacements(
+            &pins,
+            &scanned(vec![jar("mods/jei.jar", "aa")]),
+            &mut plan,
+        );
+        assert!(!plan.has_changes());
+
+        let target = scanned(vec![jar("mods/jei.jar", "bb")]);
+        source.plan_pinned_replacements(&pins, &target, &mut plan);
+        let [
+            SyncAction::UpdateMod {
+                current_path,
+                new_path,
+                ..
+            },
+        ] = plan.actions.as_slice()
+        else {
+            panic!("expected a single update, got {:?}", plan.actions);
+        };
+        assert_eq!(current_path, Path::new("mods/jei.jar"));
+        assert_eq!(new_path, Path::new("/staged/mods/jei.jar"));
+
+        // Mods the plan already acts on are left alone
+        source.plan_pinned_replacements(&pins, &target, &mut plan);
+        assert_eq!(plan.actions.len(), 1);
+    }
+}
@@ -0,0 +1,737 @@
+//! Modpacks as the source side of comparisons and syncs, and export of
+//! scanned instances as modpacks.
+
+use crate::{ManagerError, MinecraftManager, Result, curseforge_client, modrinth_client};
+use mc_link_compat::GameEnvironment;
+use mc_link_config::{CONFIG_MANAGER, LocalConnection};
+use mc_link_connector::{Connector, LocalConnector};
+use mc_link_core::traits::PathExt;
+use mc_link_core::{ModInfo, ModLoader, Platform, PlatformInfo, ServerConnector};
+use mc_link_pack::{
+    CURSEFORGE_MODE, CurseForgeManifest, CurseForgePack, CurseForgePackWriter, CurseForgeUpdate,
+    ManifestFile, ManifestModLoader, ModDownload, ModUpdate, ModrinthUpdate, Mrpack, MrpackEnv,
+    MrpackFile, MrpackHashes, MrpackIndex, MrpackWriter, PackSide, Packwiz, PackwizMod,
+    PackwizPack, PackwizSide, PackwizWriter,
+};
+use mc_link_platform::{HashAlgorithm, ModrinthFile, ModrinthVersion};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use tracing::{debug, warn};
+
+/// A modpack installed into a local directory so it can be scanned like an instance.
+#[derive(Debug, Clone)]
+pub struct InstalledPack {
+    /// Name of the pack
+    pub name: String,
+    /// Version of the pack
+    pub version: String,
+    /// Directory the pack was installed into
+    pub dir: PathBuf,
+    /// Minecraft version and loader the pack is built for
+    pub environment: GameEnvironment,
+}
+
+impl MinecraftManager<'static, Connector> {
+    /// Creates a manager for an mrpack installed for `side`.
+    ///
+    /// The pack is installed into the cache directory, replacing any earlier
+    /// install of the same version. Its files are downloaded from the URLs
+    /// listed in the pack, or copied from the download cache when an earlier
+    /// install fetched them already. Mods scanned from the pack keep their
+    /// local path, so `AddMod` and `UpdateMod` actions upload them from there.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the pack cannot be read or one of its files cannot
+    /// be downloaded.
+    pub async fn from_mrpack(path: &Path, side: PackSide) -> Result<Self> {
+        let pack = Mrpack::open(path)?;
+        let index = pack.index();
+        let dir = prepare_pack_dir(&index.name, &index.version_id)?;
+        pack.install(&dir, side, Some(&pack_files_dir())).await?;
+
+        Ok(Self::from_installed_pack(InstalledPack {
+            name: index.name.clone(),
+            version: index.version_id.clone(),
+            dir,
+            environment: GameEnvironment::new(
+                index.minecraft_version().map(str::to_string),
+                known_loader(index.loader()),
+            )
+            .with_loader_version(index.loader_version().map(str::to_string)),
+        }))
+    }
+
+    /// Creates a manager for a CurseForge pack.
+    ///
+    /// Works like [`from_mrpack`](Self::from_mrpack), except that download
+    /// URLs are looked up through the CurseForge API. CurseForge packs do
+    /// not say which side a mod runs on, so the `Client` and `Server` tags of
+    /// each file decide whether it is installed for `side`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if no CurseForge API key is configured, the pack
+    /// cannot be read, or one of its files cannot be downloaded.
+    pub async fn from_curseforge_pack(path: &Path, side: PackSide) -> Result<Self> {
+        let client = curseforge_client().ok_or_else(|| ManagerError::MissingApiKey {
+            platform: "CurseForge".to_string(),
+        })?;
+        let pack = CurseForgePack::open(path)?;
+        let manifest = pack.manifest();
+        let dir = prepare_pack_dir(&manifest.name, &manifest.version)?;
+        pack.install(&dir, side, &client, Some(&pack_files_dir()))
+            .await?;
+
+        let minecraft_version =
+            Some(manifest.minecraft.version.clone()).filter(|version| !version.is_empty());
+        let primary = manifest.loader();
+        let loader = primary.and_then(|l| known_loader(l.loader()));
+        let loader_version = primary.and_then(|l| l.version()).map(str::to_string);
+        Ok(Self::from_installed_pack(InstalledPack {
+            name: manifest.name.clone(),
+            version: manifest.version.clone(),
+            dir,
+            environment: GameEnvironment::new(minecraft_version, loader)
+                .with_loader_version(loader_version),
+        }))
+    }
+
+    /// Creates a manager for the packwiz pack in `dir` installed for `side`.
+    ///
+    /// Works like [`from_mrpack`](Self::from_mrpack). Mods that packwiz
+    /// downloads through the CurseForge API need a CurseForge API key.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the pack cannot be read, a file does not match
+    /// the hash in the index, or one of its files cannot be downloaded.
+    pub async fn from_packwiz(dir: &Path, side: PackSide) -> Result<Self> {
+        let pack = Packwiz::open(dir)?;
+        let pack_file = pack.pack();
+        let version = pack_file.version.clone().unwrap_or_default();
+        let install_dir = prepare_pack_dir(&pack_file.name, &version)?;
+        let curseforge = curseforge_client();
+        pack.install(
+            &install_dir,
+            side,
+            curseforge.as_ref(),
+            Some(&pack_files_dir()),
+        )
+        .await?;
+
+        Ok(Self::from_installed_pack(InstalledPack {
+            name: pack_file.name.clone(),
+            version,
+            dir: install_dir,
+            environment: GameEnvironment::new(
+                pack_file.minecraft_version().map(str::to_string),
+                known_loader(pack_file.loader()),
+            )
+            .with_loader_version(pack_file.loader_version().map(str::to_string)),
+        }))
+    }
+
+    /// Creates a manager scanning the directory a pack was installed into.
+    pub(crate) fn from_installed_pack(pack: InstalledPack) -> Self {
+        debug!(pack = %pack.name, dir = %pack.dir.display(), "Installed pack for scanning");
+        let connection = LocalConnection {
+            path: pack.dir.to_string_lossy().into_owned(),
+        };
+        let mut manager = Self::new(LocalConnector::new(&connection).into());
+        manager.pack = Some(pack);
+        manager
+    }
+}
+
+/// Name, version and loader details of an exported modpack.
+#[derive(Debug, Clone)]
+pub struct PackExportOptions {
+    /// Name of the pack
+    pub name: String,
+    /// Version of the pack
+    pub version_id: String,
+    /// Short description of the pack, used by mrpacks and packwiz
+    pub summary: Option<String>,
+    /// Author of the pack, used by CurseForge packs and packwiz
+    pub author: Option<String>,
+    /// Version of the instance's loader, which the pack has to name to depend on it
+    pub loader_version: Option<String>,
+}
+
+impl PackExportOptions {
+    /// Creates options for a pack with the given name and version.
+    pub fn new(name: impl Into<String>, version_id: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            version_id: version_id.into(),
+            summary: None,
+            author: None,
+            loader_version: None,
+        }
+    }
+
+    /// Sets the pack's short description.
+    pub fn with_summary(mut self, summary: impl Into<String>) -> Self {
+        self.summary = Some(summary.into());
+        self
+    }
+
+    /// Sets the pack's author.
+    pub fn with_author(mut self, author: impl Into<String>) -> Self {
+        self.author = Some(author.into());
+        self
+    }
+
+    /// Sets the loader version the pack depends on.
+    pub fn with_loader_version(mut self, loader_version: impl Into<String>) -> Self {
+        self.loader_version = Some(loader_version.into());
+        self
+    }
+}
+
+/// What went into an exported modpack.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct PackExport {
+    /// IDs of mods the pack downloads from a platform
+    pub linked: Vec<String>,
+    /// IDs of mods embedded as overrides because no download was found for them
+    pub embedded: Vec<String>,
+    /// Config and resource pack files embedded as overrides
+    pub overrides: Vec<PathBuf>,
+}
+
+/// Pack writers that embed local files as overrides.
+trait OverrideWriter {
+    fn add_override(&mut self, path: &str, source: PathBuf) -> mc_link_pack::Result<()>;
+}
+
+impl OverrideWriter for MrpackWriter {
+    fn add_override(&mut self, path: &str, source: PathBuf) -> mc_link_pack::Result<()> {
+        MrpackWriter::add_override(self, path, source)
+    }
+}
+
+impl OverrideWriter for CurseForgePackWriter {
+    fn add_override(&mut self, path: &str, source: PathBuf) -> mc_link_pack::Result<()> {
+        CurseForgePackWriter::add_override(self, path, source)
+    }
+}
+
+impl OverrideWriter for PackwizWriter {
+    fn add_override(&mut self, path: &str, source: PathBuf) -> mc_link_pack::Result<()> {
+        self.add_file(path, source)
+    }
+}
+
+impl<'a, C> MinecraftManager<'a, C>
+where
+    C: ServerConnector + Send + Sync + 'static,
+{
+    /// Returns the modpack the instance was installed from, if any.
+    pub fn pack(&self) -> Option<&InstalledPack> {
+        self.pack.as_ref()
+    }
+
+    /// Exports the scanned instance as an mrpack at `dest`.
+    ///
+    /// Enabled mods are looked up on Modrinth by hash. Those found are
+    /// listed with their Modrinth download, hashes, size, and the sides
+    /// their [`ModSide`](mc_link_core::ModSide) says they run on; the others
+    /// are downloaded from the instance and embedded as overrides, as are
+    /// the files in `config/` and `resourcepacks/`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the instance has not been scanned, a mod cannot
+    /// be downloaded from the instance, or the pack cannot be written.
+    pub async fn export_mrpack(
+        &mut self,
+        dest: &Path,
+        options: &PackExportOptions,
+    ) -> Result<PackExport> {
+        let mods = self.mods_for_export().await?;
+
+        let mut index = MrpackIndex::new(&options.name, &options.version_id);
+        index.summary = options.summary.clone();
+        if let Some(environment) = self.environment() {
+            if let Some(minecraft_version) = environment.minecraft_version {
+                index
+                    .dependencies
+                    .insert("minecraft".to_string(), minecraft_version);
+            }
+            if let Some(loader) = environment.loader.as_ref().and_then(loader_dependency)
+                && let Some(loader_version) = &options.loader_version
+            {
+                index
+                    .dependencies
+                    .insert(loader.to_string(), loader_version.clone());
+            }
+        }
+        if !index.dependencies.contains_key("minecraft") {
+            warn!("Exporting a pack without a Minecraft version; launchers may refuse it");
+        }
+
+        let versions = self.modrinth_versions(&mods).await;
+        let mut writer = MrpackWriter::new(index);
+        let mut export = PackExport::default();
+        let mut embedded = Vec::new();
+        for mod_info in mods {
+            let download = modrinth_file(&versions, &mod_info)
+                .zip(mod_info.hashes.sha1.clone())
+                .zip(mod_info.hashes.sha512.clone());
+            let Some((((_, file), sha1), sha512)) = download else {
+                embedded.push(mod_info);
+                continue;
+            };
+
+            writer.add_file(MrpackFile {
+                path: pack_path(&mod_info),
+                hashes: MrpackHashes { sha1, sha512 },
+                env: MrpackEnv::for_side(mod_info.side.clone()),
+                downloads: vec![file.url.clone()],
+                file_size: file.size,
+            });
+            export.linked.push(mod_info.id);
+        }
+
+        self.write_pack(writer, &embedded, &mut export, |writer| writer.write(dest))
+            .await?;
+        debug!(
+            linked = export.linked.len(),
+            embedded = export.embedded.len(),
+            overrides = export.overrides.len(),
+            "Exported mrpack"
+        );
+        Ok(export)
+    }
+
+    /// Exports the scanned instance as a CurseForge pack at `dest`.
+    ///
+    /// Enabled mods are identified on CurseForge by fingerprint, from what
+    /// earlier scans cached and otherwise through the API if a key is
+    /// configured. Those found are listed by project and file ID; the others
+    /// are downloaded from the instance and embedded as overrides, as are
+    /// the files in `config/` and `resourcepacks/`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the instance has not been scanned, a mod cannot
+    /// be downloaded from the instance, or the pack cannot be written.
+    pub async fn export_curseforge_pack(
+        &mut self,
+        dest: &Path,
+        options: &PackExportOptions,
+    ) -> Result<PackExport> {
+        let mods = self.mods_for_export().await?;
+        let environment = self.environment().unwrap_or_default();
+
+        let minecraft_version = environment.minecraft_version.unwrap_or_else(|| {
+            warn!("Exporting a pack without a Minecraft version; launchers may refuse it");
+            String::new()
+        });
+        let mut manifest =
+            CurseForgeManifest::new(&options.name, &options.version_id, minecraft_version);
+        manifest.author = options.author.clone().unwrap_or_default();
+        match (&environment.loader, &options.loader_version) {
+            (Some(loader), Some(version)) => manifest
+                .minecraft
+                .mod_loaders
+                .extend(ManifestModLoader::new(loader, version)),
+            _ => warn!("Exporting a pack without a loader; launchers will not install one"),
+        }
+
+        let identified = self.curseforge_files(&mods).await;
+        let mut writer = CurseForgePackWriter::new(manifest);
+        let mut export = PackExport::default();
+        let mut embedded = Vec::new();
+        for mod_info in mods {
+            let ids = mod_info
+                .hashes
+                .fingerprint
+                .and_then(|fingerprint| identified.get(&fingerprint))
+                .and_then(|info| Some((info.project_id.parse().ok()?, info.file_id.parse().ok()?)));
+            let Some((project_id, file_id)) = ids else {
+                embedded.push(mod_info);
+                continue;
+            };
+
+            writer.add_file(ManifestFile {
+                project_id,
+                file_id,
+                required: true,
+            });
+            export.linked.push(mod_info.id);
+        }
+
+        self.write_pack(writer, &embedded, &mut export, |writer| writer.write(dest))
+            .await?;
+        debug!(
+            linked = export.linked.len(),
+            embedded = export.embedded.len(),
+            overrides = export.overrides.len(),
+            "Exported CurseForge pack"
+        );
+        Ok(export)
+    }
+
+    /// Exports the scanned instance as a packwiz pack into `dir`.
+    ///
+    /// Enabled mods are looked up on Modrinth by hash, then on CurseForge by
+    /// fingerprint. Those found get a metafile that downloads them from the
+    /// platform and lets packwiz update them; the others are stored in the
+    /// pack as jars, as are the files in `config/` and `resourcepacks/`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the instance has not been scanned, a mod cannot
+    /// be downloaded from the instance, or the pack cannot be written.
+    pub async fn export_packwiz(
+        &mut self,
+        dir: &Path,
+        options: &PackExportOptions,
+    ) -> Result<PackExport> {
+        let mods = self.mods_for_export().await?;
+        let environment = self.environment().unwrap_or_default();
+
+        let mut pack = PackwizPack::new(&options.name);
+        pack.version = Some(options.version_id.clone());
+        pack.author = options.author.clone();
+        pack.description = options.summary.clone();
+        match environment.minecraft_version {
+            Some(minecraft_version) => {
+                pack.versions
+                    .insert("minecraft".to_string(), minecraft_version);
+            }
+            None => warn!("Exporting a pack without a Minecraft version; packwiz will refuse it"),
+        }
+        if let Some(loader) = &environment.loader
+            && let Some(loader_version) = &options.loader_version
+        {
+            pack.set_loader(loader, loader_version);
+        }
+
+        let versions = self.modrinth_versions(&mods).await;
+        let identified = self.curseforge_files(&mods).await;
+        let mut writer = PackwizWriter::new(pack);
+        let mut export = PackExport::default();
+        let mut embedded = Vec::new();
+        for mod_info in mods {
+            let Some(sha1) = mod_info.hashes.sha1.clone() else {
+                embedded.push(mod_info);
+                continue;
+            };
+            let curseforge = mod_info
+                .hashes
+                .fingerprint
+                .and_then(|fingerprint| identified.get(&fingerprint))
+                .and_then(|info| {
+                    Some(CurseForgeUpdate {
+                        file_id: info.file_id.parse().ok()?,
+                        project_id: info.project_id.parse().ok()?,
+                    })
+                });
+            let modrinth = modrinth_file(&versions, &mod_info);
+
+            let (url, mode) = match (modrinth, &curseforge) {
+                (Some((_, file)), _) => (Some(file.url.clone()), None),
+                (None, Some(_)) => (None, Some(CURSEFORGE_MODE.to_string())),
+                (None, None) => {
+                    embedded.push(mod_info);
+                    continue;
+                }
+            };
+            let file_name = mod_info.file_path.file_name().unwrap_or_default();
+            writer.add_mod(
+                "mods",
+                PackwizMod {
+                    name: mod_info.name.clone(),
+                    filename: file_name.to_string_lossy().into_owned(),
+                    side: PackwizSide::for_side(mod_info.side.clone()),
+                    download: ModDownload {
+                        url,
+                        hash_format: "sha1".to_string(),
+                        hash: sha1,
+                        mode,
+                    },
+                    update: ModUpdate {
+                        modrinth: modrinth.map(|(version, _)| ModrinthUpdate {
+                            mod_id: version.project_id.clone(),
+                            version: version.id.clone(),
+                        }),
+                        curseforge,
+                    },
+                },
+            )?;
+            export.linked.push(mod_info.id);
+        }
+
+        self.write_pack(writer, &embedded, &mut export, |writer| writer.write(dir))
+            .await?;
+        debug!(
+            linked = export.linked.len(),
+            embedded = export.embedded.len(),
+            overrides = export.overrides.len(),
+            "Exported packwiz pack"
+        );
+        Ok(export)
+    }
+
+    /// Connects to the instance and returns its enabled mods for an export.
+    async fn mods_for_export(&mut self) -> Result<Vec<ModInfo>> {
+        if !self.connector.is_connected().await {
+            self.connector.connect().await?;
+        }
+        let structure = self
+            .structure()
+            .ok_or_else(|| ManagerError::InvalidStructure {
+                reason: "instance has not been scanned".to_string(),
+            })?;
+        Ok(structure
+            .mods
+            .mods
+            .iter()
+            .filter(|m| m.enabled)
+            .cloned()
+            .collect())
+    }
+
+    /// Returns the Modrinth versions of mods, keyed by SHA-1.
+    async fn modrinth_versions(&self, mods: &[ModInfo]) -> HashMap<String, ModrinthVersion> {
+        let sha1s: Vec<String> = mods.iter().filter_map(|m| m.hashes.sha1.clone()).collect();
+        let modrinth = self.modrinth.clone().unwrap_or_else(modrinth_client);
+        modrinth
+            .versions_from_hashes(&sha1s, HashAlgorithm::Sha1)
+            .await
+            .inspect_err(|e| warn!(error = %e, "Failed to look up mods on Modrinth"))
+            .unwrap_or_default()
+    }
+
+    /// Returns the CurseForge files of mods, keyed by fingerprint.
+    async fn curseforge_files(&self, mods: &[ModInfo]) -> HashMap<u32, PlatformInfo> {
+        let mut identified = HashMap::new();
+        let mut unknown = Vec::new();
+        for mod_info in mods {
+            let Some(fingerprint) = mod_info.hashes.fingerprint else {
+                continue;
+            };
+            let cached = self
+                .jar_cache
+                .as_ref()
+                .zip(mod_info.hashes.sha256.as_deref())
+                .and_then(|(cache, sha256)| cache.platform_info(sha256, Platform::CurseForge));
+            match cached {
+                Some(info) => {
+                    identified.insert(fingerprint, info.clone());
+                }
+                None => unknown.push(fingerprint),
+            }
+        }
+
+        if !unknown.is_empty()
+            && let Some(client) = self.curseforge.clone().or_else(curseforge_client)
+        {
+            match client.identify(&unknown).await {
+                Ok(found) => identified.extend(found),
+                Err(e) => warn!(error = %e, "Failed to identify mods on CurseForge"),
+            }
+        }
+        identified
+    }
+
+    /// Embeds mods and the files in `config/` and `resourcepacks/` into a
+    /// pack, then writes it with `write`.
+    ///
+    /// Files are downloaded from the instance into a temporary directory,
+    /// which is removed once the pack is written.
+    async fn write_pack<W: OverrideWriter>(
+        &self,
+        mut writer: W,
+        embedded: &[ModInfo],
+        export: &mut PackExport,
+        write: impl FnOnce(&W) -> mc_link_pack::Result<()>,
+    ) -> Result<()> {
+        let structure = self
+            .structure()
+            .ok_or_else(|| ManagerError::InvalidStructure {
+                reason: "instance has not been scanned".to_string(),
+            })?;
+
+        let temp_dir = self.create_temp_directory("mc-link-export").await?;
+        let result: Result<()> = async {
+            for mod_info in embedded {
+                let file_name = mod_info.file_path.file_name().unwrap_or_default();
+                let local_path = temp_dir.join("mods").join(file_name);
+                self.connector
+                    .download_file(&mod_info.file_path, &local_path, None)
+                    .await
+                    .map_err(|e| ManagerError::FileOperationFailed {
+                        operation: format!("download mod {} for export", mod_info.id),
+                        reason: e.to_string(),
+                    })?;
+                writer.add_override(&pack_path(mod_info), local_path)?;
+                export.embedded.push(mod_info.id.clone());
+            }
+
+            let override_dirs = [
+                (structure.config.exists, &structure.config.path),
+                (structure.resourcepacks.exists, &structure.resourcepacks.path),
+            ];
+            for dir in override_dirs.iter().filter(|(exists, _)| *exists).map(|(_, path)| path) {
+                let remote_paths = self.list_files_recursive(dir).await.unwrap_or_else(|e| {
+                    warn!(error = %e, path = %dir.display(), "Failed to list files for export");
+                    Vec::new()
+                });
+                for remote_path in remote_paths {
+                    let local_path = temp_dir.join(&remote_path);
+                    if let Err(e) = self
+                        .connector
+                        .download_file(&remote_path, &local_path, None)
+                        .await
+                    {
+                        warn!(error = %e, path = %remote_path.display(), "Failed to download file for export");
+                        continue;
+                    }
+                    writer.add_override(&remote_path.to_slash_lossy(), local_path)?;
+                    export.overrides.push(remote_path);
+                }
+            }
+
+            write(&writer)?;
+            Ok(())
+        }
+        .await;
+        self.cleanup_temp_directory(&temp_dir).await;
+        result
+    }
+
+    /// Lists the files below a directory of the instance.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the directory or one below it cannot be listed.
+    pub(crate) async fn list_files_recursive(&self, dir: &Path) -> Result<Vec<PathBuf>> {
+        let mut files = Vec::new();
+        let mut pending = vec![dir.to_path_buf()];
+        while let Some(dir) = pending.pop() {
+            let entries = self.connector.list_entries(&dir).await.map_err(|e| {
+                ManagerError::FileOperationFailed {
+                    operation: format!("list {}", dir.display()),
+                    reason: e.to_string(),
+                }
+            })?;
+            for entry in entries {
+                if entry.is_dir {
+                    pending.push(entry.path);
+                } else {
+                    files.push(entry.path);
+                }
+            }
+        }
+        Ok(files)
+    }
+}
+
+/// Returns the Modrinth version and file a mod was downloaded as.
+fn modrinth_file<'v>(
+    versions: &'v HashMap<String, ModrinthVersion>,
+    mod_info: &ModInfo,
+) -> Option<(&'v ModrinthVersion, &'v ModrinthFile)> {
+    let sha1 = mod_info.hashes.sha1.as_deref()?;
+    let version = versions.get(sha1)?;
+    let file = version
+        .files
+        .iter()
+        .find(|f| f.hash(HashAlgorithm::Sha1) == Some(sha1))?;
+    Some((version, file))
+}
+
+/// Returns where a mod goes inside a pack.
+fn pack_path(mod_info: &ModInfo) -> String {
+    let file_name = mod_info.file_path.file_name().unwrap_or_default();
+    format!("mods/{}", file_name.to_string_lossy())
+}
+
+/// Returns the ID an mrpack depends on a loader by.
+fn loader_dependency(loader: &ModLoader) -> Option<&'static str> {
+    match loader {
+        ModLoader::NeoForge => Some("neoforge"),
+        ModLoader::Fabric => Some("fabric-loader"),
+        ModLoader::Forge => Some("forge"),
+        ModLoader::Unknown => None,
+    }
+}
+
+fn known_loader(loader: ModLoader) -> Option<ModLoader> {
+    (loader != ModLoader::Unknown).then_some(loader)
+}
+
+/// Returns the directory downloaded pack files are cached in.
+fn pack_files_dir() -> PathBuf {
+    CONFIG_MANAGER.cache_dir().join("packs").join("files")
+}
+
+/// Returns the directory to install a pack into, after removing any earlier
+/// install of the same version.
+pub(crate) fn prepare_pack_dir(name: &str, version: &str) -> Result<PathBuf> {
+    let dir = CONFIG_MANAGER
+        .cache_dir()
+        .join("packs")
+        .join(dir_name(&format!("{name}-{version}")));
+    if dir.exists() {
+        std::fs::remove_dir_all(&dir).map_err(|e| ManagerError::FileOperationFailed {
+            operation: format!("remove earlier install of pack {name}"),
+            reason: e.to_string(),
+        })?;
+    }
+    Ok(dir)
+}
+
+/// Turns a pack name into a directory name.
+fn dir_name(name: &str) -> String {
+    name.chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '.' || c == '-' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn lists_nested_files_below_directory() {
+        let dir = tempfile::tempdir().unwrap();
+        let config = dir.path().join("config");
+        std::fs::create_dir_all(config.join("jei/world")).unwrap();
+        std::fs::write(config.join("create.toml"), "").unwrap();
+        std::fs::write(config.join("jei/jei-client.ini"), "").unwrap();
+        std::fs::write(config.join("jei/world/bookmarks.ini"), "").unwrap();
+
+        let mut connector = LocalConnector::new(&LocalConnection {
+            path: dir.path().display().to_string(),
+        });
+        connector.connect().await.unwrap();
+        let manager = MinecraftManager::new(connector);
+
+        let mut files = manager
+            .list_files_recursive(Path::new("config"))
+            .await
+            .unwrap();
+        files.sort();
+        assert_eq!(
+            files,
+            [
+                PathBuf::from("config/create.toml"),
+                PathBuf::from("config/jei/jei-client.ini"),
+                PathBuf::from("config/jei/world/bookmarks.ini"),
+            ]
+        );
+    }
+}
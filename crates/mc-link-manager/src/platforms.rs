@@ -0,0 +1,114 @@
+//! Identification of scanned mods on mod hosting platforms.
+
+use mc_link_config::CONFIG_MANAGER;
+use mc_link_core::{ModInfo, Platform, PlatformInfo, ServerConnector};
+use mc_link_platform::HashAlgorithm;
+use std::collections::HashMap;
+use std::hash::Hash;
+use tracing::{debug, warn};
+
+/// Platform lookups for scanned mods
+impl<'a, C> super::MinecraftManager<'a, C>
+where
+    C: ServerConnector + Send + Sync + 'static,
+{
+    /// Identifies mods on every configured platform and fills gaps in their metadata.
+    ///
+    /// Identities are kept in the JAR cache, so only files that have not been
+    /// looked up before are sent to a platform. Lookup failures are logged and
+    /// leave the mods as they were.
+    pub(crate) async fn identify_on_platforms(&mut self, mods: &mut [ModInfo]) {
+        self.identify_on_modrinth(mods).await;
+        self.identify_on_curseforge(mods).await;
+    }
+
+    /// Identifies mods on Modrinth by their SHA-1.
+    async fn identify_on_modrinth(&mut self, mods: &mut [ModInfo]) {
+        let Some(client) = &self.modrinth else {
+            return;
+        };
+        let sha1 = |m: &ModInfo| m.hashes.sha1.clone();
+
+        let unknown = self.fill_from_cache(mods, Platform::Modrinth, sha1);
+        if unknown.is_empty() {
+            return;
+        }
+        match client.identify(&unknown, HashAlgorithm::Sha1).await {
+            Ok(found) => self.fill_from_lookup(mods, unknown.len(), &found, sha1),
+            Err(e) => warn!(error = %e, "Failed to identify mods on Modrinth"),
+        }
+    }
+
+    /// Identifies mods on CurseForge by their fingerprint.
+    async fn identify_on_curseforge(&mut self, mods: &mut [ModInfo]) {
+        let Some(client) = &self.curseforge else {
+            return;
+        };
+        let fingerprint = |m: &ModInfo| m.hashes.fingerprint;
+
+        let unknown = self.fill_from_cache(mods, Platform::CurseForge, fingerprint);
+        if unknown.is_empty() {
+            return;
+        }
+        match client.identify(&unknown).await {
+            Ok(found) => self.fill_from_lookup(mods, unknown.len(), &found, fingerprint),
+            Err(e) => warn!(error = %e, "Failed to identify mods on CurseForge"),
+        }
+    }
+
+    /// Fills gaps from identities cached for `platform`, and returns the keys
+    /// of the mods that still have to be looked up.
+    fn fill_from_cache<K>(
+        &self,
+        mods: &mut [ModInfo],
+        platform: Platform,
+        key: impl Fn(&ModInfo) -> Option<K>,
+    ) -> Vec<K> {
+        let jar_cache = self
+            .jar_cache
+            .as_ref()
+            .filter(|_| CONFIG_MANAGER.manager.cache_enabled);
+
+        let mut unknown = Vec::new();
+        for mod_info in mods.iter_mut() {
+            let cached = jar_cache
+                .zip(mod_info.hashes.sha256.as_deref())
+                .and_then(|(cache, hash)| cache.platform_info(hash, platform));
+            match cached {
+                Some(info) => info.fill_gaps(mod_info),
+                None => unknown.extend(key(mod_info)),
+            }
+        }
+        unknown
+    }
+
+    /// Fills gaps from a platform lookup and caches the identities found.
+    fn fill_from_lookup<K: Eq + Hash>(
+        &mut self,
+        mods: &mut [ModInfo],
+        requested: usize,
+        found: &HashMap<K, PlatformInfo>,
+        key: impl Fn(&ModInfo) -> Option<K>,
+    ) {
+        debug!(
+            requested,
+            found = found.len(),
+            "Identified mods on platform"
+        );
+        let use_cache = CONFIG_MANAGER.manager.cache_enabled;
+
+        for mod_info in mods.iter_mut() {
+            let Some(info) = key(mod_info).and_then(|k| found.get(&k)) else {
+                continue;
+            };
+            info.fill_gaps(mod_info);
+            if use_cache
+                && let Some(jar_cache) = &mut self.jar_cache
+                && let Some(hash) = &mod_info.hashes.sha256
+                && let Err(e) = jar_cache.set_platform_info(hash, info.clone())
+            {
+                warn!(error = %e, mod_id = %mod_info.id, "Failed to cache platform identity");
+            }
+        }
+    }
+}
@@ -2,11 +2,24 @@
 //!
 //! Import with `use mc_link_manager::prelude::*;` to get commonly used manager types.
 
-pub use crate::actions::{SyncAction, SyncPlan, SyncSummary, SyncTarget};
+pub use crate::actions::{BlockedAction, SyncAction, SyncPlan, SyncSummary, SyncTarget};
+pub use crate::compat_config::CompatConfigBuilder;
 pub use crate::error::{ManagerError, Result};
-pub use crate::manager::MinecraftManager;
+pub use crate::lock::{Drift, DriftReport, LockedFile, Lockfile};
+pub use crate::manager::{MinecraftManager, curseforge_client, game_environment, modrinth_client};
+pub use crate::manifest::{
+    Manifest, ManifestGroup, ManifestMod, ManifestTarget, ModOverride, ResolvedMod,
+};
+pub use crate::packs::{InstalledPack, PackExport, PackExportOptions};
+pub use crate::profiles::{
+    aliases_from_profile, resolve_profile, resolve_profile_name, rule_from_profile,
+};
 pub use crate::structure::{
-    ConfigStructure, MinecraftStructure, ModsStructure, ResourcePackStructure, ShaderPackStructure,
+    ConfigStructure, MinecraftStructure, ModsStructure, ResourcePackStructure, ScanFinding,
+    ShaderPackStructure,
+};
+pub use crate::updates::{
+    AnnouncedUpdate, HeldUpdate, ModUpdate, OutdatedReport, download_updates,
 };
 
 // Re-export commonly used types from dependencies
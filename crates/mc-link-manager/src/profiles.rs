@@ -0,0 +1,97 @@
+//! Resolution of compatibility profiles into compatibility rules.
+
+use crate::Result;
+use mc_link_compat::{CompatRule, PatternKind, RuleType, VersionTolerance};
+use mc_link_config::{
+    CompatibilityProfile, CompatibilityRule, ConfigError, ConfigManager, RuleAction, ServerConfig,
+};
+
+/// Picks the profile name to use for a server.
+///
+/// An explicit name (e.g. from `--profile`) wins, then the server's
+/// `default_profile`, then the global `default_compatibility_profile`.
+pub fn resolve_profile_name<'c>(
+    explicit: Option<&'c str>,
+    server: &'c ServerConfig,
+    config: &'c ConfigManager,
+) -> Option<&'c str> {
+    explicit
+        .or(server.compatibility.default_profile.as_deref())
+        .or(config
+            .servers()
+            .global_settings
+            .default_compatibility_profile
+            .as_deref())
+}
+
+/// Looks up the profile that applies to a server, if any.
+///
+/// # Errors
+///
+/// Returns [`ConfigError::ProfileNotFound`] if a profile is named but not installed.
+pub fn resolve_profile<'c>(
+    explicit: Option<&'c str>,
+    server: &'c ServerConfig,
+    config: &'c ConfigManager,
+) -> Result<Option<&'c CompatibilityProfile>> {
+    let Some(name) = resolve_profile_name(explicit, server, config) else {
+        return Ok(None);
+    };
+
+    config
+        .profiles()
+        .get_profile(name)
+        .map(Some)
+        .ok_or_else(|| {
+            ConfigError::ProfileNotFound {
+                profile_name: name.to_string(),
+                cause: None,
+            }
+            .into()
+        })
+}
+
+/// Maps every alias in a profile to the mod ID it is compared under.
+pub fn aliases_from_profile(
+    profile: &CompatibilityProfile,
+) -> impl Iterator<Item = (String, String)> + '_ {
+    profile.aliases.iter().flat_map(|alias| {
+        alias
+            .aliases
+            .iter()
+            .map(|id| (id.clone(), alias.mod_id.clone()))
+    })
+}
+
+/// Converts a profile rule into a compatibility rule.
+///
+/// Non-regex patterns containing `*` or `?` are treated as globs. A
+/// `tolerate` rule without a `version_tolerance` accepts same-minor drift.
+pub fn rule_from_profile(rule: &CompatibilityRule) -> CompatRule {
+    let rule_type = match rule.action {
+        RuleAction::Ignore => RuleType::AlwaysIgnore,
+        RuleAction::Require => RuleType::RequireBoth,
+        RuleAction::ClientOnly => RuleType::ClientOnly,
+        RuleAction::ServerOnly => RuleType::ServerOnly,
+        RuleAction::Warn => RuleType::Warn,
+        RuleAction::Tolerate => RuleType::Tolerate(
+            rule.version_tolerance
+                .as_deref()
+                .map_or(VersionTolerance::SameMinor, VersionTolerance::from),
+        ),
+    };
+    let pattern = if rule.is_regex {
+        PatternKind::Regex
+    } else if rule.mod_pattern.contains(['*', '?']) {
+        PatternKind::Glob
+    } else {
+        PatternKind::Exact
+    };
+
+    CompatRule::new(
+        rule.mod_pattern.clone(),
+        rule_type,
+        rule.reason.clone().unwrap_or_default(),
+    )
+    .with_pattern(pattern)
+}
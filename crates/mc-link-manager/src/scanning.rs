@@ -55,6 +55,8 @@ where
             self.scan_mods_sequential(&jar_files, structure).await?;
         }
 
+        self.identify_on_platforms(&mut structure.mods.mods).await;
+
         Ok(())
     }
 
@@ -75,7 +77,7 @@ where
 
     /// Creates a temporary directory for mod scanning
     #[inline]
-    async fn create_temp_directory(&self, name: &str) -> Result<PathBuf> {
+    pub(crate) async fn create_temp_directory(&self, name: &str) -> Result<PathBuf> {
         let temp_dir = std::env::temp_dir().join(name);
         tokio::fs::create_dir_all(&temp_dir).await.map_err(|e| {
             ManagerError::FileOperationFailed {
@@ -222,15 +224,18 @@ where
     /// Analyzes a single JAR file to extract mod metadata, using cache if available
     async fn analyze_single_jar(&mut self, remote_path: &PathBuf, local_path: &PathBuf) -> ModInfo {
         let config = &CONFIG_MANAGER.manager;
+        let hashes = GlobalJarCache::compute_file_hashes(local_path).unwrap_or_default();
+        let hash = hashes.sha256.clone();
 
         // Try to use cache if enabled
         if config.cache_enabled {
-            if let Ok(hash) = GlobalJarCache::compute_file_hash(local_path) {
+            if let Some(ref hash) = hash {
                 if let Some(ref mut jar_cache) = self.jar_cache {
-                    if let Some(cached_mod_info) = jar_cache.get(&hash, config.cache_ttl_hours) {
+                    if let Some(cached_mod_info) = jar_cache.get(hash, config.cache_ttl_hours) {
                         // Update the file path to the current remote path
                         let mut mod_info = cached_mod_info;
                         mod_info.file_path = remote_path.clone();
+                        mod_info.hashes = hashes;
                         return mod_info;
                     }
                 }
@@ -245,26 +250,19 @@ where
             .unwrap_or("unknown")
             .to_string();
 
-        let mod_info = match jar_info {
+        let mut mod_info = match jar_info {
             Ok(mut compat_mod_info) => {
                 compat_mod_info.file_path = remote_path.clone();
                 compat_mod_info
             }
-            Err(_) => ModInfo {
-                id: mod_name.clone(),
-                name: mod_name,
-                version: None,
-                file_path: remote_path.clone(),
-                enabled: true,
-                side: mc_link_core::ModSide::Unknown,
-                loader: mc_link_core::ModLoader::Unknown,
-                raw_metadata: std::collections::HashMap::new(),
-            },
+            Err(_) => ModInfo::new(mod_name, remote_path.clone()),
         };
 
+        mod_info.hashes = hashes;
+
         // Store in cache if enabled
         if config.cache_enabled {
-            if let Ok(hash) = GlobalJarCache::compute_file_hash(local_path) {
+            if let Some(hash) = hash {
                 if let Some(ref mut jar_cache) = self.jar_cache {
                     let file_size = std::fs::metadata(local_path).map(|m| m.len()).unwrap_or(0);
                     let filename = local_path
@@ -300,7 +298,7 @@ where
 
     /// Cleans up temporary directory
     #[inline]
-    async fn cleanup_temp_directory(&self, temp_dir: &PathBuf) {
+    pub(crate) async fn cleanup_temp_directory(&self, temp_dir: &PathBuf) {
         let _ = tokio::fs::remove_dir_all(temp_dir).await;
     }
 
@@ -328,11 +326,15 @@ where
                 .await
             {
                 Ok(_) => {
+                    let hashes =
+                        GlobalJarCache::compute_file_hashes(&local_jar_path).unwrap_or_default();
+
                     // Extract JAR info from downloaded file
                     match extract_jar_info(&local_jar_path) {
                         Ok(mut compat_mod_info) => {
                             // Update file path to remote path (jar extraction uses local temp path)
                             compat_mod_info.file_path = jar_file.clone();
+                            compat_mod_info.hashes = hashes;
                             structure.mods.mods.push(compat_mod_info);
                         }
                         Err(_) => {
@@ -344,14 +346,8 @@ where
                                 .to_string();
 
                             let mod_info = ModInfo {
-                                id: mod_name.clone(),
-                                name: mod_name,
-                                version: None,
-                                file_path: jar_file.clone(),
-                                enabled: true,
-                                side: mc_link_core::ModSide::Unknown,
-                                loader: mc_link_core::ModLoader::Unknown,
-                                raw_metadata: std::collections::HashMap::new(),
+                                hashes,
+                                ..ModInfo::new(mod_name, jar_file.clone())
                             };
                             structure.mods.mods.push(mod_info);
                         }
@@ -368,16 +364,7 @@ where
                         .unwrap_or("unknown")
                         .to_string();
 
-                    let mod_info = ModInfo {
-                        id: mod_name.clone(),
-                        name: mod_name,
-                        version: None,
-                        file_path: jar_file.clone(),
-                        enabled: true,
-                        side: mc_link_core::ModSide::Unknown,
-                        loader: mc_link_core::ModLoader::Unknown,
-                        raw_metadata: std::collections::HashMap::new(),
-                    };
+                    let mod_info = ModInfo::new(mod_name, jar_file.clone());
                     structure.mods.mods.push(mod_info);
                 }
             }
@@ -1,3 +1,4 @@
+use mc_link_compat::{DuplicateGroup, EnvironmentIssue, ModConflict};
 use mc_link_core::ModInfo;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
@@ -18,6 +19,20 @@ pub struct MinecraftStructure {
     pub shaderpacks: ShaderPackStructure,
     /// Server properties and other root files
     pub root_files: HashMap<String, PathBuf>,
+    /// Problems detected while scanning
+    #[serde(default)]
+    pub findings: Vec<ScanFinding>,
+}
+
+/// A problem detected while scanning an instance.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum ScanFinding {
+    /// A mod cannot run on the configured Minecraft version or loader
+    Environment(EnvironmentIssue),
+    /// A mod is installed more than once
+    Duplicate(DuplicateGroup),
+    /// Two installed mods declare each other incompatible
+    Conflict(ModConflict),
 }
 
 /// Structure of the mods directory.
@@ -89,6 +104,7 @@ impl MinecraftStructure {
                 packs: Vec::new(),
             },
             root_files: HashMap::new(),
+            findings: Vec::new(),
             root_path,
         }
     }
@@ -0,0 +1,596 @@
+//! Checks for newer versions of installed mods on mod hosting platforms.
+
+use crate::{
+    ManagerError, Result, SyncAction, SyncPlan, curseforge_client, game_environment,
+    modrinth_client,
+};
+use futures::stream::{self, StreamExt};
+use mc_link_compat::{VersionDirection, classify_version_change};
+use mc_link_config::ServerSettings;
+use mc_link_core::{ModInfo, ModLoader, Platform, ServerConnector};
+use mc_link_platform::{
+    CurseForgeFile, ForgeUpdateClient, ForgeUpdateJson, HashAlgorithm, ModrinthVersion,
+    UpdateAvailability, download_file,
+};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use tracing::{debug, warn};
+
+/// Number of update checker JSONs fetched at the same time.
+const MAX_CONCURRENT_UPDATE_JSONS: usize = 8;
+
+/// A newer file of an installed mod.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ModUpdate {
+    /// ID of the installed mod
+    pub mod_id: String,
+    /// Name of the installed mod
+    pub mod_name: String,
+    /// Version currently installed
+    pub current_version: Option<String>,
+    /// Path of the installed mod file
+    pub current_path: PathBuf,
+    /// Platform the update was found on
+    pub platform: Platform,
+    /// Project ID on the platform
+    pub project_id: String,
+    /// Version ID (Modrinth) or file ID (CurseForge) of the update
+    pub file_id: String,
+    /// Version number or display name of the update
+    pub version_number: Option<String>,
+    /// File name of the update
+    pub file_name: String,
+    /// Where the update can be downloaded, if the platform allows it
+    pub download_url: Option<String>,
+    /// Hex-encoded SHA-1 of the update, if the platform lists it
+    pub sha1: Option<String>,
+}
+
+/// An update that was found for a pinned mod and left out.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HeldUpdate {
+    /// The update that is available
+    pub update: ModUpdate,
+    /// Version the mod is pinned to
+    pub pinned_version: String,
+}
+
+/// A newer version announced by a mod's Forge update checker JSON.
+///
+/// Update checker JSONs only name versions, so these cannot be downloaded.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AnnouncedUpdate {
+    /// ID of the installed mod
+    pub mod_id: String,
+    /// Name of the installed mod
+    pub mod_name: String,
+    /// Version currently installed
+    pub current_version: Option<String>,
+    /// Latest version, if newer than the installed one
+    pub latest: Option<String>,
+    /// Recommended version, if newer than the installed one
+    pub recommended: Option<String>,
+    /// Where the mod can be downloaded, as given by the update JSON
+    pub homepage: Option<String>,
+    /// Version the mod is pinned to, if any
+    pub pinned_version: Option<String>,
+}
+
+/// Which installed mods have newer versions for a server's game version and loader.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OutdatedReport {
+    /// Minecraft version updates were filtered by
+    pub minecraft_version: Option<String>,
+    /// Loader updates were filtered by, as named by the platforms
+    pub loader: Option<String>,
+    /// Mods with a newer version available
+    pub updates: Vec<ModUpdate>,
+    /// Pinned mods with a newer version available
+    pub held: Vec<HeldUpdate>,
+    /// Mods whose update checker JSON announces a newer version
+    #[serde(default)]
+    pub announced: Vec<AnnouncedUpdate>,
+    /// IDs of mods already on their newest version
+    pub up_to_date: Vec<String>,
+    /// IDs of mods whose update status could not be determined, because no
+    /// platform knows them or their latest version lists no files
+    pub unresolved: Vec<String>,
+}
+
+impl OutdatedReport {
+    /// Builds a sync plan that replaces each outdated mod with its update.
+    ///
+    /// The updates are expected in `download_dir` under their platform file
+    /// names, as left by [`download_updates`]. Held updates, and updates the
+    /// platform does not allow downloading, are kept as-is.
+    pub fn to_sync_plan(&self, download_dir: &Path) -> SyncPlan {
+        let mut plan = SyncPlan::new();
+        for update in &self.updates {
+            if update.download_url.is_none() {
+                plan.add_action(SyncAction::KeepAsIs {
+                    mod_id: update.mod_id.clone(),
+                    reason: format!(
+                        "{:?} does not allow downloading the update",
+                        update.platform
+                    ),
+                });
+                continue;
+            }
+            plan.add_action(SyncAction::UpdateMod {
+                mod_id: update.mod_id.clone(),
+                from_version: update
+                    .current_version
+                    .clone()
+                    .unwrap_or_else(|| "unknown".to_string()),
+                to_version: update
+                    .version_number
+                    .clone()
+                    .unwrap_or_else(|| update.file_name.clone()),
+                current_path: update.current_path.clone(),
+                new_path: download_dir.join(&update.file_name),
+                direction: VersionDirection::Upgrade,
+            });
+        }
+        for held in &self.held {
+            plan.add_action(SyncAction::KeepAsIs {
+                mod_id: held.update.mod_id.clone(),
+                reason: format!("pinned at {}", held.pinned_version),
+            });
+        }
+        plan
+    }
+}
+
+/// Downloads every update into `dir`, checking hashes where the platform lists them.
+///
+/// Updates without a download URL, which happens when a CurseForge author
+/// disabled third-party downloads, are skipped and returned.
+///
+/// # Errors
+///
+/// Returns an error if a download fails.
+pub async fn download_updates<'u>(
+    updates: &'u [ModUpdate],
+    dir: &Path,
+) -> Result<Vec<&'u ModUpdate>> {
+    let mut skipped = Vec::new();
+    for update in updates {
+        let Some(url) = &update.download_url else {
+            warn!(mod_id = %update.mod_id, platform = ?update.platform, "Update cannot be downloaded");
+            skipped.push(update);
+            continue;
+        };
+        download_file(url, &dir.join(&update.file_name), update.sha1.as_deref())
+            .await
+            .map_err(|e| ManagerError::UpdateFailed {
+                action: format!("Download update of mod {}", update.mod_id),
+                reason: e.to_string(),
+            })?;
+    }
+    Ok(skipped)
+}
+
+/// Returns the name Modrinth and CurseForge use for a server's loader.
+fn platform_loader(loader: &ModLoader) -> Option<&'static str> {
+    match loader {
+        ModLoader::NeoForge => Some("neoforge"),
+        ModLoader::Fabric => Some("fabric"),
+        ModLoader::Forge => Some("forge"),
+        ModLoader::Unknown => None,
+    }
+}
+
+/// Update checks for scanned mods
+impl<'a, C> super::MinecraftManager<'a, C>
+where
+    C: ServerConnector + Send + Sync + 'static,
+{
+    /// Finds newer versions of the scanned mods for the server's Minecraft
+    /// version and loader.
+    ///
+    /// Mods are looked up by hash on Modrinth, then by fingerprint on
+    /// CurseForge if an API key is configured. Mods listed in the server's
+    /// `pinned_mods` are reported as held rather than outdated. Mods neither
+    /// platform knows are checked against the `updateJSONURL` declared in
+    /// their `mods.toml`, if any.
+    ///
+    /// A Modrinth version that does not contain the installed file is only
+    /// an update if its version number is higher, or, when version numbers
+    /// cannot be compared, if it was published after the installed one.
+    ///
+    /// Update JSONs are fetched a few at a time. Those that cannot be fetched
+    /// are logged and leave their mods unresolved.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the instance has not been scanned or a platform
+    /// request fails.
+    pub async fn check_updates(&self) -> Result<OutdatedReport> {
+        let structure = self
+            .structure()
+            .ok_or_else(|| ManagerError::InvalidStructure {
+                reason: "instance has not been scanned".to_string(),
+            })?;
+        let default_settings = ServerSettings::default();
+        let settings = self
+            .server_config
+            .map_or(&default_settings, |config| &config.settings);
+
+        let mut report = OutdatedReport {
+            minecraft_version: settings.minecraft_version.clone(),
+            loader: game_environment(settings)
+                .with_detected_loader(&structure.mods.mods)
+                .loader
+                .as_ref()
+                .and_then(platform_loader)
+                .map(str::to_string),
+            updates: Vec::new(),
+            held: Vec::new(),
+            announced: Vec::new(),
+            up_to_date: Vec::new(),
+            unresolved: Vec::new(),
+        };
+        let mut remaining: Vec<&ModInfo> =
+            structure.mods.mods.iter().filter(|m| m.enabled).collect();
+
+        let modrinth = self.modrinth.clone().unwrap_or_else(modrinth_client);
+        let hashes: Vec<String> = remaining
+            .iter()
+            .filter_map(|m| m.hashes.sha1.clone())
+            .collect();
+        let latest = modrinth
+            .latest_versions(
+                &hashes,
+                HashAlgorithm::Sha1,
+                report.loader.as_slice(),
+                report.minecraft_version.as_slice(),
+            )
+            .await?;
+        let undecided: Vec<String> = remaining
+            .iter()
+            .filter_map(|mod_info| {
+                let sha1 = mod_info.hashes.sha1.as_ref()?;
+                let version = latest.get(sha1)?;
+                (!version.has_file(sha1, HashAlgorithm::Sha1)
+                    && modrinth_direction(mod_info, version, None) == VersionDirection::Unknown)
+                    .then(|| sha1.clone())
+            })
+            .collect();
+        let installed = modrinth
+            .versions_from_hashes(&undecided, HashAlgorithm::Sha1)
+            .await?;
+        remaining.retain(|mod_info| {
+            let Some((sha1, version)) = mod_info
+                .hashes
+                .sha1
+                .as_ref()
+                .and_then(|sha1| Some((sha1, latest.get(sha1)?)))
+            else {
+                return true;
+            };
+            if version.has_file(sha1, HashAlgorithm::Sha1) {
+                report.up_to_date.push(mod_info.id.clone());
+                return false;
+            }
+            let Some(update) = modrinth_update(mod_info, version) else {
+                debug!(mod_id = %mod_info.id, version = %version.id, "Latest Modrinth version has no files");
+                return true;
+            };
+            match modrinth_direction(mod_info, version, installed.get(sha1)) {
+                VersionDirection::Upgrade | VersionDirection::Unknown => {
+                    add_update(&mut report, settings, update);
+                }
+                VersionDirection::Downgrade | VersionDirection::Sidegrade => {
+                    report.up_to_date.push(mod_info.id.clone());
+                }
+            }
+            false
+        });
+
+        if let Some(curseforge) = self.curseforge.clone().or_else(curseforge_client) {
+            let fingerprints: Vec<u32> = remaining
+                .iter()
+                .filter_map(|m| m.hashes.fingerprint)
+                .collect();
+            let latest = curseforge
+                .latest_files(
+                    &fingerprints,
+                    report.minecraft_version.as_deref(),
+                    report.loader.as_deref(),
+                )
+                .await?;
+            remaining.retain(|mod_info| {
+                let Some(found) = mod_info.hashes.fingerprint.and_then(|f| latest.get(&f)) else {
+                    return true;
+                };
+                if found.is_newer() {
+                    let update = curseforge_update(mod_info, &found.latest);
+                    add_update(&mut report, settings, update);
+                } else {
+                    report.up_to_date.push(mod_info.id.clone());
+                }
+                false
+            });
+        }
+
+        if let Some(minecraft_version) = report.minecraft_version.clone() {
+            let client = ForgeUpdateClient::new();
+            let urls: HashSet<&str> = remaining
+                .iter()
+                .filter_map(|m| m.metadata.update_json_url.as_deref())
+                .collect();
+            let update_jsons: HashMap<&str, Option<ForgeUpdateJson>> = stream::iter(urls)
+                .map(|url| {
+                    let client = &client;
+                    async move {
+                        let fetched = client
+                            .fetch(url)
+                            .await
+                            .inspect_err(|e| warn!(error = %e, url, "Failed to fetch update JSON"))
+                            .ok();
+                        (url, fetched)
+                    }
+                })
+                .buffer_unordered(MAX_CONCURRENT_UPDATE_JSONS)
+                .collect()
+                .await;
+
+            remaining.retain(|mod_info| {
+                let Some(update_json) = mod_info
+                    .metadata
+                    .update_json_url
+                    .as_deref()
+                    .and_then(|url| update_jsons.get(url)?.as_ref())
+                else {
+                    return true;
+                };
+                match update_json.availability(mod_info.version.as_deref(), &minecraft_version) {
+                    UpdateAvailability::UpToDate => report.up_to_date.push(mod_info.id.clone()),
+                    UpdateAvailability::Available {
+                        latest,
+                        recommended,
+                    } => report.announced.push(AnnouncedUpdate {
+                        mod_id: mod_info.id.clone(),
+                        mod_name: mod_info.name.clone(),
+                        current_version: mod_info.version.clone(),
+                        latest,
+                        recommended,
+                        homepage: update_json.homepage.clone(),
+                        pinned_version: settings.pinned_mods.get(&mod_info.id).cloned(),
+                    }),
+                    UpdateAvailability::Unknown => return true,
+                }
+                false
+            });
+        }
+
+        report.unresolved = remaining.iter().map(|m| m.id.clone()).collect();
+        report.updates.sort_by(|a, b| a.mod_id.cmp(&b.mod_id));
+        report
+            .held
+            .sort_by(|a, b| a.update.mod_id.cmp(&b.update.mod_id));
+        report.announced.sort_by(|a, b| a.mod_id.cmp(&b.mod_id));
+        report.up_to_date.sort();
+        report.unresolved.sort();
+        debug!(
+            updates = report.updates.len(),
+            held = report.held.len(),
+            announced = report.announced.len(),
+            up_to_date = report.up_to_date.len(),
+            unresolved = report.unresolved.len(),
+            "Checked mods for updates"
+        );
+        Ok(report)
+    }
+}
+
+/// Files an update as outdated or, for a pinned mod, as held.
+fn add_update(report: &mut OutdatedReport, settings: &ServerSettings, update: ModUpdate) {
+    match settings.pinned_mods.get(&update.mod_id) {
+        Some(pinned) => report.held.push(HeldUpdate {
+            update,
+            pinned_version: pinned.clone(),
+        }),
+        None => report.updates.push(update),
+    }
+}
+
+/// Compares Modrinth's latest version of a mod with the installed one.
+///
+/// Version numbers are compared first. If either does not parse, the
+/// publication dates are compared, provided the installed version is given
+/// and both dates are known.
+fn modrinth_direction(
+    mod_info: &ModInfo,
+    latest: &ModrinthVersion,
+    installed: Option<&ModrinthVersion>,
+) -> VersionDirection {
+    let current_number = installed
+        .and_then(|v| v.version_number.as_deref())
+        .or(mod_info.version.as_deref());
+    if let (Some(current), Some(new)) = (current_number, latest.version_number.as_deref()) {
+        let direction = classify_version_change(current, new);
+        if direction != VersionDirection::Unknown {
+            return direction;
+        }
+    }
+    // RFC 3339 timestamps in the same format sort chronologically.
+    match (
+        installed.and_then(|v| v.date_published.as_deref()),
+        latest.date_published.as_deref(),
+    ) {
+        (Some(current), Some(new)) if new > current => VersionDirection::Upgrade,
+        (Some(current), Some(new)) if new < current => VersionDirection::Downgrade,
+        (Some(_), Some(_)) => VersionDirection::Sidegrade,
+        _ => VersionDirection::Unknown,
+    }
+}
+
+fn modrinth_update(mod_info: &ModInfo, version: &ModrinthVersion) -> Option<ModUpdate> {
+    let file = version.primary_file()?;
+    Some(ModUpdate {
+        mod_id: mod_info.id.clone(),
+        mod_name: mod_info.name.clone(),
+        current_version: mod_info.version.clone(),
+        current_path: mod_info.file_path.clone(),
+        platform: Platform::Modrinth,
+        project_id: version.project_id.clone(),
+        file_id: version.id.clone(),
+        version_number: version.version_number.clone(),
+        file_name: file.filename.clone(),
+        download_url: Some(file.url.clone()),
+        sha1: file.hash(HashAlgorithm::Sha1).map(str::to_string),
+    })
+}
+
+fn curseforge_update(mod_info: &ModInfo, file: &CurseForgeFile) -> ModUpdate {
+    ModUpdate {
+        mod_id: mod_info.id.clone(),
+        mod_name: mod_info.name.clone(),
+        current_version: mod_info.version.clone(),
+        current_path: mod_info.file_path.clone(),
+        platform: Platform::CurseForge,
+        project_id: file.mod_id.to_string(),
+        file_id: file.id.to_string(),
+        version_number: file.display_name.clone(),
+        file_name: file
+            .file_name
+            .clone()
+            .unwrap_or_else(|| format!("{}.jar", file.id)),
+        download_url: file.download_url.clone(),
+        sha1: file.sha1().map(str::to_string),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn installed(version: Option<&str>) -> ModInfo {
+        ModInfo {
+            version: version.map(str::to_string),
+            ..ModInfo::new("create", "mods/create.jar")
+        }
+    }
+
+    fn version(number: &str, published: Option<&str>) -> ModrinthVersion {
+        ModrinthVersion {
+            id: format!("v{number}"),
+            project_id: "LNytGWDc".to_string(),
+            name: None,
+            version_number: Some(number.to_string()),
+            loaders: vec!["neoforge".to_string()],
+            game_versions: vec!["1.21.1".to_string()],
+            date_published: published.map(str::to_string),
+            files: Vec::new(),
+        }
+    }
+
+    fn update(mod_id: &str, download_url: Option<&str>) -> ModUpdate {
+        ModUpdate {
+            mod_id: mod_id.to_string(),
+            mod_name: mod_id.to_string(),
+            current_version: Some("1.0".to_string()),
+            current_path: PathBuf::from(format!("mods/{mod_id}.jar")),
+            platform: Platform::CurseForge,
+            project_id: "1".to_string(),
+            file_id: "2".to_string(),
+            version_number: Some("1.1".to_string()),
+            file_name: format!("{mod_id}-1.1.jar"),
+            download_url: download_url.map(str::to_string),
+            sha1: None,
+        }
+    }
+
+    fn empty_report() -> OutdatedReport {
+        OutdatedReport {
+            minecraft_version: None,
+            loader: None,
+            updates: Vec::new(),
+            held: Vec::new(),
+            announced: Vec::new(),
+            up_to_date: Vec::new(),
+            unresolved: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn holds_updates_of_pinned_mods() {
+        let mut settings = ServerSettings::default();
+        settings
+            .pinned_mods
+            .insert("create".to_string(), "1.0".to_string());
+        let mut report = empty_report();
+
+        add_update(&mut report, &settings, update("create", None));
+        add_update(&mut report, &settings, update("jei", None));
+
+        assert_eq!(report.held.len(), 1);
+        assert_eq!(report.held[0].update.mod_id, "create");
+        assert_eq!(report.held[0].pinned_version, "1.0");
+        assert_eq!(report.updates.len(), 1);
+        assert_eq!(report.updates[0].mod_id, "jei");
+    }
+
+    #[test]
+    fn older_modrinth_version_is_not_an_update() {
+        let mod_info = installed(Some("6.0.0"));
+
+        assert_eq!(
+            modrinth_direction(&mod_info, &version("6.1.0", None), None),
+            VersionDirection::Upgrade
+        );
+        assert_eq!(
+            modrinth_direction(&mod_info, &version("5.9.0", None), None),
+            VersionDirection::Downgrade
+        );
+    }
+
+    #[test]
+    fn compares_publication_dates_of_unknown_versions() {
+        let mod_info = installed(Some("${file.jarVersion}"));
+        let current = ModrinthVersion {
+            version_number: None,
+            ..version("", Some("2024-05-01T10:00:00.000000Z"))
+        };
+
+        assert_eq!(
+            modrinth_direction(&mod_info, &version("6.1.0", None), None),
+            VersionDirection::Unknown
+        );
+        assert_eq!(
+            modrinth_direction(
+                &mod_info,
+                &version("6.1.0", Some("2024-06-01T10:00:00.000000Z")),
+                Some(&current)
+            ),
+            VersionDirection::Upgrade
+        );
+        assert_eq!(
+            modrinth_direction(
+                &mod_info,
+                &version("6.1.0", Some("2024-04-01T10:00:00.000000Z")),
+                Some(&current)
+            ),
+            VersionDirection::Downgrade
+        );
+    }
+
+    #[test]
+    fn keeps_mods_whose_update_cannot_be_downloaded() {
+        let mut report = empty_report();
+        report.updates = vec![
+            update("create", Some("https://example.com/create-1.1.jar")),
+            update("jei", None),
+        ];
+
+        let plan = report.to_sync_plan(Path::new("downloads"));
+
+        assert!(
+            matches!(&plan.actions[0], SyncAction::UpdateMod { mod_id, new_path, .. }
+            if mod_id == "create" && new_path == Path::new("downloads/create-1.1.jar"))
+        );
+        assert!(matches!(&plan.actions[1], SyncAction::KeepAsIs { mod_id, .. } if mod_id == "jei"));
+    }
+}
@@ -0,0 +1,190 @@
+//! Files shared by the pack formats: zip archives with an index file and
+//! a folder of overrides, and files downloaded through a local cache.
+
+use crate::{PackError, Result};
+use mc_link_platform::download_file;
+use serde::Serialize;
+use sha1::Sha1;
+use sha2::{Digest, Sha256, Sha512};
+use std::fs::File;
+use std::path::{Component, Path, PathBuf};
+use tracing::warn;
+use zip::write::SimpleFileOptions;
+use zip::{ZipArchive, ZipWriter};
+
+/// Reads and parses the index file of a pack archive.
+pub(crate) fn read_index<T: serde::de::DeserializeOwned>(path: &Path, name: &str) -> Result<T> {
+    let mut archive = ZipArchive::new(File::open(path)?)?;
+    match archive.by_name(name) {
+        Ok(entry) => Ok(serde_json::from_reader(entry)?),
+        Err(zip::result::ZipError::FileNotFound) => Err(PackError::InvalidPack {
+            reason: format!("{} has no {name}", path.display()),
+        }),
+        Err(e) => Err(e.into()),
+    }
+}
+
+/// Extracts the files below `prefix` in the archive at `path` into `dir`,
+/// and returns how many files were written.
+pub(crate) fn extract_dir(path: &Path, prefix: &str, dir: &Path) -> Result<usize> {
+    let mut archive = ZipArchive::new(File::open(path)?)?;
+    let mut extracted = 0;
+
+    for i in 0..archive.len() {
+        let mut entry = archive.by_index(i)?;
+        if entry.is_dir() {
+            continue;
+        }
+        let Some(relative) = entry
+            .enclosed_name()
+            .and_then(|name| name.strip_prefix(prefix).ok().map(Path::to_path_buf))
+        else {
+            continue;
+        };
+
+        let dest = dir.join(relative);
+        if let Some(parent) = dest.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::io::copy(&mut entry, &mut File::create(&dest)?)?;
+        extracted += 1;
+    }
+    Ok(extracted)
+}
+
+/// Writes a pack archive holding `index` as `index_name`, and each local
+/// file of `overrides` at its instance path below `overrides_dir`.
+pub(crate) fn write_archive(
+    dest: &Path,
+    index_name: &str,
+    index: &impl Serialize,
+    overrides_dir: &str,
+    overrides: &[(String, PathBuf)],
+) -> Result<()> {
+    let mut zip = ZipWriter::new(File::create(dest)?);
+    let options = SimpleFileOptions::default();
+
+    zip.start_file(index_name, options)?;
+    serde_json::to_writer_pretty(&mut zip, index)?;
+    for (path, source) in overrides {
+        zip.start_file(format!("{overrides_dir}/{path}"), options)?;
+        std::io::copy(&mut File::open(source)?, &mut zip)?;
+    }
+    zip.finish()?;
+    Ok(())
+}
+
+/// Puts a pack file at `dest`.
+///
+/// The file is copied from `cache_dir` when a file named after its SHA-1 is
+/// there and still has that hash, and otherwise downloaded from the first of
+/// `urls` that works and added to the cache. Files without a SHA-1 bypass the
+/// cache.
+pub(crate) async fn fetch_file(
+    path: &str,
+    urls: &[String],
+    sha1: Option<&str>,
+    dest: &Path,
+    cache_dir: Option<&Path>,
+) -> Result<()> {
+    if let Some(parent) = dest.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+
+    let cached = cache_dir
+        .zip(sha1.filter(|sha1| is_sha1(sha1)))
+        .map(|(cache, sha1)| cache.join(sha1));
+    if let (Some(cached), Some(sha1)) = (cached.as_ref().filter(|c| c.is_file()), sha1) {
+        std::fs::copy(cached, dest)?;
+        match check_hash(dest, "sha1", sha1) {
+            Ok(()) => return Ok(()),
+            Err(e) => {
+                warn!(error = %e, path, "Discarding corrupt cached pack file");
+                if let Err(e) = std::fs::remove_file(cached) {
+                    warn!(error = %e, path, "Failed to remove corrupt cached pack file");
+                }
+            }
+        }
+    }
+
+    download_first(path, urls, sha1, dest).await?;
+    if let Some(cached) = &cached {
+        let copied = std::fs::create_dir_all(cached.parent().unwrap_or(Path::new(".")))
+            .and_then(|()| std::fs::copy(dest, cached));
+        if let Err(e) = copied {
+            warn!(error = %e, path, "Failed to cache pack file");
+        }
+    }
+    Ok(())
+}
+
+/// Downloads a pack file from the first of its URLs that works.
+async fn download_first(
+    path: &str,
+    urls: &[String],
+    sha1: Option<&str>,
+    dest: &Path,
+) -> Result<()> {
+    let mut reason = "no download URLs".to_string();
+    for url in urls {
+        match download_file(url, dest, sha1).await {
+            Ok(()) => return Ok(()),
+            Err(e) => {
+                warn!(error = %e, url, "Failed to download pack file");
+                reason = e.to_string();
+            }
+        }
+    }
+    Err(PackError::Download {
+        path: path.to_string(),
+        reason,
+    })
+}
+
+/// Returns the hex-encoded hash of `contents`, or `None` for an algorithm
+/// other than SHA-1, SHA-256 and SHA-512.
+pub(crate) fn digest(format: &str, contents: &[u8]) -> Option<String> {
+    match format {
+        "sha1" => Some(format!("{:x}", Sha1::digest(contents))),
+        "sha256" => Some(format!("{:x}", Sha256::digest(contents))),
+        "sha512" => Some(format!("{:x}", Sha512::digest(contents))),
+        _ => None,
+    }
+}
+
+/// Returns whether [`digest`] can compute hashes of an algorithm.
+pub(crate) fn is_supported_hash(format: &str) -> bool {
+    matches!(format, "sha1" | "sha256" | "sha512")
+}
+
+/// Checks a file against its hash. Algorithms [`digest`] does not know,
+/// such as packwiz's `murmur2`, are skipped with a warning.
+pub(crate) fn check_hash(path: &Path, format: &str, expected: &str) -> Result<()> {
+    let Some(actual) = digest(format, &std::fs::read(path)?) else {
+        warn!(path = %path.display(), format, "Skipping check of unsupported hash format");
+        return Ok(());
+    };
+    if !actual.eq_ignore_ascii_case(expected) {
+        return Err(PackError::InvalidPack {
+            reason: format!(
+                "{} has {format} hash {actual}, expected {expected}",
+                path.display()
+            ),
+        });
+    }
+    Ok(())
+}
+
+/// Returns a pack path as a relative path, or `None` if it would leave the instance.
+pub(crate) fn instance_path(path: &str) -> Option<PathBuf> {
+    let path = Path::new(path);
+    let inside = path
+        .components()
+        .all(|component| matches!(component, Component::Normal(_)));
+    (inside && !path.as_os_str().is_empty()).then(|| path.to_path_buf())
+}
+
+/// Returns whether a string is a hex-encoded SHA-1, and so safe as a file name.
+pub(crate) fn is_sha1(hash: &str) -> bool {
+    hash.len() == 40 && hash.chars().all(|c| c.is_ascii_hexdigit())
+}
@@ -0,0 +1,496 @@
+//! CurseForge modpacks.
+//!
+//! A CurseForge pack is a zip archive holding a `manifest.json`, which lists
+//! the project and file IDs of its mods along with the Minecraft version and
+//! loader it is built for, and a folder of overrides, usually `overrides/`.
+
+use crate::archive::{extract_dir, fetch_file, instance_path, read_index, write_archive};
+use crate::{PackError, PackSide, Result};
+use mc_link_core::{ModLoader, SideSupport};
+use mc_link_platform::{CurseForgeClient, CurseForgeFile};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use tracing::{debug, warn};
+
+/// Name of the manifest file inside a CurseForge pack.
+pub const MANIFEST_FILE: &str = "manifest.json";
+
+/// CurseForge class ID of mods.
+const MODS_CLASS: u32 = 6;
+
+/// CurseForge class ID of resource packs.
+const RESOURCE_PACKS_CLASS: u32 = 12;
+
+/// CurseForge class ID of shader packs.
+const SHADERS_CLASS: u32 = 6552;
+
+/// The `manifest.json` of a CurseForge pack.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CurseForgeManifest {
+    /// Minecraft version and loaders the pack is built for
+    pub minecraft: ManifestMinecraft,
+    /// Kind of manifest, always `minecraftModpack`
+    pub manifest_type: String,
+    /// Version of the manifest format, currently always 1
+    pub manifest_version: u32,
+    /// Name of the pack
+    pub name: String,
+    /// Version of the pack
+    #[serde(default)]
+    pub version: String,
+    /// Author of the pack
+    #[serde(default)]
+    pub author: String,
+    /// Mod files to download into the instance
+    #[serde(default)]
+    pub files: Vec<ManifestFile>,
+    /// Folder of the archive holding the overrides
+    #[serde(default = "default_overrides")]
+    pub overrides: String,
+}
+
+impl CurseForgeManifest {
+    /// Creates an empty manifest for a pack.
+    pub fn new(
+        name: impl Into<String>,
+        version: impl Into<String>,
+        minecraft_version: impl Into<String>,
+    ) -> Self {
+        Self {
+            minecraft: ManifestMinecraft {
+                version: minecraft_version.into(),
+                mod_loaders: Vec::new(),
+            },
+            manifest_type: "minecraftModpack".to_string(),
+            manifest_version: 1,
+            name: name.into(),
+            version: version.into(),
+            author: String::new(),
+            files: Vec::new(),
+            overrides: default_overrides(),
+        }
+    }
+
+    /// Returns the pack's primary loader, falling back to the first listed.
+    pub fn loader(&self) -> Option<&ManifestModLoader> {
+        let loaders = &self.minecraft.mod_loaders;
+        loaders.iter().find(|l| l.primary).or(loaders.first())
+    }
+}
+
+fn default_overrides() -> String {
+    "overrides".to_string()
+}
+
+/// Minecraft version and loaders of a CurseForge pack.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ManifestMinecraft {
+    /// Minecraft version, e.g. `1.21.1`
+    pub version: String,
+    /// Loaders to install
+    #[serde(default)]
+    pub mod_loaders: Vec<ManifestModLoader>,
+}
+
+/// A loader of a CurseForge pack.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ManifestModLoader {
+    /// Loader and version, e.g. `neoforge-21.1.77`
+    pub id: String,
+    /// Whether this is the loader launchers install
+    #[serde(default)]
+    pub primary: bool,
+}
+
+impl ManifestModLoader {
+    /// Creates the primary loader entry for a loader version, or `None` for
+    /// an unknown loader.
+    pub fn new(loader: &ModLoader, version: &str) -> Option<Self> {
+        let name = match loader {
+            ModLoader::NeoForge => "neoforge",
+            ModLoader::Fabric => "fabric",
+            ModLoader::Forge => "forge",
+            ModLoader::Unknown => return None,
+        };
+        Some(Self {
+            id: format!("{name}-{version}"),
+            primary: true,
+        })
+    }
+
+    /// Returns the loader named by the ID.
+    pub fn loader(&self) -> ModLoader {
+        match self
+            .id
+            .split_once('-')
+            .map_or(self.id.as_str(), |(name, _)| name)
+        {
+            "neoforge" => ModLoader::NeoForge,
+            "fabric" | "quilt" => ModLoader::Fabric,
+            "forge" => ModLoader::Forge,
+            _ => ModLoader::Unknown,
+        }
+    }
+
+    /// Returns the loader version named by the ID.
+    pub fn version(&self) -> Option<&str> {
+        self.id.split_once('-').map(|(_, version)| version)
+    }
+}
+
+/// A mod file of a CurseForge pack.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ManifestFile {
+    /// CurseForge project ID
+    #[serde(rename = "projectID")]
+    pub project_id: u64,
+    /// CurseForge file ID
+    #[serde(rename = "fileID")]
+    pub file_id: u64,
+    /// Whether the file is installed; optional files are left to the player
+    #[serde(default = "default_required")]
+    pub required: bool,
+}
+
+fn default_required() -> bool {
+    true
+}
+
+/// A CurseForge pack on disk.
+#[derive(Debug, Clone)]
+pub struct CurseForgePack {
+    path: PathBuf,
+    manifest: CurseForgeManifest,
+}
+
+impl CurseForgePack {
+    /// Opens a CurseForge pack and reads its manifest.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file is not a zip archive or has no valid manifest.
+    pub fn open(path: &Path) -> Result<Self> {
+        let manifest: CurseForgeManifest = read_index(path, MANIFEST_FILE)?;
+        if instance_path(&manifest.overrides).is_none() {
+            return Err(PackError::InvalidPack {
+                reason: format!("invalid overrides folder {}", manifest.overrides),
+            });
+        }
+
+        Ok(Self {
+            path: path.to_path_buf(),
+            manifest,
+        })
+    }
+
+    /// Returns the pack's manifest.
+    pub fn manifest(&self) -> &CurseForgeManifest {
+        &self.manifest
+    }
+
+    /// Installs the pack's required files for `side` and its overrides into `dir`.
+    ///
+    /// Download URLs and project classes are looked up through `client`.
+    /// Mods go into `mods/`, resource packs into `resourcepacks/` and shader
+    /// packs into `shaderpacks/`; projects of other classes are skipped.
+    /// Mods tagged for the other side only, and resource and shader packs on
+    /// a server, are left out. Files are copied from `cache_dir` when a file
+    /// named after their SHA-1 is there, as for mrpacks.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a file is unknown to CurseForge, its author
+    /// disabled third-party downloads, or it cannot be downloaded.
+    pub async fn install(
+        &self,
+        dir: &Path,
+        side: PackSide,
+        client: &CurseForgeClient,
+        cache_dir: Option<&Path>,
+    ) -> Result<()> {
+        let required: Vec<&ManifestFile> =
+            self.manifest.files.iter().filter(|f| f.required).collect();
+        let ids: Vec<u64> = required.iter().map(|f| f.file_id).collect();
+        let files: HashMap<u64, _> = client
+            .files(&ids)
+            .await?
+            .into_iter()
+            .map(|file| (file.id, file))
+            .collect();
+        let project_ids: Vec<u64> = required.iter().map(|f| f.project_id).collect();
+        let classes: HashMap<u64, Option<u32>> = client
+            .mods(&project_ids)
+            .await?
+            .into_iter()
+            .map(|curse_mod| (curse_mod.id, curse_mod.class_id))
+            .collect();
+
+        let mut installed = 0;
+        for entry in required {
+            let label = format!("project {} file {}", entry.project_id, entry.file_id);
+            let Some(file) = files.get(&entry.file_id) else {
+                return Err(PackError::Download {
+                    path: label,
+                    reason: "file not found on CurseForge".to_string(),
+                });
+            };
+            let class_id = classes.get(&entry.project_id).copied().flatten();
+            let Some(folder) = class_dir(class_id) else {
+                warn!(file = %label, class_id, "Skipping file of unsupported CurseForge class");
+                continue;
+            };
+            if !is_needed_on(file, folder, side) {
+                continue;
+            }
+            let Some(url) = &file.download_url else {
+                return Err(PackError::Download {
+                    path: label,
+                    reason: "the author does not allow third-party downloads".to_string(),
+                });
+            };
+            let pack_path = format!("{folder}/{}", file.file_name.as_deref().unwrap_or_default());
+            let Some(relative) = instance_path(&pack_path).filter(|p| p.components().count() == 2)
+            else {
+                return Err(PackError::InvalidPack {
+                    reason: format!("{label} has an invalid file name"),
+                });
+            };
+
+            fetch_file(
+                &pack_path,
+                std::slice::from_ref(url),
+                file.sha1(),
+                &dir.join(relative),
+                cache_dir,
+            )
+            .await?;
+            installed += 1;
+        }
+
+        let extracted = self.extract_overrides(dir)?;
+        debug!(
+            pack = %self.manifest.name,
+            version = %self.manifest.version,
+            files = installed,
+            overrides = extracted,
+            "Installed CurseForge pack"
+        );
+        Ok(())
+    }
+
+    /// Extracts the overrides into `dir`, and returns how many files were written.
+    pub fn extract_overrides(&self, dir: &Path) -> Result<usize> {
+        extract_dir(&self.path, &self.manifest.overrides, dir)
+    }
+}
+
+/// Returns the instance folder for files of a CurseForge class, or `None`
+/// for classes that are not installed as a file, such as worlds.
+///
+/// Projects whose class is unknown are taken to be mods.
+fn class_dir(class_id: Option<u32>) -> Option<&'static str> {
+    match class_id {
+        Some(MODS_CLASS) | None => Some("mods"),
+        Some(RESOURCE_PACKS_CLASS) => Some("resourcepacks"),
+        Some(SHADERS_CLASS) => Some("shaderpacks"),
+        Some(_) => None,
+    }
+}
+
+/// Returns whether a file in `folder` is installed on `side`.
+///
+/// Resource and shader packs are only used by clients. Mods are installed
+/// unless their `Client` and `Server` tags leave out the side.
+fn is_needed_on(file: &CurseForgeFile, folder: &str, side: PackSide) -> bool {
+    if folder != "mods" {
+        return side == PackSide::Client;
+    }
+    let (client, server) = file.side_support();
+    let support = match side {
+        PackSide::Client => client,
+        PackSide::Server => server,
+    };
+    support != SideSupport::Unsupported
+}
+
+/// Writes a CurseForge pack from a manifest and local files embedded as overrides.
+#[derive(Debug, Clone)]
+pub struct CurseForgePackWriter {
+    manifest: CurseForgeManifest,
+    overrides: Vec<(String, PathBuf)>,
+}
+
+impl CurseForgePackWriter {
+    /// Creates a writer for a pack with the given manifest.
+    pub fn new(manifest: CurseForgeManifest) -> Self {
+        Self {
+            manifest,
+            overrides: Vec::new(),
+        }
+    }
+
+    /// Adds a mod file to download into the instance.
+    pub fn add_file(&mut self, file: ManifestFile) {
+        self.manifest.files.push(file);
+    }
+
+    /// Embeds the local file `source` as an override at `path` inside the instance.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `path` would leave the instance.
+    pub fn add_override(&mut self, path: &str, source: impl Into<PathBuf>) -> Result<()> {
+        if instance_path(path).is_none() {
+            return Err(PackError::InvalidPack {
+                reason: format!("override path {path} leaves the instance"),
+            });
+        }
+        self.overrides.push((path.to_string(), source.into()));
+        Ok(())
+    }
+
+    /// Returns the manifest written so far.
+    pub fn manifest(&self) -> &CurseForgeManifest {
+        &self.manifest
+    }
+
+    /// Writes the pack to `dest`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if an override cannot be read or the pack cannot be written.
+    pub fn write(&self, dest: &Path) -> Result<()> {
+        write_archive(
+            dest,
+            MANIFEST_FILE,
+            &self.manifest,
+            &self.manifest.overrides,
+            &self.overrides,
+        )?;
+        debug!(
+            pack = %self.manifest.name,
+            dest = %dest.display(),
+            files = self.manifest.files.len(),
+            overrides = self.overrides.len(),
+            "Wrote CurseForge pack"
+        );
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use sha1::{Digest, Sha1};
+    use wiremock::matchers::{header, method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    #[tokio::test]
+    async fn installs_written_pack() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/v1/mods/files"))
+            .and(header("x-api-key", "key"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({"data": [
+                file_json(&server, 5001, 238222, "jei.jar", &["1.21.1"]),
+                file_json(&server, 5002, 306612, "zoomify.jar", &["1.21.1", "Client"]),
+                file_json(&server, 5003, 457153, "faithful.zip", &["1.21.1"]),
+            ]})))
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .and(path("/v1/mods"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({"data": [
+                {"id": 238222, "slug": "jei", "name": "JEI", "classId": 6},
+                {"id": 306612, "slug": "zoomify", "name": "Zoomify", "classId": 6},
+                {"id": 457153, "slug": "faithful", "name": "Faithful", "classId": 12},
+            ]})))
+            .mount(&server)
+            .await;
+        for name in ["jei.jar", "zoomify.jar", "faithful.zip"] {
+            Mock::given(method("GET"))
+                .and(path(format!("/files/{name}")))
+                .respond_with(ResponseTemplate::new(200).set_body_bytes(name.as_bytes().to_vec()))
+                .mount(&server)
+                .await;
+        }
+
+        let dir = tempfile::tempdir().unwrap();
+        let config = dir.path().join("jei-client.toml");
+        std::fs::write(&config, "cheats = false").unwrap();
+
+        let mut manifest = CurseForgeManifest::new("Server Mods", "1.0.0", "1.21.1");
+        manifest
+            .minecraft
+            .mod_loaders
+            .extend(ManifestModLoader::new(&ModLoader::NeoForge, "21.1.77"));
+        let mut writer = CurseForgePackWriter::new(manifest);
+        writer.add_file(ManifestFile {
+            project_id: 238222,
+            file_id: 5001,
+            required: true,
+        });
+        for (project_id, file_id) in [(306612, 5002), (457153, 5003)] {
+            writer.add_file(ManifestFile {
+                project_id,
+                file_id,
+                required: true,
+            });
+        }
+        writer.add_file(ManifestFile {
+            project_id: 1,
+            file_id: 2,
+            required: false,
+        });
+        writer
+            .add_override("config/jei-client.toml", &config)
+            .unwrap();
+        let pack_path = dir.path().join("pack.zip");
+        writer.write(&pack_path).unwrap();
+
+        let pack = CurseForgePack::open(&pack_path).unwrap();
+        let loader = pack.manifest().loader().unwrap();
+        assert_eq!(loader.loader(), ModLoader::NeoForge);
+        assert_eq!(loader.version(), Some("21.1.77"));
+
+        let client = CurseForgeClient::new("key").with_base_url(server.uri());
+        let server_dir = dir.path().join("server");
+        pack.install(&server_dir, PackSide::Server, &client, None)
+            .await
+            .unwrap();
+        let client_dir = dir.path().join("client");
+        pack.install(&client_dir, PackSide::Client, &client, None)
+            .await
+            .unwrap();
+
+        let read = |p: &str| std::fs::read_to_string(server_dir.join(p)).unwrap();
+        assert_eq!(read("mods/jei.jar"), "jei.jar");
+        assert_eq!(read("config/jei-client.toml"), "cheats = false");
+        assert!(!server_dir.join("mods/zoomify.jar").exists());
+        assert!(!server_dir.join("resourcepacks/faithful.zip").exists());
+        assert!(client_dir.join("mods/zoomify.jar").is_file());
+        assert!(client_dir.join("resourcepacks/faithful.zip").is_file());
+        assert!(!client_dir.join("mods/faithful.zip").exists());
+    }
+
+    fn file_json(
+        server: &MockServer,
+        id: u64,
+        mod_id: u64,
+        name: &str,
+        game_versions: &[&str],
+    ) -> serde_json::Value {
+        json!({
+            "id": id,
+            "modId": mod_id,
+            "fileName": name,
+            "gameVersions": game_versions,
+            "downloadUrl": format!("{}/files/{name}", server.uri()),
+            "hashes": [{"value": format!("{:x}", Sha1::digest(name.as_bytes())), "algo": 1}]
+        })
+    }
+}
@@ -0,0 +1,41 @@
+use mc_link_platform::PlatformError;
+use thiserror::Error;
+
+/// Errors that can occur while reading or installing a modpack.
+#[derive(Error, Debug)]
+pub enum PackError {
+    /// The pack is not a readable zip archive
+    #[error("Zip error: {0}")]
+    Zip(#[from] zip::result::ZipError),
+
+    /// The pack does not follow its format
+    #[error("Invalid pack: {reason}")]
+    InvalidPack { reason: String },
+
+    /// None of a file's download URLs worked
+    #[error("Failed to download {path}: {reason}")]
+    Download { path: String, reason: String },
+
+    /// A platform request failed
+    #[error("Platform error: {0}")]
+    Platform(#[from] PlatformError),
+
+    /// IO error occurred
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+
+    /// JSON parsing error
+    #[error("JSON error: {0}")]
+    Json(#[from] serde_json::Error),
+
+    /// TOML parsing error
+    #[error("TOML error: {0}")]
+    Toml(#[from] toml::de::Error),
+
+    /// TOML serialization error
+    #[error("TOML serialization error: {0}")]
+    TomlSerialize(#[from] toml::ser::Error),
+}
+
+/// Result type for pack operations.
+pub type Result<T> = std::result::Result<T, PackError>;
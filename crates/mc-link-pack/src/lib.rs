@@ -0,0 +1,20 @@
+//! Modpack formats.
+//!
+//! This crate reads modpacks published on mod hosting platforms or kept in
+//! git with packwiz, so a pack can stand in for a live instance when
+//! comparing and syncing mods, and writes them so an instance's mod set can
+//! be published.
+
+#![cfg_attr(not(debug_assertions), forbid(missing_docs))]
+
+mod archive;
+pub mod curseforge;
+pub mod error;
+pub mod mrpack;
+pub mod packwiz;
+pub mod prelude;
+
+pub use curseforge::*;
+pub use error::*;
+pub use mrpack::*;
+pub use packwiz::*;
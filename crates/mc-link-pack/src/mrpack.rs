@@ -0,0 +1,530 @@
+//! Modrinth modpacks (`.mrpack`).
+//!
+//! An mrpack is a zip archive holding a `modrinth.index.json`, which lists
+//! the files to download along with their hashes and the sides they run on,
+//! and override folders copied over the instance afterwards: `overrides/`
+//! for every side, then `client-overrides/` or `server-overrides/`.
+
+use crate::archive::{
+    check_hash, extract_dir, fetch_file, instance_path, is_sha1, read_index, write_archive,
+};
+use crate::{PackError, Result};
+use mc_link_core::{ModLoader, ModSide, SideSupport};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use tracing::debug;
+
+/// Name of the index file inside an mrpack.
+pub const INDEX_FILE: &str = "modrinth.index.json";
+
+/// Folder of overrides applied on every side.
+pub const OVERRIDES_DIR: &str = "overrides";
+
+/// Which side of the game a pack is installed for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum PackSide {
+    /// A game client
+    Client,
+    /// A dedicated server
+    Server,
+}
+
+impl PackSide {
+    /// Returns the folder of overrides applied only on this side.
+    pub fn overrides_dir(self) -> &'static str {
+        match self {
+            PackSide::Client => "client-overrides",
+            PackSide::Server => "server-overrides",
+        }
+    }
+}
+
+/// The `modrinth.index.json` of an mrpack.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MrpackIndex {
+    /// Version of the format, currently always 1
+    pub format_version: u32,
+    /// Game the pack is for, currently always `minecraft`
+    pub game: String,
+    /// Version of the pack
+    pub version_id: String,
+    /// Name of the pack
+    pub name: String,
+    /// Short description of the pack
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub summary: Option<String>,
+    /// Files to download into the instance
+    #[serde(default)]
+    pub files: Vec<MrpackFile>,
+    /// Versions of `minecraft` and the loader, keyed by their IDs
+    #[serde(default)]
+    pub dependencies: HashMap<String, String>,
+}
+
+impl MrpackIndex {
+    /// Creates an empty Minecraft pack index.
+    pub fn new(name: impl Into<String>, version_id: impl Into<String>) -> Self {
+        Self {
+            format_version: 1,
+            game: "minecraft".to_string(),
+            version_id: version_id.into(),
+            name: name.into(),
+            summary: None,
+            files: Vec::new(),
+            dependencies: HashMap::new(),
+        }
+    }
+
+    /// Returns the Minecraft version the pack is built for.
+    pub fn minecraft_version(&self) -> Option<&str> {
+        self.dependencies.get("minecraft").map(String::as_str)
+    }
+
+    /// Returns the loader the pack is built for.
+    pub fn loader(&self) -> ModLoader {
+        let has = |id: &str| self.dependencies.contains_key(id);
+        if has("neoforge") {
+            ModLoader::NeoForge
+        } else if has("fabric-loader") || has("quilt-loader") {
+            ModLoader::Fabric
+        } else if has("forge") {
+            ModLoader::Forge
+        } else {
+            ModLoader::Unknown
+        }
+    }
+
+    /// Returns the version of the loader the pack is built for.
+    ///
+    /// Quilt versions are not returned, since they do not match the Fabric
+    /// loader ranges mods declare.
+    pub fn loader_version(&self) -> Option<&str> {
+        ["neoforge", "fabric-loader", "forge"]
+            .iter()
+            .find_map(|id| self.dependencies.get(*id))
+            .map(String::as_str)
+    }
+}
+
+/// A file an mrpack downloads into the instance.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MrpackFile {
+    /// Destination relative to the instance root, e.g. `mods/sodium.jar`
+    pub path: String,
+    /// Hashes of the file
+    pub hashes: MrpackHashes,
+    /// Sides the file is installed on; every side if missing
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub env: Option<MrpackEnv>,
+    /// URLs the file can be downloaded from, tried in order
+    pub downloads: Vec<String>,
+    /// Size of the file in bytes
+    pub file_size: u64,
+}
+
+impl MrpackFile {
+    /// Returns whether the file is installed on `side`.
+    pub fn is_needed_on(&self, side: PackSide) -> bool {
+        let Some(env) = &self.env else {
+            return true;
+        };
+        let support = match side {
+            PackSide::Client => env.client,
+            PackSide::Server => env.server,
+        };
+        support != SideSupport::Unsupported
+    }
+}
+
+/// Hex-encoded hashes of a pack file.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MrpackHashes {
+    /// SHA-1 of the file
+    pub sha1: String,
+    /// SHA-512 of the file
+    pub sha512: String,
+}
+
+/// Whether a pack file is installed on each side.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct MrpackEnv {
+    /// Support on game clients
+    pub client: SideSupport,
+    /// Support on dedicated servers
+    pub server: SideSupport,
+}
+
+impl MrpackEnv {
+    /// Returns the sides a mod running on `side` is installed on, or `None`
+    /// when the side is unknown and the mod goes everywhere.
+    pub fn for_side(side: ModSide) -> Option<Self> {
+        use SideSupport::{Required, Unsupported};
+        let (client, server) = match side {
+            ModSide::Client => (Required, Unsupported),
+            ModSide::Server => (Unsupported, Required),
+            ModSide::Both => (Required, Required),
+            ModSide::Unknown => return None,
+        };
+        Some(Self { client, server })
+    }
+}
+
+/// An mrpack on disk.
+#[derive(Debug, Clone)]
+pub struct Mrpack {
+    path: PathBuf,
+    index: MrpackIndex,
+}
+
+impl Mrpack {
+    /// Opens an mrpack and reads its index.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file is not a zip archive or has no valid index.
+    pub fn open(path: &Path) -> Result<Self> {
+        let index: MrpackIndex = read_index(path, INDEX_FILE)?;
+        for file in &index.files {
+            if instance_path(&file.path).is_none() {
+                return Err(PackError::InvalidPack {
+                    reason: format!("file path {} leaves the instance", file.path),
+                });
+            }
+            // The SHA-1 names the file in the download cache
+            if !is_sha1(&file.hashes.sha1) {
+                return Err(PackError::InvalidPack {
+                    reason: format!("file {} has an invalid SHA-1", file.path),
+                });
+            }
+        }
+
+        Ok(Self {
+            path: path.to_path_buf(),
+            index,
+        })
+    }
+
+    /// Returns the pack's index.
+    pub fn index(&self) -> &MrpackIndex {
+        &self.index
+    }
+
+    /// Installs the pack for `side` into `dir`.
+    ///
+    /// Files are copied from `cache_dir` when a file with their SHA-1 as name
+    /// is there, and otherwise downloaded from the first URL that works and
+    /// added to the cache. Either way their SHA-512 is checked. Overrides are
+    /// extracted afterwards.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a file cannot be downloaded from any of its URLs,
+    /// does not match its hashes, or the instance cannot be written.
+    pub async fn install(
+        &self,
+        dir: &Path,
+        side: PackSide,
+        cache_dir: Option<&Path>,
+    ) -> Result<()> {
+        for file in self.index.files.iter().filter(|f| f.is_needed_on(side)) {
+            let Some(relative) = instance_path(&file.path) else {
+                continue;
+            };
+            let dest = dir.join(relative);
+            fetch_file(
+                &file.path,
+                &file.downloads,
+                Some(&file.hashes.sha1),
+                &dest,
+                cache_dir,
+            )
+            .await?;
+            check_hash(&dest, "sha512", &file.hashes.sha512)?;
+        }
+
+        let extracted = self.extract_overrides(dir, side)?;
+        debug!(
+            pack = %self.index.name,
+            version = %self.index.version_id,
+            files = self.index.files.len(),
+            overrides = extracted,
+            "Installed mrpack"
+        );
+        Ok(())
+    }
+
+    /// Extracts the overrides for `side` into `dir`, and returns how many
+    /// files were written.
+    ///
+    /// Side-specific overrides are extracted last, so they win over the
+    /// shared ones.
+    pub fn extract_overrides(&self, dir: &Path, side: PackSide) -> Result<usize> {
+        let shared = extract_dir(&self.path, OVERRIDES_DIR, dir)?;
+        Ok(shared + extract_dir(&self.path, side.overrides_dir(), dir)?)
+    }
+}
+
+/// Writes an mrpack from an index and local files embedded as overrides.
+#[derive(Debug, Clone)]
+pub struct MrpackWriter {
+    index: MrpackIndex,
+    overrides: Vec<(String, PathBuf)>,
+}
+
+impl MrpackWriter {
+    /// Creates a writer for a pack with the given index.
+    pub fn new(index: MrpackIndex) -> Self {
+        Self {
+            index,
+            overrides: Vec::new(),
+        }
+    }
+
+    /// Adds a file to download into the instance.
+    pub fn add_file(&mut self, file: MrpackFile) {
+        self.index.files.push(file);
+    }
+
+    /// Embeds the local file `source` as an override at `path` inside the instance.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `path` would leave the instance.
+    pub fn add_override(&mut self, path: &str, source: impl Into<PathBuf>) -> Result<()> {
+        if instance_path(path).is_none() {
+            return Err(PackError::InvalidPack {
+                reason: format!("override path {path} leaves the instance"),
+            });
+        }
+        self.overrides.push((path.to_string(), source.into()));
+        Ok(())
+    }
+
+    /// Returns the index written so far.
+    pub fn index(&self) -> &MrpackIndex {
+        &self.index
+    }
+
+    /// Writes the pack to `dest`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if an override cannot be read or the pack cannot be written.
+    pub fn write(&self, dest: &Path) -> Result<()> {
+        write_archive(
+            dest,
+            INDEX_FILE,
+            &self.index,
+            OVERRIDES_DIR,
+            &self.overrides,
+        )?;
+
+        debug!(
+            pack = %self.index.name,
+            dest = %dest.display(),
+            files = self.index.files.len(),
+            overrides = self.overrides.len(),
+            "Wrote mrpack"
+        );
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use sha1::Sha1;
+    use sha2::{Digest, Sha512};
+    use std::fs::File;
+    use std::io::Write;
+    use wiremock::matchers::{method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    use zip::write::SimpleFileOptions;
+
+    fn write_pack(path: &Path, index: serde_json::Value, entries: &[(&str, &str)]) {
+        let mut zip = zip::ZipWriter::new(File::create(path).unwrap());
+        zip.start_file(INDEX_FILE, SimpleFileOptions::default())
+            .unwrap();
+        zip.write_all(index.to_string().as_bytes()).unwrap();
+        for (name, contents) in entries {
+            zip.start_file(*name, SimpleFileOptions::default()).unwrap();
+            zip.write_all(contents.as_bytes()).unwrap();
+        }
+        zip.finish().unwrap();
+    }
+
+    fn pack_file(path: &str, contents: &[u8], url: String, server: &str) -> serde_json::Value {
+        json!({
+            "path": path,
+            "hashes": {"sha1": format!("{:x}", Sha1::digest(contents)), "sha512": format!("{:x}", Sha512::digest(contents))},
+            "env": {"client": "required", "server": server},
+            "downloads": [url],
+            "fileSize": contents.len()
+        })
+    }
+
+    #[tokio::test]
+    async fn installs_server_side_of_pack() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/lithium.jar"))
+            .respond_with(ResponseTemplate::new(200).set_body_bytes(b"lithium".to_vec()))
+            .expect(1)
+            .mount(&server)
+            .await;
+        let dir = tempfile::tempdir().unwrap();
+        let pack_path = dir.path().join("pack.mrpack");
+        write_pack(
+            &pack_path,
+            json!({
+                "formatVersion": 1,
+                "game": "minecraft",
+                "versionId": "1.2.0",
+                "name": "Official Pack",
+                "files": [
+                    pack_file("mods/lithium.jar", b"lithium", format!("{}/lithium.jar", server.uri()), "required"),
+                    pack_file("mods/sodium.jar", b"sodium", format!("{}/sodium.jar", server.uri()), "unsupported"),
+                ],
+                "dependencies": {"minecraft": "1.21.1", "fabric-loader": "0.16.5"}
+            }),
+            &[
+                ("overrides/config/shared.toml", "shared"),
+                ("overrides/config/side.toml", "both"),
+                ("server-overrides/config/side.toml", "server"),
+                ("client-overrides/options.txt", "client"),
+            ],
+        );
+
+        let pack = Mrpack::open(&pack_path).unwrap();
+        assert_eq!(pack.index().minecraft_version(), Some("1.21.1"));
+        assert_eq!(pack.index().loader(), ModLoader::Fabric);
+
+        let cache = dir.path().join("cache");
+        for instance in ["first", "second"] {
+            let instance = dir.path().join(instance);
+            pack.install(&instance, PackSide::Server, Some(&cache))
+                .await
+                .unwrap();
+
+            let read = |p: &str| std::fs::read_to_string(instance.join(p)).unwrap();
+            assert_eq!(read("mods/lithium.jar"), "lithium");
+            assert!(!instance.join("mods/sodium.jar").exists());
+            assert_eq!(read("config/shared.toml"), "shared");
+            assert_eq!(read("config/side.toml"), "server");
+            assert!(!instance.join("options.txt").exists());
+        }
+    }
+
+    #[tokio::test]
+    async fn replaces_corrupt_cached_file() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/lithium.jar"))
+            .respond_with(ResponseTemplate::new(200).set_body_bytes(b"lithium".to_vec()))
+            .expect(1)
+            .mount(&server)
+            .await;
+        let dir = tempfile::tempdir().unwrap();
+        let pack_path = dir.path().join("pack.mrpack");
+        write_pack(
+            &pack_path,
+            json!({
+                "formatVersion": 1,
+                "game": "minecraft",
+                "versionId": "1.0.0",
+                "name": "Pack",
+                "files": [
+                    pack_file("mods/lithium.jar", b"lithium", format!("{}/lithium.jar", server.uri()), "required"),
+                ],
+                "dependencies": {"minecraft": "1.21.1"}
+            }),
+            &[],
+        );
+        let cache = dir.path().join("cache");
+        let cached = cache.join(format!("{:x}", Sha1::digest(b"lithium")));
+        std::fs::create_dir_all(&cache).unwrap();
+        std::fs::write(&cached, "truncated").unwrap();
+
+        let instance = dir.path().join("instance");
+        Mrpack::open(&pack_path)
+            .unwrap()
+            .install(&instance, PackSide::Server, Some(&cache))
+            .await
+            .unwrap();
+
+        assert_eq!(
+            std::fs::read_to_string(instance.join("mods/lithium.jar")).unwrap(),
+            "lithium"
+        );
+        assert_eq!(std::fs::read_to_string(cached).unwrap(), "lithium");
+    }
+
+    #[test]
+    fn writes_pack_that_reads_back() {
+        let dir = tempfile::tempdir().unwrap();
+        let embedded = dir.path().join("custom.jar");
+        std::fs::write(&embedded, "custom").unwrap();
+
+        let mut index = MrpackIndex::new("Server Mods", "2024.1");
+        index
+            .dependencies
+            .insert("minecraft".to_string(), "1.21.1".to_string());
+        let mut writer = MrpackWriter::new(index);
+        writer.add_file(MrpackFile {
+            path: "mods/lithium.jar".to_string(),
+            hashes: MrpackHashes {
+                sha1: "a9993e364706816aba3e25717850c26c9cd0d89d".to_string(),
+                sha512: "cd".to_string(),
+            },
+            env: MrpackEnv::for_side(ModSide::Server),
+            downloads: vec!["https://cdn.modrinth.com/lithium.jar".to_string()],
+            file_size: 7,
+        });
+        writer.add_override("mods/custom.jar", &embedded).unwrap();
+        assert!(writer.add_override("../custom.jar", &embedded).is_err());
+        let pack_path = dir.path().join("out.mrpack");
+        writer.write(&pack_path).unwrap();
+
+        let pack = Mrpack::open(&pack_path).unwrap();
+        assert_eq!(pack.index().name, "Server Mods");
+        assert_eq!(pack.index().minecraft_version(), Some("1.21.1"));
+        let file = &pack.index().files[0];
+        assert!(!file.is_needed_on(PackSide::Client));
+        assert!(file.is_needed_on(PackSide::Server));
+
+        let instance = dir.path().join("instance");
+        assert_eq!(
+            pack.extract_overrides(&instance, PackSide::Client).unwrap(),
+            1
+        );
+        assert_eq!(
+            std::fs::read_to_string(instance.join("mods/custom.jar")).unwrap(),
+            "custom"
+        );
+    }
+
+    #[test]
+    fn rejects_paths_outside_instance() {
+        let dir = tempfile::tempdir().unwrap();
+        let pack_path = dir.path().join("pack.mrpack");
+        write_pack(
+            &pack_path,
+            json!({
+                "formatVersion": 1,
+                "game": "minecraft",
+                "versionId": "1",
+                "name": "Bad",
+                "files": [pack_file("../evil.jar", b"x", "https://example.com".into(), "required")]
+            }),
+            &[],
+        );
+
+        let err = Mrpack::open(&pack_path).unwrap_err();
+        assert!(matches!(err, PackError::InvalidPack { .. }));
+    }
+}
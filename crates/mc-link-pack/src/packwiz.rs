@@ -0,0 +1,720 @@
+//! packwiz modpacks.
+//!
+//! A packwiz pack is a directory, usually kept in git, holding a `pack.toml`
+//! with the pack's Minecraft and loader versions, and an `index.toml` listing
+//! every file of the pack with its hash. Mods are listed as `.pw.toml`
+//! metafiles that say where to download the jar from; other files, such as
+//! configs, are stored in the pack as they are.
+
+use crate::archive::{check_hash, digest, fetch_file, instance_path, is_supported_hash};
+use crate::{PackError, PackSide, Result};
+use mc_link_core::{ModLoader, ModSide};
+use mc_link_platform::CurseForgeClient;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use tracing::debug;
+
+/// Name of the pack file at the root of a packwiz pack.
+pub const PACK_FILE: &str = "pack.toml";
+
+/// Name packwiz gives the index file.
+pub const INDEX_TOML: &str = "index.toml";
+
+/// Suffix of the metafiles that describe mods.
+pub const METAFILE_SUFFIX: &str = ".pw.toml";
+
+/// Version of the packwiz format written.
+const PACK_FORMAT: &str = "packwiz:1.1.0";
+
+/// Hash format of the index and the files it lists.
+const HASH_FORMAT: &str = "sha256";
+
+/// The `pack.toml` of a packwiz pack.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct PackwizPack {
+    /// Name of the pack
+    pub name: String,
+    /// Author of the pack
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub author: Option<String>,
+    /// Version of the pack
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub version: Option<String>,
+    /// Short description of the pack
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    /// Version of the packwiz format
+    #[serde(default)]
+    pub pack_format: String,
+    /// Where the index is and what it hashes to
+    pub index: IndexRef,
+    /// Versions of `minecraft` and the loader, keyed by their IDs
+    pub versions: BTreeMap<String, String>,
+}
+
+impl PackwizPack {
+    /// Creates a pack file for a pack with the given name.
+    pub fn new(name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            author: None,
+            version: None,
+            description: None,
+            pack_format: PACK_FORMAT.to_string(),
+            index: IndexRef {
+                file: INDEX_TOML.to_string(),
+                hash_format: HASH_FORMAT.to_string(),
+                hash: String::new(),
+            },
+            versions: BTreeMap::new(),
+        }
+    }
+
+    /// Returns the Minecraft version the pack is built for.
+    pub fn minecraft_version(&self) -> Option<&str> {
+        self.versions.get("minecraft").map(String::as_str)
+    }
+
+    /// Returns the loader the pack is built for.
+    pub fn loader(&self) -> ModLoader {
+        let has = |id: &str| self.versions.contains_key(id);
+        if has("neoforge") {
+            ModLoader::NeoForge
+        } else if has("fabric") || has("quilt") {
+            ModLoader::Fabric
+        } else if has("forge") {
+            ModLoader::Forge
+        } else {
+            ModLoader::Unknown
+        }
+    }
+
+    /// Returns the version of the loader the pack is built for.
+    ///
+    /// Quilt versions are not returned, since they do not match the Fabric
+    /// loader ranges mods declare.
+    pub fn loader_version(&self) -> Option<&str> {
+        ["neoforge", "fabric", "forge"]
+            .iter()
+            .find_map(|id| self.versions.get(*id))
+            .map(String::as_str)
+    }
+
+    /// Sets the version of a loader, or does nothing for an unknown loader.
+    pub fn set_loader(&mut self, loader: &ModLoader, version: impl Into<String>) {
+        let id = match loader {
+            ModLoader::NeoForge => "neoforge",
+            ModLoader::Fabric => "fabric",
+            ModLoader::Forge => "forge",
+            ModLoader::Unknown => return,
+        };
+        self.versions.insert(id.to_string(), version.into());
+    }
+}
+
+/// Where the index of a packwiz pack is.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct IndexRef {
+    /// Path of the index relative to the pack file
+    pub file: String,
+    /// Algorithm of `hash`
+    pub hash_format: String,
+    /// Hash of the index file
+    pub hash: String,
+}
+
+/// The `index.toml` of a packwiz pack.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct PackwizIndex {
+    /// Algorithm of the file hashes, unless a file names its own
+    pub hash_format: String,
+    /// Files of the pack
+    #[serde(default)]
+    pub files: Vec<IndexEntry>,
+}
+
+/// A file listed in a packwiz index.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct IndexEntry {
+    /// Path relative to the index, e.g. `mods/jei.pw.toml`
+    pub file: String,
+    /// Hash of the file
+    pub hash: String,
+    /// Algorithm of `hash`, if it differs from the index's
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub hash_format: Option<String>,
+    /// Path the file is installed at instead of `file`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub alias: Option<String>,
+    /// Whether the file is a `.pw.toml` metafile describing a download
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub metafile: bool,
+    /// Whether launchers keep the player's changes to the file
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub preserve: bool,
+}
+
+/// A `.pw.toml` metafile describing a mod to download.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct PackwizMod {
+    /// Display name of the mod
+    pub name: String,
+    /// File name the jar is saved as, next to the metafile
+    pub filename: String,
+    /// Side the mod is installed on
+    #[serde(default)]
+    pub side: PackwizSide,
+    /// Where the jar is downloaded from
+    pub download: ModDownload,
+    /// Where packwiz looks for updates
+    #[serde(default, skip_serializing_if = "ModUpdate::is_empty")]
+    pub update: ModUpdate,
+}
+
+impl PackwizMod {
+    /// Returns the path of the metafile packwiz would give this mod.
+    pub fn metafile_path(&self, dir: &str) -> String {
+        let stem = self.filename.strip_suffix(".jar").unwrap_or(&self.filename);
+        format!("{dir}/{stem}{METAFILE_SUFFIX}")
+    }
+}
+
+/// Side a packwiz mod is installed on.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PackwizSide {
+    /// Only game clients
+    Client,
+    /// Only dedicated servers
+    Server,
+    /// Every side
+    #[default]
+    Both,
+}
+
+impl PackwizSide {
+    /// Returns the side a mod is installed on from where it runs.
+    pub fn for_side(side: ModSide) -> Self {
+        match side {
+            ModSide::Client => PackwizSide::Client,
+            ModSide::Server => PackwizSide::Server,
+            ModSide::Both | ModSide::Unknown => PackwizSide::Both,
+        }
+    }
+
+    /// Returns whether a mod with this side is installed on `side`.
+    pub fn is_needed_on(self, side: PackSide) -> bool {
+        matches!(
+            (self, side),
+            (PackwizSide::Both, _)
+                | (PackwizSide::Client, PackSide::Client)
+                | (PackwizSide::Server, PackSide::Server)
+        )
+    }
+}
+
+/// Where a packwiz mod is downloaded from.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct ModDownload {
+    /// Download URL, missing for CurseForge files looked up through the API
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub url: Option<String>,
+    /// Algorithm of `hash`
+    pub hash_format: String,
+    /// Hash of the jar
+    pub hash: String,
+    /// How the download is found, `metadata:curseforge` for the CurseForge API
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub mode: Option<String>,
+}
+
+/// Download mode of files looked up through the CurseForge API.
+pub const CURSEFORGE_MODE: &str = "metadata:curseforge";
+
+/// Platforms packwiz checks for updates of a mod.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ModUpdate {
+    /// The mod's Modrinth version
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub modrinth: Option<ModrinthUpdate>,
+    /// The mod's CurseForge file
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub curseforge: Option<CurseForgeUpdate>,
+}
+
+impl ModUpdate {
+    /// Returns whether no platform is listed.
+    pub fn is_empty(&self) -> bool {
+        self.modrinth.is_none() && self.curseforge.is_none()
+    }
+}
+
+/// A Modrinth version of a packwiz mod.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct ModrinthUpdate {
+    /// Modrinth project ID
+    pub mod_id: String,
+    /// Modrinth version ID
+    pub version: String,
+}
+
+/// A CurseForge file of a packwiz mod.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct CurseForgeUpdate {
+    /// CurseForge file ID
+    pub file_id: u64,
+    /// CurseForge project ID
+    pub project_id: u64,
+}
+
+/// A packwiz pack on disk.
+#[derive(Debug, Clone)]
+pub struct Packwiz {
+    index_dir: PathBuf,
+    pack: PackwizPack,
+    index: PackwizIndex,
+}
+
+impl Packwiz {
+    /// Opens the packwiz pack in `dir` and reads its pack file and index.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if either file is missing or invalid, the index is
+    /// hashed with an unsupported algorithm or does not match its hash in the
+    /// pack file, or a file path or alias would leave the pack or instance.
+    pub fn open(dir: &Path) -> Result<Self> {
+        let pack: PackwizPack = read_toml(&dir.join(PACK_FILE))?;
+        let Some(index_path) = instance_path(&pack.index.file) else {
+            return Err(PackError::InvalidPack {
+                reason: format!("invalid index path {}", pack.index.file),
+            });
+        };
+        // The index hash vouches for every other hash, so it cannot be skipped
+        if !is_supported_hash(&pack.index.hash_format) {
+            return Err(PackError::InvalidPack {
+                reason: format!("unsupported index hash format {}", pack.index.hash_format),
+            });
+        }
+        let index_path = dir.join(index_path);
+        check_hash(&index_path, &pack.index.hash_format, &pack.index.hash)?;
+
+        let index: PackwizIndex = read_toml(&index_path)?;
+        if let Some(path) = index
+            .files
+            .iter()
+            .flat_map(|e| [Some(&e.file), e.alias.as_ref()])
+            .flatten()
+            .find(|path| instance_path(path).is_none())
+        {
+            return Err(PackError::InvalidPack {
+                reason: format!("file path {path} leaves the instance"),
+            });
+        }
+
+        Ok(Self {
+            index_dir: index_path.parent().unwrap_or(dir).to_path_buf(),
+            pack,
+            index,
+        })
+    }
+
+    /// Returns the pack file.
+    pub fn pack(&self) -> &PackwizPack {
+        &self.pack
+    }
+
+    /// Returns the index.
+    pub fn index(&self) -> &PackwizIndex {
+        &self.index
+    }
+
+    /// Reads the mod metafiles of the pack, with the index entries they belong to.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a metafile cannot be read or parsed.
+    pub fn mods(&self) -> Result<Vec<(&IndexEntry, PackwizMod)>> {
+        self.index
+            .files
+            .iter()
+            .filter(|entry| entry.metafile)
+            .map(|entry| Ok((entry, read_toml(&self.index_dir.join(&entry.file))?)))
+            .collect()
+    }
+
+    /// Installs the files of the pack needed on `side` into `dir`.
+    ///
+    /// Mods are downloaded next to their metafile, from their URL or, for
+    /// `metadata:curseforge` downloads, from the URL `curseforge` looks up.
+    /// Files are copied from `cache_dir` when a file named after their SHA-1
+    /// is there, as for mrpacks. Other files are copied from the pack.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a file does not match its hash, a CurseForge
+    /// download is needed without a client, or a file cannot be downloaded.
+    pub async fn install(
+        &self,
+        dir: &Path,
+        side: PackSide,
+        curseforge: Option<&CurseForgeClient>,
+        cache_dir: Option<&Path>,
+    ) -> Result<()> {
+        let mut installed = 0;
+        for entry in &self.index.files {
+            let source = self.index_dir.join(&entry.file);
+            let hash_format = entry
+                .hash_format
+                .as_deref()
+                .unwrap_or(&self.index.hash_format);
+            check_hash(&source, hash_format, &entry.hash)?;
+            let relative = instance_path(entry.path()).unwrap_or_default();
+
+            if !entry.metafile {
+                let dest = dir.join(&relative);
+                if let Some(parent) = dest.parent() {
+                    std::fs::create_dir_all(parent)?;
+                }
+                std::fs::copy(&source, &dest)?;
+                installed += 1;
+                continue;
+            }
+
+            let metafile: PackwizMod = read_toml(&source)?;
+            if !metafile.side.is_needed_on(side) {
+                continue;
+            }
+            let Some(file_name) =
+                instance_path(&metafile.filename).filter(|name| name.components().count() == 1)
+            else {
+                return Err(PackError::InvalidPack {
+                    reason: format!("{} has an invalid file name", entry.file),
+                });
+            };
+            let dest = dir
+                .join(relative.parent().unwrap_or(Path::new("")))
+                .join(file_name);
+            let url = self
+                .download_url(&entry.file, &metafile, curseforge)
+                .await?;
+
+            let download = &metafile.download;
+            let sha1 = (download.hash_format == "sha1").then_some(download.hash.as_str());
+            fetch_file(&entry.file, &[url], sha1, &dest, cache_dir).await?;
+            if sha1.is_none() {
+                check_hash(&dest, &download.hash_format, &download.hash)?;
+            }
+            installed += 1;
+        }
+
+        debug!(
+            pack = %self.pack.name,
+            files = installed,
+            "Installed packwiz pack"
+        );
+        Ok(())
+    }
+
+    /// Returns the URL a mod is downloaded from.
+    async fn download_url(
+        &self,
+        path: &str,
+        metafile: &PackwizMod,
+        curseforge: Option<&CurseForgeClient>,
+    ) -> Result<String> {
+        if let Some(url) = &metafile.download.url {
+            return Ok(url.clone());
+        }
+
+        let download_error = |reason: &str| PackError::Download {
+            path: path.to_string(),
+            reason: reason.to_string(),
+        };
+        let file_id = match (&metafile.download.mode, &metafile.update.curseforge) {
+            (Some(mode), Some(update)) if mode == CURSEFORGE_MODE => update.file_id,
+            _ => return Err(download_error("no download URL")),
+        };
+        let Some(client) = curseforge else {
+            return Err(download_error("CurseForge API key is not configured"));
+        };
+        client
+            .files(&[file_id])
+            .await?
+            .into_iter()
+            .find(|file| file.id == file_id)
+            .ok_or_else(|| download_error("file not found on CurseForge"))?
+            .download_url
+            .ok_or_else(|| download_error("the author does not allow third-party downloads"))
+    }
+}
+
+impl IndexEntry {
+    /// Returns the path the file is installed at.
+    pub fn path(&self) -> &str {
+        self.alias.as_deref().unwrap_or(&self.file)
+    }
+}
+
+/// Writes a packwiz pack from mod metafiles and local files.
+#[derive(Debug, Clone)]
+pub struct PackwizWriter {
+    pack: PackwizPack,
+    mods: Vec<(String, PackwizMod)>,
+    files: Vec<(String, PathBuf)>,
+}
+
+impl PackwizWriter {
+    /// Creates a writer for a pack with the given pack file.
+    pub fn new(pack: PackwizPack) -> Self {
+        Self {
+            pack,
+            mods: Vec::new(),
+            files: Vec::new(),
+        }
+    }
+
+    /// Adds a mod, described by a metafile in `dir`, e.g. `mods`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the metafile path would leave the instance.
+    pub fn add_mod(&mut self, dir: &str, metafile: PackwizMod) -> Result<()> {
+        let path = metafile.metafile_path(dir);
+        if instance_path(&path).is_none() {
+            return Err(PackError::InvalidPack {
+                reason: format!("metafile path {path} leaves the instance"),
+            });
+        }
+        self.mods.push((path, metafile));
+        Ok(())
+    }
+
+    /// Stores the local file `source` in the pack at `path` inside the instance.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `path` would leave the instance.
+    pub fn add_file(&mut self, path: &str, source: impl Into<PathBuf>) -> Result<()> {
+        if instance_path(path).is_none() {
+            return Err(PackError::InvalidPack {
+                reason: format!("file path {path} leaves the instance"),
+            });
+        }
+        self.files.push((path.to_string(), source.into()));
+        Ok(())
+    }
+
+    /// Returns the pack file written so far.
+    pub fn pack(&self) -> &PackwizPack {
+        &self.pack
+    }
+
+    /// Writes the pack into `dir`, with its index and pack file.
+    ///
+    /// Files already in `dir` are overwritten when the pack has a file of
+    /// the same path, and otherwise left alone outside the index.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a file cannot be read or written.
+    pub fn write(&self, dir: &Path) -> Result<()> {
+        let mut index = PackwizIndex {
+            hash_format: HASH_FORMAT.to_string(),
+            files: Vec::new(),
+        };
+        let mut add_entry = |path: &str, contents: &[u8], metafile: bool| -> Result<()> {
+            let dest = dir.join(path);
+            if let Some(parent) = dest.parent() {
+                std::fs::create_dir_all(parent)?;
+            }
+            std::fs::write(&dest, contents)?;
+            index.files.push(IndexEntry {
+                file: path.to_string(),
+                hash: digest(HASH_FORMAT, contents).unwrap_or_default(),
+                hash_format: None,
+                alias: None,
+                metafile,
+                preserve: false,
+            });
+            Ok(())
+        };
+
+        for (path, metafile) in &self.mods {
+            add_entry(path, toml::to_string(metafile)?.as_bytes(), true)?;
+        }
+        for (path, source) in &self.files {
+            add_entry(path, &std::fs::read(source)?, false)?;
+        }
+        index.files.sort_by(|a, b| a.file.cmp(&b.file));
+
+        let index_contents = toml::to_string(&index)?;
+        std::fs::write(dir.join(INDEX_TOML), &index_contents)?;
+        let mut pack = self.pack.clone();
+        pack.index = IndexRef {
+            file: INDEX_TOML.to_string(),
+            hash_format: HASH_FORMAT.to_string(),
+            hash: digest(HASH_FORMAT, index_contents.as_bytes()).unwrap_or_default(),
+        };
+        std::fs::write(dir.join(PACK_FILE), toml::to_string(&pack)?)?;
+
+        debug!(
+            pack = %self.pack.name,
+            dir = %dir.display(),
+            mods = self.mods.len(),
+            files = self.files.len(),
+            "Wrote packwiz pack"
+        );
+        Ok(())
+    }
+}
+
+fn read_toml<T: serde::de::DeserializeOwned>(path: &Path) -> Result<T> {
+    let content = std::fs::read_to_string(path).map_err(|e| PackError::InvalidPack {
+        reason: format!("failed to read {}: {e}", path.display()),
+    })?;
+    Ok(toml::from_str(&content)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wiremock::matchers::{method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    fn metafile(name: &str, url: String, hash: &[u8], side: PackwizSide) -> PackwizMod {
+        PackwizMod {
+            name: name.to_string(),
+            filename: format!("{name}.jar"),
+            side,
+            download: ModDownload {
+                url: Some(url),
+                hash_format: "sha512".to_string(),
+                hash: digest("sha512", hash).unwrap(),
+                mode: None,
+            },
+            update: ModUpdate::default(),
+        }
+    }
+
+    #[tokio::test]
+    async fn installs_written_pack() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/files/jei.jar"))
+            .respond_with(ResponseTemplate::new(200).set_body_bytes(b"jei".to_vec()))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let dir = tempfile::tempdir().unwrap();
+        let config = dir.path().join("jei-client.toml");
+        std::fs::write(&config, "cheats = false").unwrap();
+
+        let mut pack = PackwizPack::new("Server Mods");
+        pack.versions
+            .insert("minecraft".to_string(), "1.21.1".to_string());
+        pack.set_loader(&ModLoader::NeoForge, "21.1.77");
+        let mut writer = PackwizWriter::new(pack);
+        let jei = format!("{}/files/jei.jar", server.uri());
+        let sodium = format!("{}/files/sodium.jar", server.uri());
+        writer
+            .add_mod("mods", metafile("jei", jei, b"jei", PackwizSide::Both))
+            .unwrap();
+        writer
+            .add_mod(
+                "mods",
+                metafile("sodium", sodium, b"x", PackwizSide::Client),
+            )
+            .unwrap();
+        writer.add_file("config/jei-client.toml", &config).unwrap();
+        let pack_dir = dir.path().join("pack");
+        writer.write(&pack_dir).unwrap();
+
+        let pack = Packwiz::open(&pack_dir).unwrap();
+        assert_eq!(pack.pack().minecraft_version(), Some("1.21.1"));
+        assert_eq!(pack.pack().loader(), ModLoader::NeoForge);
+        assert_eq!(pack.mods().unwrap().len(), 2);
+
+        let instance = dir.path().join("instance");
+        pack.install(&instance, PackSide::Server, None, None)
+            .await
+            .unwrap();
+
+        let read = |p: &str| std::fs::read_to_string(instance.join(p)).unwrap();
+        assert_eq!(read("mods/jei.jar"), "jei");
+        assert_eq!(read("config/jei-client.toml"), "cheats = false");
+        assert!(!instance.join("mods/sodium.jar").exists());
+    }
+
+    #[test]
+    fn rejects_modified_index() {
+        let dir = tempfile::tempdir().unwrap();
+        PackwizWriter::new(PackwizPack::new("Pack"))
+            .write(dir.path())
+            .unwrap();
+        std::fs::write(dir.path().join(INDEX_TOML), "hash-format = \"sha1\"").unwrap();
+
+        assert!(matches!(
+            Packwiz::open(dir.path()),
+            Err(PackError::InvalidPack { .. })
+        ));
+    }
+
+    /// Writes a pack whose pack file vouches for `index` with `hash_format`.
+    fn write_pack(dir: &Path, index: &str, hash_format: &str) {
+        let mut pack = PackwizPack::new("Pack");
+        pack.index.hash_format = hash_format.to_string();
+        pack.index.hash = digest(hash_format, index.as_bytes()).unwrap_or_default();
+        std::fs::write(dir.join(PACK_FILE), toml::to_string(&pack).unwrap()).unwrap();
+        std::fs::write(dir.join(INDEX_TOML), index).unwrap();
+    }
+
+    #[test]
+    fn rejects_unsupported_index_hash_format() {
+        let dir = tempfile::tempdir().unwrap();
+        write_pack(dir.path(), "hash-format = \"sha256\"", "murmur2");
+
+        assert!(matches!(
+            Packwiz::open(dir.path()),
+            Err(PackError::InvalidPack { .. })
+        ));
+    }
+
+    #[test]
+    fn rejects_aliased_file_outside_the_pack() {
+        let dir = tempfile::tempdir().unwrap();
+        let index = r#"
+            hash-format = "sha256"
+
+            [[files]]
+            file = "../../etc/passwd"
+            hash = "00"
+            alias = "config/passwd"
+        "#;
+        write_pack(dir.path(), index, "sha256");
+
+        assert!(matches!(
+            Packwiz::open(dir.path()),
+            Err(PackError::InvalidPack { reason }) if reason.contains("../../etc/passwd")
+        ));
+
+        let index = index.replace("../../etc/passwd", "config/passwd");
+        let index = index.replace("alias = \"config/passwd\"", "alias = \"../options.txt\"");
+        write_pack(dir.path(), &index, "sha256");
+        assert!(Packwiz::open(dir.path()).is_err());
+
+        let index = index.replace("../options.txt", "options.txt");
+        write_pack(dir.path(), &index, "sha256");
+        assert!(Packwiz::open(dir.path()).is_ok());
+    }
+}
@@ -0,0 +1,16 @@
+//! Prelude module for modpack formats.
+//!
+//! Import with `use mc_link_pack::prelude::*;` to get commonly used pack types.
+
+pub use crate::curseforge::{
+    CurseForgeManifest, CurseForgePack, CurseForgePackWriter, ManifestFile, ManifestMinecraft,
+    ManifestModLoader,
+};
+pub use crate::error::{PackError, Result};
+pub use crate::mrpack::{
+    Mrpack, MrpackEnv, MrpackFile, MrpackHashes, MrpackIndex, MrpackWriter, PackSide,
+};
+pub use crate::packwiz::{
+    CurseForgeUpdate, IndexEntry, IndexRef, ModDownload, ModUpdate, ModrinthUpdate, Packwiz,
+    PackwizIndex, PackwizMod, PackwizPack, PackwizSide, PackwizWriter,
+};
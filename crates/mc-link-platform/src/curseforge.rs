@@ -0,0 +1,546 @@
+//! Client for the CurseForge API.
+//!
+//! Mod files are matched by their murmur2 fingerprint (see
+//! [`curseforge_fingerprint`](mc_link_core::curseforge_fingerprint)), and the
+//! mods they belong to are fetched in one batch for their slug and name.
+
+use crate::Result;
+use crate::error::check_status;
+use crate::http;
+use mc_link_core::{Platform, PlatformInfo, SideSupport};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use tracing::debug;
+
+/// Game versions CurseForge lists for a file that name a mod loader.
+const LOADER_TAGS: [&str; 4] = ["Forge", "NeoForge", "Fabric", "Quilt"];
+
+/// Hash algorithm ID CurseForge uses for SHA-1.
+const SHA1_ALGO: u8 = 1;
+
+/// Returns CurseForge's `ModLoaderType` ID for a loader name.
+fn loader_type(loader: &str) -> Option<u8> {
+    match loader.to_ascii_lowercase().as_str() {
+        "forge" => Some(1),
+        "fabric" => Some(4),
+        "quilt" => Some(5),
+        "neoforge" => Some(6),
+        _ => None,
+    }
+}
+
+/// A file of a CurseForge mod, as returned by the API.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CurseForgeFile {
+    /// File ID
+    pub id: u64,
+    /// ID of the mod the file belongs to
+    pub mod_id: u64,
+    /// Display name of the file
+    #[serde(default)]
+    pub display_name: Option<String>,
+    /// File name
+    #[serde(default)]
+    pub file_name: Option<String>,
+    /// Fingerprint of the file
+    #[serde(default)]
+    pub file_fingerprint: Option<u32>,
+    /// Upload date as an RFC 3339 timestamp
+    #[serde(default)]
+    pub file_date: Option<String>,
+    /// Minecraft versions, loaders and environments the file is tagged with
+    #[serde(default)]
+    pub game_versions: Vec<String>,
+    /// Download URL, missing when the author disabled third-party downloads
+    #[serde(default)]
+    pub download_url: Option<String>,
+    /// File size in bytes
+    #[serde(default)]
+    pub file_length: u64,
+    /// Hashes of the file
+    #[serde(default)]
+    pub hashes: Vec<CurseForgeHash>,
+}
+
+/// A hash of a CurseForge file.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CurseForgeHash {
+    /// Hex-encoded hash
+    pub value: String,
+    /// Algorithm ID: 1 for SHA-1, 2 for MD5
+    pub algo: u8,
+}
+
+impl CurseForgeFile {
+    /// Returns the file's SHA-1, if the API listed it.
+    pub fn sha1(&self) -> Option<&str> {
+        self.hashes
+            .iter()
+            .find(|hash| hash.algo == SHA1_ALGO)
+            .map(|hash| hash.value.as_str())
+    }
+
+    /// Returns the loaders the file is tagged with.
+    pub fn loaders(&self) -> Vec<String> {
+        self.game_versions
+            .iter()
+            .filter(|tag| LOADER_TAGS.contains(&tag.as_str()))
+            .map(|tag| tag.to_ascii_lowercase())
+            .collect()
+    }
+
+    /// Returns the Minecraft versions the file is tagged with.
+    pub fn minecraft_versions(&self) -> Vec<String> {
+        self.game_versions
+            .iter()
+            .filter(|tag| tag.starts_with(|c: char| c.is_ascii_digit()))
+            .cloned()
+            .collect()
+    }
+
+    /// Returns whether the file runs on the client and on a server.
+    ///
+    /// Only newer files carry `Client` and `Server` tags; files without
+    /// either are unknown on both sides.
+    pub fn side_support(&self) -> (SideSupport, SideSupport) {
+        let tagged = |tag: &str| self.game_versions.iter().any(|t| t == tag);
+        match (tagged("Client"), tagged("Server")) {
+            (false, false) => (SideSupport::Unknown, SideSupport::Unknown),
+            (client, server) => (support(client), support(server)),
+        }
+    }
+
+    /// Describes the file as a [`PlatformInfo`], with the mod it belongs to if known.
+    pub fn platform_info(&self, curse_mod: Option<&CurseForgeMod>) -> PlatformInfo {
+        let (client_side, server_side) = self.side_support();
+        PlatformInfo {
+            platform: Platform::CurseForge,
+            project_id: self.mod_id.to_string(),
+            slug: curse_mod.map(|m| m.slug.clone()),
+            title: curse_mod.map(|m| m.name.clone()),
+            file_id: self.id.to_string(),
+            version_number: None,
+            client_side,
+            server_side,
+            loaders: self.loaders(),
+            game_versions: self.minecraft_versions(),
+        }
+    }
+}
+
+fn support(tagged: bool) -> SideSupport {
+    if tagged {
+        SideSupport::Required
+    } else {
+        SideSupport::Unsupported
+    }
+}
+
+/// A CurseForge mod, as returned by the API.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CurseForgeMod {
+    /// Mod ID
+    pub id: u64,
+    /// URL slug of the mod
+    pub slug: String,
+    /// Mod name
+    pub name: String,
+    /// Class the project belongs to, such as mods or resource packs
+    #[serde(default)]
+    pub class_id: Option<u32>,
+    /// Newest file for each Minecraft version and loader
+    #[serde(default)]
+    pub latest_files_indexes: Vec<FileIndex>,
+}
+
+impl CurseForgeMod {
+    /// Returns the newest file for a Minecraft version and loader.
+    ///
+    /// A filter left as `None` matches every file.
+    pub fn latest_file_for(
+        &self,
+        game_version: Option<&str>,
+        loader: Option<&str>,
+    ) -> Option<&FileIndex> {
+        let loader = loader.map(loader_type);
+        self.latest_files_indexes
+            .iter()
+            .filter(|index| game_version.is_none_or(|v| index.game_version == v))
+            .filter(|index| loader.is_none_or(|l| l.is_some() && index.mod_loader == l))
+            .max_by_key(|index| index.file_id)
+    }
+}
+
+/// The newest file of a mod for one Minecraft version and loader.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FileIndex {
+    /// Minecraft version
+    pub game_version: String,
+    /// ID of the file
+    pub file_id: u64,
+    /// File name
+    pub filename: String,
+    /// 1 for release, 2 for beta, 3 for alpha
+    pub release_type: u8,
+    /// `ModLoaderType` ID, if the file is for a loader
+    #[serde(default)]
+    pub mod_loader: Option<u8>,
+}
+
+/// A matched file together with the newest compatible file of its mod.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CurseForgeUpdate {
+    /// The file that was matched
+    pub current: CurseForgeFile,
+    /// The newest file of the same mod for the requested version and loader
+    pub latest: CurseForgeFile,
+    /// The mod both files belong to
+    pub curse_mod: CurseForgeMod,
+}
+
+impl CurseForgeUpdate {
+    /// Returns true if the latest file was uploaded after the current one.
+    pub fn is_newer(&self) -> bool {
+        self.latest.id > self.current.id
+    }
+}
+
+/// A file whose fingerprint matched exactly.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FingerprintMatch {
+    /// ID of the matched file
+    pub id: u64,
+    /// The matched file
+    pub file: CurseForgeFile,
+    /// Latest files of the mod the file belongs to
+    #[serde(default)]
+    pub latest_files: Vec<CurseForgeFile>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct FingerprintMatches {
+    #[serde(default)]
+    exact_matches: Vec<FingerprintMatch>,
+}
+
+#[derive(Deserialize)]
+struct Data<T> {
+    data: T,
+}
+
+#[derive(Serialize)]
+struct FingerprintsRequest<'a> {
+    fingerprints: &'a [u32],
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ModsRequest<'a> {
+    mod_ids: &'a [u64],
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct FilesRequest<'a> {
+    file_ids: &'a [u64],
+}
+
+/// Client for the CurseForge API.
+///
+/// The API requires a key, which is sent with every request.
+#[derive(Debug, Clone)]
+pub struct CurseForgeClient {
+    http: reqwest::Client,
+    base_url: String,
+    api_key: String,
+}
+
+impl CurseForgeClient {
+    /// Base URL of the public CurseForge API.
+    pub const DEFAULT_BASE_URL: &'static str = "https://api.curseforge.com";
+
+    /// Creates a client for the public CurseForge API.
+    pub fn new(api_key: impl Into<String>) -> Self {
+        let http = http::client_builder(http::API_TIMEOUT)
+            .build()
+            .unwrap_or_default();
+
+        Self {
+            http,
+            base_url: Self::DEFAULT_BASE_URL.to_string(),
+            api_key: api_key.into(),
+        }
+    }
+
+    /// Uses a different API base URL, such as a proxy or a test server.
+    pub fn with_base_url(mut self, base_url: impl Into<String>) -> Self {
+        self.base_url = base_url.into().trim_end_matches('/').to_string();
+        self
+    }
+
+    /// Looks up the files with the given fingerprints.
+    ///
+    /// Fingerprints CurseForge does not know are left out.
+    pub async fn match_fingerprints(&self, fingerprints: &[u32]) -> Result<Vec<FingerprintMatch>> {
+        if fingerprints.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let url = format!("{}/v1/fingerprints", self.base_url);
+        let response = self
+            .http
+            .post(&url)
+            .header("x-api-key", &self.api_key)
+            .json(&FingerprintsRequest { fingerprints })
+            .send()
+            .await?;
+
+        let matches: Data<FingerprintMatches> = check_status(url, response).await?.json().await?;
+        Ok(matches.data.exact_matches)
+    }
+
+    /// Fetches several mods at once.
+    pub async fn mods(&self, ids: &[u64]) -> Result<Vec<CurseForgeMod>> {
+        if ids.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let url = format!("{}/v1/mods", self.base_url);
+        let response = self
+            .http
+            .post(&url)
+            .header("x-api-key", &self.api_key)
+            .json(&ModsRequest { mod_ids: ids })
+            .send()
+            .await?;
+
+        let mods: Data<Vec<CurseForgeMod>> = check_status(url, response).await?.json().await?;
+        Ok(mods.data)
+    }
+
+    /// Fetches several files at once.
+    pub async fn files(&self, ids: &[u64]) -> Result<Vec<CurseForgeFile>> {
+        if ids.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let url = format!("{}/v1/mods/files", self.base_url);
+        let response = self
+            .http
+            .post(&url)
+            .header("x-api-key", &self.api_key)
+            .json(&FilesRequest { file_ids: ids })
+            .send()
+            .await?;
+
+        let files: Data<Vec<CurseForgeFile>> = check_status(url, response).await?.json().await?;
+        Ok(files.data)
+    }
+
+    /// Looks up the newest file for a Minecraft version and loader of each
+    /// mod that has a file with one of the given fingerprints.
+    ///
+    /// The result is keyed by fingerprint; fingerprints CurseForge does not
+    /// know, or whose mod has no matching file, are left out.
+    pub async fn latest_files(
+        &self,
+        fingerprints: &[u32],
+        game_version: Option<&str>,
+        loader: Option<&str>,
+    ) -> Result<HashMap<u32, CurseForgeUpdate>> {
+        let matches = self.match_fingerprints(fingerprints).await?;
+
+        let mut mod_ids: Vec<u64> = matches.iter().map(|m| m.file.mod_id).collect();
+        mod_ids.sort_unstable();
+        mod_ids.dedup();
+        let mods: HashMap<u64, CurseForgeMod> = self
+            .mods(&mod_ids)
+            .await?
+            .into_iter()
+            .map(|m| (m.id, m))
+            .collect();
+
+        let mut latest_ids: Vec<u64> = mods
+            .values()
+            .filter_map(|m| m.latest_file_for(game_version, loader))
+            .map(|index| index.file_id)
+            .collect();
+        latest_ids.sort_unstable();
+        let files: HashMap<u64, CurseForgeFile> = self
+            .files(&latest_ids)
+            .await?
+            .into_iter()
+            .map(|file| (file.id, file))
+            .collect();
+
+        Ok(matches
+            .into_iter()
+            .filter_map(|m| {
+                let fingerprint = m.file.file_fingerprint?;
+                let curse_mod = mods.get(&m.file.mod_id)?;
+                let index = curse_mod.latest_file_for(game_version, loader)?;
+                let latest = files.get(&index.file_id)?;
+                Some((
+                    fingerprint,
+                    CurseForgeUpdate {
+                        current: m.file,
+                        latest: latest.clone(),
+                        curse_mod: curse_mod.clone(),
+                    },
+                ))
+            })
+            .collect())
+    }
+
+    /// Identifies mod files by fingerprint.
+    ///
+    /// The result is keyed by fingerprint; files CurseForge does not know are
+    /// left out.
+    pub async fn identify(&self, fingerprints: &[u32]) -> Result<HashMap<u32, PlatformInfo>> {
+        let matches = self.match_fingerprints(fingerprints).await?;
+
+        let mut mod_ids: Vec<u64> = matches.iter().map(|m| m.file.mod_id).collect();
+        mod_ids.sort_unstable();
+        mod_ids.dedup();
+        let mods: HashMap<u64, CurseForgeMod> = self
+            .mods(&mod_ids)
+            .await?
+            .into_iter()
+            .map(|m| (m.id, m))
+            .collect();
+        debug!(
+            files = matches.len(),
+            mods = mods.len(),
+            "Identified files on CurseForge"
+        );
+
+        Ok(matches
+            .into_iter()
+            .filter_map(|m| {
+                let fingerprint = m.file.file_fingerprint?;
+                let curse_mod = mods.get(&m.file.mod_id);
+                Some((fingerprint, m.file.platform_info(curse_mod)))
+            })
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use wiremock::matchers::{body_json, header, method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    #[tokio::test]
+    async fn identifies_files_by_fingerprint() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/v1/fingerprints"))
+            .and(header("x-api-key", "secret"))
+            .and(body_json(json!({"fingerprints": [1234, 5678]})))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "data": {
+                    "isCacheBuilt": true,
+                    "exactMatches": [{
+                        "id": 5001,
+                        "file": {
+                            "id": 5001,
+                            "modId": 238222,
+                            "displayName": "jei-1.21.1-neoforge-19.21.0.247.jar",
+                            "fileFingerprint": 1234,
+                            "gameVersions": ["1.21.1", "NeoForge", "Client", "Server"]
+                        },
+                        "latestFiles": []
+                    }],
+                    "exactFingerprints": [1234],
+                    "unmatchedFingerprints": [5678]
+                }
+            })))
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .and(path("/v1/mods"))
+            .and(body_json(json!({"modIds": [238222]})))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "data": [{"id": 238222, "slug": "jei", "name": "Just Enough Items (JEI)"}]
+            })))
+            .mount(&server)
+            .await;
+
+        let client = CurseForgeClient::new("secret").with_base_url(server.uri());
+        let found = client.identify(&[1234, 5678]).await.unwrap();
+
+        assert_eq!(found.len(), 1);
+        let info = &found[&1234];
+        assert_eq!(info.project_id, "238222");
+        assert_eq!(info.file_id, "5001");
+        assert_eq!(info.slug.as_deref(), Some("jei"));
+        assert_eq!(info.game_versions, ["1.21.1"]);
+        assert_eq!(info.side(), mc_link_core::ModSide::Both);
+        assert_eq!(info.loader(), mc_link_core::ModLoader::NeoForge);
+    }
+
+    #[tokio::test]
+    async fn finds_latest_file_for_version_and_loader() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/v1/fingerprints"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "data": {
+                    "exactMatches": [{
+                        "id": 5001,
+                        "file": {"id": 5001, "modId": 238222, "fileFingerprint": 1234}
+                    }]
+                }
+            })))
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .and(path("/v1/mods"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "data": [{
+                    "id": 238222,
+                    "slug": "jei",
+                    "name": "Just Enough Items (JEI)",
+                    "latestFilesIndexes": [
+                        {"gameVersion": "1.21.1", "fileId": 5100, "filename": "jei-neoforge.jar", "releaseType": 1, "modLoader": 6},
+                        {"gameVersion": "1.21.1", "fileId": 5200, "filename": "jei-fabric.jar", "releaseType": 1, "modLoader": 4},
+                        {"gameVersion": "1.21.4", "fileId": 5300, "filename": "jei-neoforge.jar", "releaseType": 1, "modLoader": 6}
+                    ]
+                }]
+            })))
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .and(path("/v1/mods/files"))
+            .and(body_json(json!({"fileIds": [5100]})))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "data": [{
+                    "id": 5100,
+                    "modId": 238222,
+                    "fileName": "jei-neoforge.jar",
+                    "downloadUrl": "https://edge.forgecdn.net/files/5100/jei-neoforge.jar",
+                    "hashes": [{"value": "aaa", "algo": 1}, {"value": "bbb", "algo": 2}]
+                }]
+            })))
+            .mount(&server)
+            .await;
+
+        let client = CurseForgeClient::new("secret").with_base_url(server.uri());
+        let updates = client
+            .latest_files(&[1234], Some("1.21.1"), Some("neoforge"))
+            .await
+            .unwrap();
+
+        let update = &updates[&1234];
+        assert!(update.is_newer());
+        assert_eq!(update.latest.id, 5100);
+        assert_eq!(update.latest.sha1(), Some("aaa"));
+    }
+}
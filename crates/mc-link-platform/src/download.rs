@@ -0,0 +1,67 @@
+//! Downloads of mod files from platform CDNs.
+
+use crate::error::check_status;
+use crate::http;
+use crate::{PlatformError, Result};
+use sha1::{Digest, Sha1};
+use std::path::Path;
+use tracing::debug;
+
+/// Downloads a file to `dest`, checking its SHA-1 if one is given.
+///
+/// The file is written to a temporary name next to `dest` and only moved
+/// into place once the whole file has arrived and matched its hash.
+pub async fn download_file(url: &str, dest: &Path, sha1: Option<&str>) -> Result<()> {
+    let http = http::client_builder(http::DOWNLOAD_TIMEOUT).build()?;
+    let response = check_status(url.to_string(), http.get(url).send().await?).await?;
+    let bytes = response.bytes().await?;
+
+    if let Some(expected) = sha1 {
+        let actual = format!("{:x}", Sha1::digest(&bytes));
+        if !actual.eq_ignore_ascii_case(expected) {
+            return Err(PlatformError::HashMismatch {
+                url: url.to_string(),
+                expected: expected.to_string(),
+                actual,
+            });
+        }
+    }
+
+    if let Some(parent) = dest.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    let partial = dest.with_extension("part");
+    std::fs::write(&partial, &bytes)?;
+    std::fs::rename(&partial, dest)?;
+    debug!(url, dest = %dest.display(), size = bytes.len(), "Downloaded file");
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wiremock::matchers::{method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    #[tokio::test]
+    async fn rejects_hash_mismatch() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/mod.jar"))
+            .respond_with(ResponseTemplate::new(200).set_body_bytes(b"jar".to_vec()))
+            .mount(&server)
+            .await;
+        let dir = tempfile::tempdir().unwrap();
+        let url = format!("{}/mod.jar", server.uri());
+
+        let dest = dir.path().join("mod.jar");
+        let sha1 = format!("{:x}", Sha1::digest(b"jar"));
+        download_file(&url, &dest, Some(&sha1)).await.unwrap();
+        assert_eq!(std::fs::read(&dest).unwrap(), b"jar");
+
+        let other = dir.path().join("other.jar");
+        let err = download_file(&url, &other, Some("0000")).await.unwrap_err();
+        assert!(matches!(err, PlatformError::HashMismatch { .. }));
+        assert!(!other.exists());
+    }
+}
@@ -0,0 +1,53 @@
+use thiserror::Error;
+
+/// Errors that can occur while talking to a mod hosting platform.
+#[derive(Error, Debug)]
+pub enum PlatformError {
+    /// The request could not be sent or its response could not be read
+    #[error("HTTP error: {0}")]
+    Http(#[from] reqwest::Error),
+
+    /// The platform answered with an error status
+    #[error("API error: {url} returned {status} - {message}")]
+    Api {
+        url: String,
+        status: u16,
+        message: String,
+    },
+
+    /// A downloaded file does not have the hash the platform listed
+    #[error("Hash mismatch for {url}: expected {expected}, got {actual}")]
+    HashMismatch {
+        url: String,
+        expected: String,
+        actual: String,
+    },
+
+    /// IO error occurred
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+
+    /// JSON parsing error
+    #[error("JSON error: {0}")]
+    Json(#[from] serde_json::Error),
+}
+
+/// Result type for platform operations.
+pub type Result<T> = std::result::Result<T, PlatformError>;
+
+/// Turns an error status into a [`PlatformError::Api`].
+pub(crate) async fn check_status(
+    url: String,
+    response: reqwest::Response,
+) -> Result<reqwest::Response> {
+    let status = response.status();
+    if status.is_success() {
+        return Ok(response);
+    }
+    let message = response.text().await.unwrap_or_default();
+    Err(PlatformError::Api {
+        url,
+        status: status.as_u16(),
+        message,
+    })
+}
@@ -0,0 +1,179 @@
+//! Client for Forge update checker JSONs.
+//!
+//! Forge and NeoForge mods can declare an `updateJSONURL` in their
+//! `mods.toml`, pointing at a JSON document whose `promos` map lists the
+//! latest and recommended version for each Minecraft version:
+//!
+//! ```json
+//! {
+//!   "homepage": "https://example.com/mod",
+//!   "promos": { "1.21.1-latest": "2.1.0", "1.21.1-recommended": "2.0.3" }
+//! }
+//! ```
+
+use crate::Result;
+use crate::error::check_status;
+use crate::http;
+use mc_link_compat::compare_versions;
+use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
+use std::collections::HashMap;
+
+/// Whether an update checker JSON announces a newer version of a mod.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum UpdateAvailability {
+    /// The installed version is at least as new as every promoted one
+    UpToDate,
+    /// A newer version is promoted
+    Available {
+        /// Latest version, if newer than the installed one
+        latest: Option<String>,
+        /// Recommended version, if newer than the installed one
+        recommended: Option<String>,
+    },
+    /// Nothing is promoted for the Minecraft version, or the versions cannot be compared
+    Unknown,
+}
+
+/// A Forge update checker JSON.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ForgeUpdateJson {
+    /// Where to download the mod
+    #[serde(default)]
+    pub homepage: Option<String>,
+    /// Versions keyed by `<minecraft version>-latest` and `<minecraft version>-recommended`
+    #[serde(default)]
+    pub promos: HashMap<String, String>,
+}
+
+impl ForgeUpdateJson {
+    /// Returns the latest version for a Minecraft version.
+    pub fn latest(&self, minecraft_version: &str) -> Option<&str> {
+        self.promo(minecraft_version, "latest")
+    }
+
+    /// Returns the recommended version for a Minecraft version.
+    pub fn recommended(&self, minecraft_version: &str) -> Option<&str> {
+        self.promo(minecraft_version, "recommended")
+    }
+
+    /// Compares an installed version with the versions promoted for a Minecraft version.
+    pub fn availability(
+        &self,
+        current: Option<&str>,
+        minecraft_version: &str,
+    ) -> UpdateAvailability {
+        let Some(current) = current else {
+            return UpdateAvailability::Unknown;
+        };
+        let promoted = [
+            self.latest(minecraft_version),
+            self.recommended(minecraft_version),
+        ];
+        let orderings = promoted.map(|v| v.and_then(|v| compare_versions(current, v)));
+        let newer = |i: usize| {
+            (orderings[i] == Some(Ordering::Less))
+                .then(|| promoted[i].unwrap_or_default().to_string())
+        };
+
+        match (newer(0), newer(1)) {
+            (None, None) if orderings.iter().any(Option::is_some) => UpdateAvailability::UpToDate,
+            (None, None) => UpdateAvailability::Unknown,
+            (latest, recommended) => UpdateAvailability::Available {
+                latest,
+                recommended,
+            },
+        }
+    }
+
+    fn promo(&self, minecraft_version: &str, kind: &str) -> Option<&str> {
+        self.promos
+            .get(&format!("{minecraft_version}-{kind}"))
+            .map(String::as_str)
+    }
+}
+
+/// Client for fetching Forge update checker JSONs.
+#[derive(Debug, Clone)]
+pub struct ForgeUpdateClient {
+    http: reqwest::Client,
+}
+
+impl Default for ForgeUpdateClient {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ForgeUpdateClient {
+    /// Creates a client.
+    pub fn new() -> Self {
+        let http = http::client_builder(http::API_TIMEOUT)
+            .build()
+            .unwrap_or_default();
+
+        Self { http }
+    }
+
+    /// Fetches an update checker JSON.
+    pub async fn fetch(&self, url: &str) -> Result<ForgeUpdateJson> {
+        let response = self.http.get(url).send().await?;
+        Ok(check_status(url.to_string(), response)
+            .await?
+            .json()
+            .await?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use wiremock::matchers::{method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    #[tokio::test]
+    async fn reads_promos_for_minecraft_version() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/update.json"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "homepage": "https://example.com/mod",
+                "1.21.1": {"2.1.0": "Fixes", "2.0.3": "Stable"},
+                "promos": {
+                    "1.21.1-latest": "2.1.0",
+                    "1.21.1-recommended": "2.0.3",
+                    "1.20.1-latest": "1.9.0"
+                }
+            })))
+            .mount(&server)
+            .await;
+
+        let client = ForgeUpdateClient::new();
+        let update = client
+            .fetch(&format!("{}/update.json", server.uri()))
+            .await
+            .unwrap();
+
+        assert_eq!(update.latest("1.21.1"), Some("2.1.0"));
+        assert_eq!(update.recommended("1.21.1"), Some("2.0.3"));
+        assert_eq!(update.recommended("1.20.1"), None);
+        assert_eq!(update.homepage.as_deref(), Some("https://example.com/mod"));
+
+        assert_eq!(
+            update.availability(Some("2.0.3"), "1.21.1"),
+            UpdateAvailability::Available {
+                latest: Some("2.1.0".to_string()),
+                recommended: None,
+            }
+        );
+        assert_eq!(
+            update.availability(Some("2.1.0"), "1.21.1"),
+            UpdateAvailability::UpToDate
+        );
+        assert_eq!(
+            update.availability(Some("2.1.0"), "1.19.2"),
+            UpdateAvailability::Unknown
+        );
+    }
+}
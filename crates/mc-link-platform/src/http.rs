@@ -0,0 +1,20 @@
+//! Settings shared by the HTTP clients of this crate.
+
+use std::time::Duration;
+
+/// Time allowed for a whole API request, including reading the response.
+pub(crate) const API_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Time allowed for a mod file download, which may be several megabytes.
+pub(crate) const DOWNLOAD_TIMEOUT: Duration = Duration::from_secs(300);
+
+/// Time allowed for connecting to a host.
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Returns a client builder with the user agent and timeouts set.
+pub(crate) fn client_builder(timeout: Duration) -> reqwest::ClientBuilder {
+    reqwest::Client::builder()
+        .user_agent(concat!("mc-link/", env!("CARGO_PKG_VERSION")))
+        .connect_timeout(CONNECT_TIMEOUT)
+        .timeout(timeout)
+}
@@ -0,0 +1,21 @@
+//! Mod identification through mod hosting platforms.
+//!
+//! This crate provides clients for the platforms mods are published on, used
+//! to identify mod files by their hashes or fingerprints when their own
+//! metadata falls short, and to find newer versions of them.
+
+#![cfg_attr(not(debug_assertions), forbid(missing_docs))]
+
+pub mod curseforge;
+pub mod download;
+pub mod error;
+pub mod forge;
+mod http;
+pub mod modrinth;
+pub mod prelude;
+
+pub use curseforge::*;
+pub use download::*;
+pub use error::*;
+pub use forge::*;
+pub use modrinth::*;
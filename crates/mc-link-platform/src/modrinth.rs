@@ -0,0 +1,413 @@
+//! Client for the Modrinth API.
+//!
+//! Mod files are looked up by hash through the version-files endpoint, and
+//! the projects they belong to are fetched in one batch for their side
+//! support.
+
+use crate::Result;
+use crate::error::check_status;
+use crate::http;
+use mc_link_core::{Platform, PlatformInfo, SideSupport};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use tracing::debug;
+
+/// Hash algorithms the Modrinth API accepts for identifying files.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum HashAlgorithm {
+    /// SHA-1
+    Sha1,
+    /// SHA-512
+    Sha512,
+}
+
+/// A version of a Modrinth project, as returned by the API.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ModrinthVersion {
+    /// Version ID
+    pub id: String,
+    /// ID of the project the version belongs to
+    pub project_id: String,
+    /// Display name of the version
+    #[serde(default)]
+    pub name: Option<String>,
+    /// Version number, usually the one in the mod's metadata
+    #[serde(default)]
+    pub version_number: Option<String>,
+    /// Loaders the version supports
+    #[serde(default)]
+    pub loaders: Vec<String>,
+    /// Minecraft versions the version supports
+    #[serde(default)]
+    pub game_versions: Vec<String>,
+    /// Publication date as an RFC 3339 timestamp
+    #[serde(default)]
+    pub date_published: Option<String>,
+    /// Files of the version
+    #[serde(default)]
+    pub files: Vec<ModrinthFile>,
+}
+
+impl ModrinthVersion {
+    /// Returns the file marked primary, or the first file if none is.
+    pub fn primary_file(&self) -> Option<&ModrinthFile> {
+        self.files
+            .iter()
+            .find(|file| file.primary)
+            .or_else(|| self.files.first())
+    }
+
+    /// Returns true if one of the version's files has the given hash.
+    pub fn has_file(&self, hash: &str, algorithm: HashAlgorithm) -> bool {
+        self.files
+            .iter()
+            .any(|file| file.hash(algorithm) == Some(hash))
+    }
+}
+
+/// A file of a Modrinth version, as returned by the API.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ModrinthFile {
+    /// Download URL
+    pub url: String,
+    /// File name
+    pub filename: String,
+    /// Whether this is the version's main file
+    #[serde(default)]
+    pub primary: bool,
+    /// File size in bytes
+    #[serde(default)]
+    pub size: u64,
+    /// Hex-encoded hashes by algorithm name
+    #[serde(default)]
+    pub hashes: HashMap<String, String>,
+}
+
+impl ModrinthFile {
+    /// Returns the file's hash for an algorithm, if the API listed it.
+    pub fn hash(&self, algorithm: HashAlgorithm) -> Option<&str> {
+        let name = match algorithm {
+            HashAlgorithm::Sha1 => "sha1",
+            HashAlgorithm::Sha512 => "sha512",
+        };
+        self.hashes.get(name).map(String::as_str)
+    }
+}
+
+/// A Modrinth project, as returned by the API.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ModrinthProject {
+    /// Project ID
+    pub id: String,
+    /// URL slug of the project
+    pub slug: String,
+    /// Project title
+    pub title: String,
+    /// Whether the project runs on the client
+    #[serde(default)]
+    pub client_side: SideSupport,
+    /// Whether the project runs on a dedicated server
+    #[serde(default)]
+    pub server_side: SideSupport,
+}
+
+#[derive(Serialize)]
+struct VersionFilesRequest<'a> {
+    hashes: &'a [String],
+    algorithm: HashAlgorithm,
+}
+
+#[derive(Serialize)]
+struct LatestVersionsRequest<'a> {
+    hashes: &'a [String],
+    algorithm: HashAlgorithm,
+    #[serde(skip_serializing_if = "<[_]>::is_empty")]
+    loaders: &'a [String],
+    #[serde(skip_serializing_if = "<[_]>::is_empty")]
+    game_versions: &'a [String],
+}
+
+/// Client for the Modrinth API.
+#[derive(Debug, Clone)]
+pub struct ModrinthClient {
+    http: reqwest::Client,
+    base_url: String,
+}
+
+impl Default for ModrinthClient {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ModrinthClient {
+    /// Base URL of the public Modrinth API.
+    pub const DEFAULT_BASE_URL: &'static str = "https://api.modrinth.com";
+
+    /// Creates a client for the public Modrinth API.
+    pub fn new() -> Self {
+        let http = http::client_builder(http::API_TIMEOUT)
+            .build()
+            .unwrap_or_default();
+
+        Self {
+            http,
+            base_url: Self::DEFAULT_BASE_URL.to_string(),
+        }
+    }
+
+    /// Uses a different API base URL, such as a mirror or a test server.
+    pub fn with_base_url(mut self, base_url: impl Into<String>) -> Self {
+        self.base_url = base_url.into().trim_end_matches('/').to_string();
+        self
+    }
+
+    /// Looks up the versions that contain files with the given hashes.
+    ///
+    /// The result is keyed by hash; hashes Modrinth does not know are left out.
+    pub async fn versions_from_hashes(
+        &self,
+        hashes: &[String],
+        algorithm: HashAlgorithm,
+    ) -> Result<HashMap<String, ModrinthVersion>> {
+        if hashes.is_empty() {
+            return Ok(HashMap::new());
+        }
+
+        let url = format!("{}/v2/version_files", self.base_url);
+        let response = self
+            .http
+            .post(&url)
+            .json(&VersionFilesRequest { hashes, algorithm })
+            .send()
+            .await?;
+
+        Ok(check_status(url, response).await?.json().await?)
+    }
+
+    /// Looks up the newest version of each project that contains a file with
+    /// one of the given hashes.
+    ///
+    /// Only versions supporting one of `loaders` and one of `game_versions`
+    /// are considered; an empty filter matches everything. The result is keyed
+    /// by hash; hashes Modrinth does not know, or whose project has no
+    /// matching version, are left out.
+    pub async fn latest_versions(
+        &self,
+        hashes: &[String],
+        algorithm: HashAlgorithm,
+        loaders: &[String],
+        game_versions: &[String],
+    ) -> Result<HashMap<String, ModrinthVersion>> {
+        if hashes.is_empty() {
+            return Ok(HashMap::new());
+        }
+
+        let url = format!("{}/v2/version_files/update", self.base_url);
+        let response = self
+            .http
+            .post(&url)
+            .json(&LatestVersionsRequest {
+                hashes,
+                algorithm,
+                loaders,
+                game_versions,
+            })
+            .send()
+            .await?;
+
+        Ok(check_status(url, response).await?.json().await?)
+    }
+
+    /// Fetches several projects at once.
+    pub async fn projects(&self, ids: &[String]) -> Result<Vec<ModrinthProject>> {
+        if ids.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let url = format!("{}/v2/projects", self.base_url);
+        let response = self
+            .http
+            .get(&url)
+            .query(&[("ids", serde_json::to_string(ids)?)])
+            .send()
+            .await?;
+
+        Ok(check_status(url, response).await?.json().await?)
+    }
+
+    /// Identifies mod files by hash.
+    ///
+    /// The result is keyed by hash; files Modrinth does not know are left out.
+    pub async fn identify(
+        &self,
+        hashes: &[String],
+        algorithm: HashAlgorithm,
+    ) -> Result<HashMap<String, PlatformInfo>> {
+        let versions = self.versions_from_hashes(hashes, algorithm).await?;
+
+        let mut project_ids: Vec<String> =
+            versions.values().map(|v| v.project_id.clone()).collect();
+        project_ids.sort();
+        project_ids.dedup();
+        let projects: HashMap<String, ModrinthProject> = self
+            .projects(&project_ids)
+            .await?
+            .into_iter()
+            .map(|project| (project.id.clone(), project))
+            .collect();
+        debug!(
+            files = versions.len(),
+            projects = projects.len(),
+            "Identified files on Modrinth"
+        );
+
+        Ok(versions
+            .into_iter()
+            .map(|(hash, version)| {
+                let project = projects.get(&version.project_id);
+                (hash, platform_info(version, project))
+            })
+            .collect())
+    }
+}
+
+fn platform_info(version: ModrinthVersion, project: Option<&ModrinthProject>) -> PlatformInfo {
+    PlatformInfo {
+        platform: Platform::Modrinth,
+        project_id: version.project_id,
+        slug: project.map(|p| p.slug.clone()),
+        title: project.map(|p| p.title.clone()),
+        file_id: version.id,
+        version_number: version.version_number,
+        client_side: project.map_or(SideSupport::Unknown, |p| p.client_side),
+        server_side: project.map_or(SideSupport::Unknown, |p| p.server_side),
+        loaders: version.loaders,
+        game_versions: version.game_versions,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::PlatformError;
+    use serde_json::json;
+    use wiremock::matchers::{body_json, method, path, query_param};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    #[tokio::test]
+    async fn identifies_files_by_hash() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/v2/version_files"))
+            .and(body_json(
+                json!({"hashes": ["abc", "def"], "algorithm": "sha1"}),
+            ))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "abc": {
+                    "id": "IZskON6d",
+                    "project_id": "AANobbMI",
+                    "version_number": "mc1.21.1-0.6.0-neoforge",
+                    "loaders": ["neoforge"],
+                    "game_versions": ["1.21.1"]
+                }
+            })))
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/v2/projects"))
+            .and(query_param("ids", r#"["AANobbMI"]"#))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!([{
+                "id": "AANobbMI",
+                "slug": "sodium",
+                "title": "Sodium",
+                "client_side": "required",
+                "server_side": "unsupported"
+            }])))
+            .mount(&server)
+            .await;
+
+        let client = ModrinthClient::new().with_base_url(server.uri());
+        let found = client
+            .identify(&["abc".to_string(), "def".to_string()], HashAlgorithm::Sha1)
+            .await
+            .unwrap();
+
+        assert_eq!(found.len(), 1);
+        let info = &found["abc"];
+        assert_eq!(info.slug.as_deref(), Some("sodium"));
+        assert_eq!(
+            info.version_number.as_deref(),
+            Some("mc1.21.1-0.6.0-neoforge")
+        );
+        assert_eq!(info.side(), mc_link_core::ModSide::Client);
+        assert_eq!(info.loader(), mc_link_core::ModLoader::NeoForge);
+    }
+
+    #[tokio::test]
+    async fn finds_latest_matching_versions() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/v2/version_files/update"))
+            .and(body_json(json!({
+                "hashes": ["abc"],
+                "algorithm": "sha1",
+                "loaders": ["neoforge"],
+                "game_versions": ["1.21.1"]
+            })))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "abc": {
+                    "id": "xYz12345",
+                    "project_id": "AANobbMI",
+                    "version_number": "mc1.21.1-0.6.5-neoforge",
+                    "loaders": ["neoforge"],
+                    "game_versions": ["1.21.1"],
+                    "files": [{
+                        "url": "https://cdn.modrinth.com/data/AANobbMI/versions/xYz12345/sodium.jar",
+                        "filename": "sodium-neoforge-0.6.5.jar",
+                        "primary": true,
+                        "size": 1024,
+                        "hashes": {"sha1": "fff", "sha512": "eee"}
+                    }]
+                }
+            })))
+            .mount(&server)
+            .await;
+
+        let client = ModrinthClient::new().with_base_url(server.uri());
+        let latest = client
+            .latest_versions(
+                &["abc".to_string()],
+                HashAlgorithm::Sha1,
+                &["neoforge".to_string()],
+                &["1.21.1".to_string()],
+            )
+            .await
+            .unwrap();
+
+        let version = &latest["abc"];
+        assert!(!version.has_file("abc", HashAlgorithm::Sha1));
+        let file = version.primary_file().unwrap();
+        assert_eq!(file.filename, "sodium-neoforge-0.6.5.jar");
+        assert_eq!(file.hash(HashAlgorithm::Sha1), Some("fff"));
+    }
+
+    #[tokio::test]
+    async fn reports_error_status() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/v2/version_files"))
+            .respond_with(ResponseTemplate::new(429).set_body_string("rate limited"))
+            .mount(&server)
+            .await;
+
+        let client = ModrinthClient::new().with_base_url(server.uri());
+        let err = client
+            .identify(&["abc".to_string()], HashAlgorithm::Sha1)
+            .await
+            .unwrap_err();
+
+        assert!(matches!(err, PlatformError::Api { status: 429, .. }));
+    }
+}
@@ -0,0 +1,15 @@
+//! Prelude module for mod hosting platforms.
+//!
+//! Import with `use mc_link_platform::prelude::*;` to get commonly used platform types.
+
+pub use crate::curseforge::{
+    CurseForgeClient, CurseForgeFile, CurseForgeHash, CurseForgeMod, CurseForgeUpdate, FileIndex,
+    FingerprintMatch,
+};
+pub use crate::download::download_file;
+pub use crate::error::{PlatformError, Result};
+pub use crate::forge::{ForgeUpdateClient, ForgeUpdateJson, UpdateAvailability};
+pub use crate::modrinth::{
+    HashAlgorithm, ModrinthClient, ModrinthFile, ModrinthProject, ModrinthVersion,
+};
+pub use mc_link_core::{Platform, PlatformInfo, SideSupport};
@@ -1,5 +1,6 @@
 use clap::{Args, Parser, Subcommand, ValueEnum};
-use std::path::PathBuf;
+use mc_link_manager::prelude::{CompatError, CompatRule};
+use std::path::{Path, PathBuf};
 
 #[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, ValueEnum)]
 
@@ -8,6 +9,49 @@ pub enum ConnectionType {
     Ftp,
 }
 
+/// How planned downgrades are handled
+#[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, ValueEnum)]
+pub enum DowngradeMode {
+    /// Downgrade like any other update
+    Allow,
+    /// Ask before applying downgrades
+    Confirm,
+    /// Keep the target's newer version
+    Refuse,
+}
+
+impl From<DowngradeMode> for mc_link_manager::prelude::DowngradePolicy {
+    fn from(mode: DowngradeMode) -> Self {
+        match mode {
+            DowngradeMode::Allow => Self::Allow,
+            DowngradeMode::Confirm => Self::Confirm,
+            DowngradeMode::Refuse => Self::Refuse,
+        }
+    }
+}
+
+/// Modpack formats a server can be exported as
+#[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, ValueEnum)]
+pub enum ExportFormat {
+    /// Modrinth modpack (.mrpack)
+    Mrpack,
+    /// CurseForge modpack (.zip)
+    Curseforge,
+    /// packwiz pack directory
+    Packwiz,
+}
+
+impl ExportFormat {
+    /// Returns where a server's pack is written unless told otherwise.
+    pub fn default_output(self, id: &str) -> PathBuf {
+        match self {
+            ExportFormat::Mrpack => format!("{id}.mrpack").into(),
+            ExportFormat::Curseforge => format!("{id}.zip").into(),
+            ExportFormat::Packwiz => id.into(),
+        }
+    }
+}
+
 /// MC-Link: Minecraft server mod synchronization tool
 #[derive(Parser)]
 #[command(
@@ -73,6 +117,104 @@ pub enum Commands {
         targets: SyncTargets,
     },
 
+    /// Bring servers to the mod set a manifest declares for them
+    Apply {
+        #[command(flatten)]
+        options: ApplyOptions,
+    },
+
+    /// Report which installed mods support a Minecraft version
+    UpgradeCheck {
+        /// Server/client ID to check
+        server: String,
+
+        /// Minecraft version to check against (e.g., 1.21.4)
+        #[arg(long)]
+        to: String,
+
+        /// Show in JSON format
+        #[arg(short, long)]
+        json: bool,
+    },
+
+    /// List mods with newer versions for the server's Minecraft version and loader
+    Outdated {
+        /// Server/client ID to check
+        server: String,
+
+        /// Download the updates into this directory and show the resulting sync plan
+        #[arg(long)]
+        download: Option<PathBuf>,
+
+        /// Show in JSON format
+        #[arg(short, long)]
+        json: bool,
+    },
+
+    /// Record the hash of every mod file of a server in a lockfile
+    Lock {
+        /// Server/client ID to lock
+        server: String,
+
+        /// Lockfile to write
+        #[arg(long, default_value = mc_link_manager::LOCK_FILE)]
+        file: PathBuf,
+
+        /// Also record the files under config/
+        #[arg(long)]
+        include_config: bool,
+    },
+
+    /// Report files of a server that drifted from its lockfile
+    Verify {
+        /// Server/client ID to verify
+        server: String,
+
+        /// Lockfile to compare with
+        #[arg(long, default_value = mc_link_manager::LOCK_FILE)]
+        file: PathBuf,
+
+        /// Show in JSON format
+        #[arg(short, long)]
+        json: bool,
+    },
+
+    /// Export a server's mods and configs as a modpack
+    Export {
+        /// Server/client ID to export
+        id: String,
+
+        /// Modpack format to write
+        #[arg(long, value_enum, default_value = "mrpack")]
+        format: ExportFormat,
+
+        /// File or, for packwiz, directory to write (defaults to `<id>.mrpack`, `<id>.zip` or `<id>/`)
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+
+        /// Name of the pack (defaults to the server's name)
+        #[arg(long)]
+        name: Option<String>,
+
+        /// Version of the pack
+        #[arg(long, default_value = "1.0.0")]
+        pack_version: String,
+
+        /// Author of the pack, for CurseForge and packwiz packs
+        #[arg(long)]
+        author: Option<String>,
+
+        /// Version of the server's loader, for launchers to install
+        #[arg(long)]
+        loader_version: Option<String>,
+    },
+
+    /// Manage compatibility profiles
+    Profile {
+        #[command(subcommand)]
+        action: ProfileAction,
+    },
+
     /// Enable or disable a server/client
     /// Uses interactive selection when ID is not provided
     Toggle {
@@ -91,6 +233,29 @@ pub enum Commands {
     },
 }
 
+/// Operations on compatibility profiles
+#[derive(Subcommand)]
+pub enum ProfileAction {
+    /// List installed profiles
+    List,
+
+    /// Install a profile from a shared JSON file
+    Import {
+        /// Path to the profile file
+        file: PathBuf,
+    },
+
+    /// Write a profile to a JSON file for sharing
+    Export {
+        /// Profile name
+        name: String,
+
+        /// Output file (defaults to <name>.json)
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+    },
+}
+
 /// Server configuration for adding new servers
 #[derive(Args)]
 #[group(required = false, multiple = true)]
@@ -123,9 +288,13 @@ pub struct AddServerConfig {
     #[arg(long, default_value = "1.21.1")]
     pub minecraft_version: String,
 
-    /// Mod loader (NeoForge, Forge, Fabric)
-    #[arg(long, default_value = "NeoForge")]
-    pub mod_loader: String,
+    /// Mod loader (NeoForge, Forge, Fabric); detected from the installed mods if not set
+    #[arg(long)]
+    pub mod_loader: Option<String>,
+
+    /// Mod loader version (e.g., 21.1.77)
+    #[arg(long)]
+    pub loader_version: Option<String>,
 
     /// Disable the server/client by default
     #[arg(long)]
@@ -185,14 +354,61 @@ pub struct CompareTargets {
     /// Target (server) ID
     pub target: Option<String>,
 
+    /// Compare a Modrinth modpack (.mrpack) with the one server ID given
+    #[arg(long, value_name = "FILE", conflicts_with_all = ["curseforge_pack", "packwiz"])]
+    pub mrpack: Option<PathBuf>,
+
+    /// Compare a CurseForge modpack (.zip) with the one server ID given
+    #[arg(long, value_name = "FILE", conflicts_with = "packwiz")]
+    pub curseforge_pack: Option<PathBuf>,
+
+    /// Compare a packwiz pack directory with the one server ID given
+    #[arg(long, value_name = "DIR")]
+    pub packwiz: Option<PathBuf>,
+
     /// Show detailed comparison results
     #[arg(short, long)]
     pub detailed: bool,
+
+    /// Explain how the compatibility check decided on a mod
+    #[arg(long, value_name = "MOD_ID")]
+    pub explain: Option<String>,
+
+    /// How to treat mods where the target has a newer version
+    #[arg(long, value_enum, default_value = "confirm")]
+    pub downgrades: DowngradeMode,
+
+    /// Compatibility profile to apply (defaults to the target's, then the global default)
+    #[arg(long)]
+    pub profile: Option<String>,
+
+    /// Ignore a mod ID or glob pattern for this run (repeatable)
+    #[arg(long, value_name = "PATTERN")]
+    pub ignore: Vec<String>,
+
+    /// Extra compatibility rule for this run, e.g. "warn create # desyncs" (repeatable)
+    #[arg(long, value_name = "RULE")]
+    pub rule: Vec<String>,
 }
 
 impl CompareTargets {
     pub fn is_interactive(&self) -> bool {
-        self.source.is_none() || self.target.is_none()
+        self.endpoints().is_none()
+    }
+
+    /// Returns where mods come from and the target ID.
+    pub fn endpoints(&self) -> Option<(Source<'_>, &str)> {
+        let pack = pack_source(
+            self.mrpack.as_deref(),
+            self.curseforge_pack.as_deref(),
+            self.packwiz.as_deref(),
+        );
+        endpoints(pack, &self.source, &self.target)
+    }
+
+    /// Parses `--ignore` and `--rule` into compatibility rules.
+    pub fn cli_rules(&self) -> Result<Vec<CompatRule>, CompatError> {
+        parse_cli_rules(&self.ignore, &self.rule)
     }
 }
 
@@ -205,6 +421,18 @@ pub struct SyncTargets {
     /// Target (server) ID  
     pub target: Option<String>,
 
+    /// Sync from a Modrinth modpack (.mrpack) to the one server ID given
+    #[arg(long, value_name = "FILE", conflicts_with_all = ["curseforge_pack", "packwiz"])]
+    pub mrpack: Option<PathBuf>,
+
+    /// Sync from a CurseForge modpack (.zip) to the one server ID given
+    #[arg(long, value_name = "FILE", conflicts_with = "packwiz")]
+    pub curseforge_pack: Option<PathBuf>,
+
+    /// Sync from a packwiz pack directory to the one server ID given
+    #[arg(long, value_name = "DIR")]
+    pub packwiz: Option<PathBuf>,
+
     /// Skip confirmation prompt
     #[arg(short, long)]
     pub force: bool,
@@ -212,14 +440,135 @@ pub struct SyncTargets {
     /// Dry run - show what would be done without executing
     #[arg(long)]
     pub dry_run: bool,
+
+    /// How to treat mods where the target has a newer version
+    #[arg(long, value_enum, default_value = "confirm")]
+    pub downgrades: DowngradeMode,
+
+    /// Compatibility profile to apply (defaults to the target's, then the global default)
+    #[arg(long)]
+    pub profile: Option<String>,
+
+    /// Ignore a mod ID or glob pattern for this run (repeatable)
+    #[arg(long, value_name = "PATTERN")]
+    pub ignore: Vec<String>,
+
+    /// Extra compatibility rule for this run, e.g. "warn create # desyncs" (repeatable)
+    #[arg(long, value_name = "RULE")]
+    pub rule: Vec<String>,
+
+    /// Remove stale copies of mods installed more than once on the target
+    #[arg(long)]
+    pub remove_duplicates: bool,
 }
 
 impl SyncTargets {
     pub fn is_interactive(&self) -> bool {
-        self.source.is_none() || self.target.is_none()
+        self.endpoints().is_none()
+    }
+
+    /// Returns where mods come from and the target ID.
+    pub fn endpoints(&self) -> Option<(Source<'_>, &str)> {
+        let pack = pack_source(
+            self.mrpack.as_deref(),
+            self.curseforge_pack.as_deref(),
+            self.packwiz.as_deref(),
+        );
+        endpoints(pack, &self.source, &self.target)
+    }
+
+    /// Parses `--ignore` and `--rule` into compatibility rules.
+    pub fn cli_rules(&self) -> Result<Vec<CompatRule>, CompatError> {
+        parse_cli_rules(&self.ignore, &self.rule)
     }
 }
 
+/// Manifest and servers for apply operations
+#[derive(Args)]
+pub struct ApplyOptions {
+    /// Manifest of the mods each server should run
+    pub manifest: PathBuf,
+
+    /// Server ID to apply the manifest to (repeatable; defaults to every server it names)
+    #[arg(long = "server", value_name = "ID")]
+    pub servers: Vec<String>,
+
+    /// Skip confirmation prompt
+    #[arg(short, long)]
+    pub force: bool,
+
+    /// Dry run - show what would be done without executing
+    #[arg(long)]
+    pub dry_run: bool,
+
+    /// How to treat mods where the server has a newer version
+    #[arg(long, value_enum, default_value = "confirm")]
+    pub downgrades: DowngradeMode,
+
+    /// Compatibility profile to apply (defaults to the server's, then the global default)
+    #[arg(long)]
+    pub profile: Option<String>,
+
+    /// Ignore a mod ID or glob pattern for this run (repeatable)
+    #[arg(long, value_name = "PATTERN")]
+    pub ignore: Vec<String>,
+
+    /// Extra compatibility rule for this run, e.g. "warn create # desyncs" (repeatable)
+    #[arg(long, value_name = "RULE")]
+    pub rule: Vec<String>,
+}
+
+impl ApplyOptions {
+    /// Parses `--ignore` and `--rule` into compatibility rules.
+    pub fn cli_rules(&self) -> Result<Vec<CompatRule>, CompatError> {
+        parse_cli_rules(&self.ignore, &self.rule)
+    }
+}
+
+/// Where a comparison or sync takes its mods from
+pub enum Source<'a> {
+    /// Another configured server
+    Server(&'a str),
+    /// A Modrinth modpack file
+    Mrpack(&'a Path),
+    /// A CurseForge modpack file
+    CurseForgePack(&'a Path),
+    /// A packwiz pack directory
+    Packwiz(&'a Path),
+}
+
+fn pack_source<'a>(
+    mrpack: Option<&'a Path>,
+    curseforge_pack: Option<&'a Path>,
+    packwiz: Option<&'a Path>,
+) -> Option<Source<'a>> {
+    mrpack
+        .map(Source::Mrpack)
+        .or(curseforge_pack.map(Source::CurseForgePack))
+        .or(packwiz.map(Source::Packwiz))
+}
+
+/// With a pack as the source, the only positional ID is the target.
+fn endpoints<'a>(
+    pack: Option<Source<'a>>,
+    source: &'a Option<String>,
+    target: &'a Option<String>,
+) -> Option<(Source<'a>, &'a str)> {
+    match (pack, source, target) {
+        (Some(pack), Some(target), None) => Some((pack, target)),
+        (None, Some(source), Some(target)) => Some((Source::Server(source), target)),
+        _ => None,
+    }
+}
+
+fn parse_cli_rules(ignore: &[String], rules: &[String]) -> Result<Vec<CompatRule>, CompatError> {
+    ignore
+        .iter()
+        .map(|pattern| format!("ignore {pattern}").parse())
+        .chain(rules.iter().map(|rule| rule.parse()))
+        .collect()
+}
+
 /// Target for toggle operations
 #[derive(Args)]
 pub struct ToggleTarget {
@@ -3,13 +3,16 @@ mod progress;
 mod tui;
 
 use clap::Parser;
-use cli::{Cli, Commands};
+use cli::{Cli, Commands, ProfileAction, Source};
 use crossterm::{
     event::{DisableMouseCapture, EnableMouseCapture},
     execute,
     terminal::{EnterAlternateScreen, LeaveAlternateScreen, disable_raw_mode, enable_raw_mode},
 };
 use mc_link_config::{CONFIG_MANAGER, ConfigManager};
+use mc_link_connector::Connector;
+use mc_link_manager::MinecraftManager;
+use mc_link_pack::PackSide;
 use ratatui::{Terminal, backend::CrosstermBackend};
 use std::io;
 use tracing::info;
@@ -51,7 +54,6 @@ async fn handle_cli_command(
     config: &ConfigManager,
 ) -> Result<(), Box<dyn std::error::Error>> {
     use mc_link_config::{ConnectionType, FtpConnection, LocalConnection, ServerConfig};
-    use mc_link_manager::MinecraftManager;
 
     match command {
         Commands::List { enabled_only } => {
@@ -122,12 +124,17 @@ async fn handle_cli_command(
 
                 // Parse mod loader
                 use mc_link_config::ModLoader;
-                server_config.settings.mod_loader = match add_config.mod_loader.as_str() {
-                    "NeoForge" => ModLoader::NeoForge,
-                    "Forge" => ModLoader::Forge,
-                    "Fabric" => ModLoader::Fabric,
-                    _ => ModLoader::NeoForge,
-                };
+                server_config.settings.mod_loader =
+                    add_config
+                        .mod_loader
+                        .as_deref()
+                        .map(|mod_loader| match mod_loader {
+                            "NeoForge" => ModLoader::NeoForge,
+                            "Forge" => ModLoader::Forge,
+                            "Fabric" => ModLoader::Fabric,
+                            _ => ModLoader::NeoForge,
+                        });
+                server_config.settings.loader_version = add_config.loader_version.clone();
 
                 server_config.validate()?;
                 config_manager.add_server(server_config);
@@ -195,6 +202,8 @@ async fn handle_cli_command(
                                     println!("  {}. {} ({})", i + 1, mod_info.name, version);
                                 }
                             }
+
+                            print_scan_findings(&structure.findings);
                         }
                         Err(e) => {
                             return Err(format!("Failed to scan server: {e}").into());
@@ -208,29 +217,28 @@ async fn handle_cli_command(
         Commands::Compare { targets } => {
             if targets.is_interactive() {
                 return Err("Interactive server comparison is not yet implemented. Use --source and --target to specify servers.".into());
-            } else if let (Some(source), Some(target)) = (&targets.source, &targets.target) {
-                let source_config = config
-                    .get_server(source)
-                    .ok_or(format!("Source server '{source}' not found."))?;
+            } else if let Some((source, target)) = targets.endpoints() {
                 let target_config = config
                     .get_server(target)
                     .ok_or(format!("Target server '{target}' not found."))?;
+                let (source_manager, source_name) = source_manager(config, &source).await?;
 
-                println!(
-                    "Comparing '{}' -> '{}'...",
-                    source_config.name, target_config.name
-                );
+                println!("Comparing '{source_name}' -> '{}'...", target_config.name);
 
                 let (progress_reporter, progress_task) = progress::create_cli_progress_reporter();
 
-                let mut source_manager = MinecraftManager::from_config(source_config)
+                let mut source_manager = source_manager
                     .with_caching()?
                     .with_progress_reporter(progress_reporter);
                 let mut target_manager =
                     MinecraftManager::from_config(target_config).with_caching()?;
 
-                use mc_link_manager::prelude::CompatConfig;
-                let compat_config = CompatConfig::default();
+                use mc_link_manager::prelude::CompatConfigBuilder;
+                let compat_config = CompatConfigBuilder::new(config, target_config)
+                    .with_rules(targets.cli_rules()?)
+                    .with_profile(targets.profile.as_deref())
+                    .with_downgrade_policy(targets.downgrades.into())
+                    .build()?;
 
                 let compare_result = source_manager
                     .compare_with(&mut target_manager, &compat_config)
@@ -240,6 +248,13 @@ async fn handle_cli_command(
                 match compare_result {
                     Ok(plan) => {
                         println!("✓ Comparison complete!");
+                        print_plan_summary(&plan);
+                        if let Some(structure) = target_manager.structure() {
+                            print_scan_findings(&structure.findings);
+                        }
+                        if let Some(mod_id) = &targets.explain {
+                            print_explanation(&plan, mod_id);
+                        }
                         if targets.detailed {
                             println!("Sync plan: {plan:#?}");
                         } else {
@@ -255,24 +270,19 @@ async fn handle_cli_command(
         Commands::Sync { targets } => {
             if targets.is_interactive() {
                 return Err("Interactive server syncing is not yet implemented. Use --source and --target to specify servers.".into());
-            } else if let (Some(source), Some(target)) = (&targets.source, &targets.target) {
-                let source_config = config
-                    .get_server(source)
-                    .ok_or(format!("Source server '{source}' not found."))?;
+            } else if let Some((source, target)) = targets.endpoints() {
                 let target_config = config
                     .get_server(target)
                     .ok_or(format!("Target server '{target}' not found."))?;
+                let (source_manager, source_name) = source_manager(config, &source).await?;
 
                 if targets.dry_run {
                     println!(
-                        "DRY RUN: Would sync '{}' -> '{}'",
-                        source_config.name, target_config.name
+                        "DRY RUN: Would sync '{source_name}' -> '{}'",
+                        target_config.name
                     );
                 } else {
-                    println!(
-                        "Syncing '{}' -> '{}'...",
-                        source_config.name, target_config.name
-                    );
+                    println!("Syncing '{source_name}' -> '{}'...", target_config.name);
 
                     if !targets.force {
                         print!("This will modify the target server. Continue? (y/N): ");
@@ -289,14 +299,18 @@ async fn handle_cli_command(
 
                 let (progress_reporter, progress_task) = progress::create_cli_progress_reporter();
 
-                let mut source_manager = MinecraftManager::from_config(source_config)
+                let mut source_manager = source_manager
                     .with_caching()?
                     .with_progress_reporter(progress_reporter);
                 let mut target_manager =
                     MinecraftManager::from_config(target_config).with_caching()?;
 
-                use mc_link_manager::prelude::CompatConfig;
-                let compat_config = CompatConfig::default();
+                use mc_link_manager::prelude::{CompatConfigBuilder, DowngradePolicy};
+                let compat_config = CompatConfigBuilder::new(config, target_config)
+                    .with_rules(targets.cli_rules()?)
+                    .with_profile(targets.profile.as_deref())
+                    .with_downgrade_policy(targets.downgrades.into())
+                    .build()?;
 
                 let sync_result = source_manager
                     .compare_with(&mut target_manager, &compat_config)
@@ -304,7 +318,23 @@ async fn handle_cli_command(
                 progress_task.abort();
 
                 match sync_result {
-                    Ok(plan) => {
+                    Ok(mut plan) => {
+                        if targets.remove_duplicates {
+                            target_manager.plan_duplicate_removal(&mut plan);
+                        } else if let Some(structure) = target_manager.structure() {
+                            print_scan_findings(&structure.findings);
+                        }
+
+                        if compat_config.downgrade_policy == DowngradePolicy::Confirm
+                            && plan.downgrades().next().is_some()
+                            && !targets.dry_run
+                            && !targets.force
+                            && !confirm_downgrades(&plan)
+                        {
+                            plan.drop_downgrades("Downgrade declined");
+                        }
+
+                        print_plan_summary(&plan);
                         if targets.dry_run {
                             println!("✓ Dry run complete! Sync plan:");
                             println!("{plan:#?}");
@@ -322,6 +352,327 @@ async fn handle_cli_command(
                 }
             }
         }
+        Commands::Apply { options } => {
+            let manifest = mc_link_manager::Manifest::load(&options.manifest)?;
+            let server_ids: Vec<String> = if options.servers.is_empty() {
+                manifest
+                    .server_ids()
+                    .into_iter()
+                    .map(str::to_string)
+                    .collect()
+            } else {
+                options.servers.clone()
+            };
+            if server_ids.is_empty() {
+                return Err("The manifest names no servers. Use --server to pick one.".into());
+            }
+
+            for server_id in &server_ids {
+                let target_config = config
+                    .get_server(server_id)
+                    .ok_or(format!("Server '{server_id}' not found."))?;
+                println!(
+                    "Applying {} to '{}'...",
+                    options.manifest.display(),
+                    target_config.name
+                );
+
+                let (source_manager, pins) =
+                    MinecraftManager::from_manifest(&manifest, server_id).await?;
+                let mut source_manager = source_manager.with_caching()?;
+                let mut target_manager =
+                    MinecraftManager::from_config(target_config).with_caching()?;
+
+                use mc_link_manager::prelude::{CompatConfigBuilder, DowngradePolicy};
+                let compat_config = CompatConfigBuilder::new(config, target_config)
+                    .with_rules(options.cli_rules()?)
+                    .with_profile(options.profile.as_deref())
+                    .with_downgrade_policy(options.downgrades.into())
+                    .build()?;
+                let mut plan = source_manager
+                    .compare_with(&mut target_manager, &compat_config)
+                    .await
+                    .map_err(|e| format!("Failed to create sync plan: {e}"))?;
+
+                source_manager.plan_pinned_replacements(&pins, &target_manager, &mut plan);
+                if let Some(structure) = source_manager.structure() {
+                    for resolved in &pins {
+                        if let Some(actual) = resolved.version_mismatch(&structure.mods.mods) {
+                            println!(
+                                "⚠️  {} declares version {actual}, the manifest expects {}",
+                                resolved.path.display(),
+                                resolved.version.as_deref().unwrap_or_default()
+                            );
+                        }
+                    }
+                }
+                if compat_config.downgrade_policy == DowngradePolicy::Confirm
+                    && plan.downgrades().next().is_some()
+                    && !options.dry_run
+                    && !options.force
+                    && !confirm_downgrades(&plan)
+                {
+                    plan.drop_downgrades("Downgrade declined");
+                }
+                print_plan_summary(&plan);
+
+                let summary = &plan.summary;
+                let changes = summary.mods_to_update
+                    + summary.mods_to_add
+                    + summary.mods_to_remove
+                    + summary.mods_to_rename;
+                if changes == 0 {
+                    println!("✓ '{}' already matches the manifest.", target_config.name);
+                    continue;
+                }
+                if options.dry_run {
+                    println!(
+                        "DRY RUN: Would make {changes} changes to '{}'",
+                        target_config.name
+                    );
+                    continue;
+                }
+                if !options.force {
+                    print!(
+                        "This will modify '{}'. Continue? (y/N): ",
+                        target_config.name
+                    );
+                    use std::io::{self, Write};
+                    io::stdout().flush().unwrap();
+                    let mut input = String::new();
+                    io::stdin().read_line(&mut input).unwrap();
+                    if !input.trim().to_lowercase().starts_with('y') {
+                        println!("Skipped '{}'.", target_config.name);
+                        continue;
+                    }
+                }
+
+                target_manager
+                    .execute_sync_plan(&plan, None)
+                    .await
+                    .map_err(|e| format!("Failed to apply manifest to '{server_id}': {e}"))?;
+                println!("✓ Applied {changes} changes to '{}'", target_config.name);
+            }
+        }
+        Commands::UpgradeCheck { server, to, json } => {
+            let server_config = config
+                .get_server(&server)
+                .ok_or(format!("Server '{server}' not found."))?;
+
+            let mut manager = MinecraftManager::from_config(server_config).with_caching()?;
+            let structure = manager
+                .scan()
+                .await
+                .map_err(|e| format!("Failed to scan server: {e}"))?;
+            let report = mc_link_manager::prelude::check_upgrade(&structure.mods.mods, &to);
+
+            if json {
+                println!("{}", serde_json::to_string_pretty(&report)?);
+            } else {
+                print_upgrade_report(&server_config.name, &report);
+            }
+        }
+        Commands::Outdated {
+            server,
+            download,
+            json,
+        } => {
+            let server_config = config
+                .get_server(&server)
+                .ok_or(format!("Server '{server}' not found."))?;
+
+            let mut manager = MinecraftManager::from_config(server_config).with_caching()?;
+            manager
+                .scan()
+                .await
+                .map_err(|e| format!("Failed to scan server: {e}"))?;
+            let report = manager
+                .check_updates()
+                .await
+                .map_err(|e| format!("Failed to check for updates: {e}"))?;
+
+            if let Some(dir) = download {
+                let skipped = mc_link_manager::download_updates(&report.updates, &dir).await?;
+                let plan = report.to_sync_plan(&dir);
+                if json {
+                    println!("{}", serde_json::to_string_pretty(&plan)?);
+                } else {
+                    println!(
+                        "Downloaded {} updates to {}",
+                        report.updates.len() - skipped.len(),
+                        dir.display()
+                    );
+                    for update in &skipped {
+                        println!(
+                            "  Skipped {}: {:?} does not allow downloading it",
+                            update.mod_name, update.platform
+                        );
+                    }
+                    print_plan_summary(&plan);
+                }
+            } else if json {
+                println!("{}", serde_json::to_string_pretty(&report)?);
+            } else {
+                print_outdated_report(&server_config.name, &report);
+            }
+        }
+        Commands::Lock {
+            server,
+            file,
+            include_config,
+        } => {
+            let server_config = config
+                .get_server(&server)
+                .ok_or(format!("Server '{server}' not found."))?;
+
+            let mut manager = MinecraftManager::from_config(server_config).with_caching()?;
+            manager
+                .scan()
+                .await
+                .map_err(|e| format!("Failed to scan server: {e}"))?;
+            let lockfile = manager
+                .lock(include_config)
+                .await
+                .map_err(|e| format!("Failed to lock server: {e}"))?;
+            lockfile.save(&file)?;
+
+            let mods = lockfile.files.iter().filter(|f| f.mod_id.is_some()).count();
+            println!(
+                "✓ Locked '{}' to {}: {} mods, {} other files",
+                server_config.name,
+                file.display(),
+                mods,
+                lockfile.files.len() - mods
+            );
+        }
+        Commands::Verify { server, file, json } => {
+            let server_config = config
+                .get_server(&server)
+                .ok_or(format!("Server '{server}' not found."))?;
+            let lockfile = mc_link_manager::Lockfile::load(&file)?;
+            if lockfile.server_id != server_config.id {
+                return Err(format!(
+                    "{} was written for server '{}', not '{server}'.",
+                    file.display(),
+                    lockfile.server_id
+                )
+                .into());
+            }
+
+            let mut manager = MinecraftManager::from_config(server_config).with_caching()?;
+            manager
+                .scan()
+                .await
+                .map_err(|e| format!("Failed to scan server: {e}"))?;
+            let report = manager
+                .verify(&lockfile)
+                .await
+                .map_err(|e| format!("Failed to verify server: {e}"))?;
+
+            if json {
+                println!("{}", serde_json::to_string_pretty(&report)?);
+            } else {
+                print_drift_report(&server_config.name, &report);
+            }
+            if !report.is_clean() {
+                return Err(
+                    format!("'{}' drifted from {}", server_config.name, file.display()).into(),
+                );
+            }
+        }
+        Commands::Export {
+            id,
+            format,
+            output,
+            name,
+            pack_version,
+            author,
+            loader_version,
+        } => {
+            let server_config = config
+                .get_server(&id)
+                .ok_or(format!("Server '{id}' not found."))?;
+            let output = output.unwrap_or_else(|| format.default_output(&id));
+
+            let mut manager = MinecraftManager::from_config(server_config).with_caching()?;
+            manager
+                .scan()
+                .await
+                .map_err(|e| format!("Failed to scan server: {e}"))?;
+
+            use mc_link_manager::prelude::PackExportOptions;
+            let mut options = PackExportOptions::new(
+                name.unwrap_or_else(|| server_config.name.clone()),
+                pack_version,
+            );
+            if let Some(author) = author {
+                options = options.with_author(author);
+            }
+            if let Some(loader_version) = loader_version {
+                options = options.with_loader_version(loader_version);
+            }
+            let (export, platform) = match format {
+                cli::ExportFormat::Mrpack => {
+                    (manager.export_mrpack(&output, &options).await, "Modrinth")
+                }
+                cli::ExportFormat::Curseforge => (
+                    manager.export_curseforge_pack(&output, &options).await,
+                    "CurseForge",
+                ),
+                cli::ExportFormat::Packwiz => (
+                    manager.export_packwiz(&output, &options).await,
+                    "Modrinth or CurseForge",
+                ),
+            };
+            let export = export.map_err(|e| format!("Failed to export server: {e}"))?;
+
+            println!(
+                "✓ Exported '{}' to {}",
+                server_config.name,
+                output.display()
+            );
+            println!("  {} mods download from {platform}", export.linked.len());
+            println!(
+                "  {} config and resource pack files",
+                export.overrides.len()
+            );
+            if !export.embedded.is_empty() {
+                println!("  {} mods embedded in the pack:", export.embedded.len());
+                for mod_id in &export.embedded {
+                    println!("    {mod_id}");
+                }
+            }
+        }
+        Commands::Profile { action } => match action {
+            ProfileAction::List => {
+                let profiles = config.profiles();
+                let mut names = profiles.list_profiles();
+                names.sort();
+                for name in names {
+                    let Some(profile) = profiles.get_profile(&name) else {
+                        continue;
+                    };
+                    println!(
+                        "  - {} v{} ({} rules, {} aliases){}",
+                        profile.name,
+                        profile.version,
+                        profile.rules.len(),
+                        profile.aliases.len(),
+                        if profile.system { " [system]" } else { "" }
+                    );
+                }
+            }
+            ProfileAction::Import { file } => {
+                let mut config_manager = ConfigManager::new()?;
+                let name = config_manager.profiles_mut().import_profile(&file)?;
+                println!("✓ Profile '{name}' imported successfully!");
+            }
+            ProfileAction::Export { name, output } => {
+                let output = output.unwrap_or_else(|| format!("{name}.json").into());
+                config.profiles().export_profile(&name, &output)?;
+                println!("✓ Profile '{name}' exported to {}", output.display());
+            }
+        },
         Commands::Toggle { target } => {
             if target.is_interactive() {
                 return Err("Interactive server toggling is not yet implemented. Use --id to specify a server.".into());
@@ -362,7 +713,13 @@ async fn handle_cli_command(
                         if let Some(mc_version) = &server.settings.minecraft_version {
                             println!("Minecraft Version: {mc_version}");
                         }
-                        println!("Mod Loader: {:?}", server.settings.mod_loader);
+                        match &server.settings.mod_loader {
+                            Some(mod_loader) => println!("Mod Loader: {mod_loader:?}"),
+                            None => println!("Mod Loader: detected from installed mods"),
+                        }
+                        if let Some(loader_version) = &server.settings.loader_version {
+                            println!("Loader Version: {loader_version}");
+                        }
                     }
                 } else {
                     return Err(format!("Server '{server_id}' not found.").into());
@@ -388,6 +745,272 @@ async fn handle_cli_command(
     Ok(())
 }
 
+/// Prints an upgrade readiness report grouped by support status.
+fn print_upgrade_report(server_name: &str, report: &mc_link_manager::prelude::UpgradeReport) {
+    let print_group = |title: &str, mods: &[mc_link_manager::prelude::ModReadiness]| {
+        if mods.is_empty() {
+            return;
+        }
+        println!("\n{title} ({}):", mods.len());
+        for entry in mods {
+            let version = entry.version.as_deref().unwrap_or("unknown");
+            match &entry.declared_range {
+                Some(range) => println!("  {} ({version}) - declares {range}", entry.mod_name),
+                None => println!("  {} ({version})", entry.mod_name),
+            }
+        }
+    };
+
+    println!(
+        "Upgrade readiness of '{server_name}' for Minecraft {}:",
+        report.target_version
+    );
+    print_group("✓ Supported", &report.supported);
+    print_group("✗ Unsupported", &report.unsupported);
+    print_group("? Undeclared", &report.undeclared);
+
+    if report.is_ready() {
+        println!("\nAll mods declare support for {}.", report.target_version);
+    }
+}
+
+/// Creates the manager a comparison or sync takes its mods from, and names it.
+async fn source_manager<'a>(
+    config: &'a ConfigManager,
+    source: &Source<'_>,
+) -> Result<(MinecraftManager<'a, Connector>, String), Box<dyn std::error::Error>> {
+    match source {
+        Source::Server(id) => {
+            let source_config = config
+                .get_server(id)
+                .ok_or(format!("Source server '{id}' not found."))?;
+            Ok((
+                MinecraftManager::from_config(source_config),
+                source_config.name.clone(),
+            ))
+        }
+        Source::Mrpack(path) | Source::CurseForgePack(path) | Source::Packwiz(path) => {
+            println!("Installing {}...", path.display());
+            let manager = match source {
+                Source::Mrpack(_) => MinecraftManager::from_mrpack(path, PackSide::Server).await?,
+                Source::Packwiz(_) => {
+                    MinecraftManager::from_packwiz(path, PackSide::Server).await?
+                }
+                _ => MinecraftManager::from_curseforge_pack(path, PackSide::Server).await?,
+            };
+            let name = manager
+                .pack()
+                .map(|pack| format!("{} {}", pack.name, pack.version))
+                .unwrap_or_default();
+            Ok((manager, name))
+        }
+    }
+}
+
+fn print_drift_report(server_name: &str, report: &mc_link_manager::DriftReport) {
+    use mc_link_manager::Drift;
+
+    if report.is_clean() {
+        println!("✓ '{server_name}' matches its lockfile.");
+        return;
+    }
+
+    println!("'{server_name}' drifted from its lockfile:");
+    let describe = |file: &mc_link_manager::LockedFile| match (&file.mod_id, &file.version) {
+        (Some(id), Some(version)) => format!(" ({id} {version})"),
+        (Some(id), None) => format!(" ({id})"),
+        _ => String::new(),
+    };
+    for drift in &report.drift {
+        match drift {
+            Drift::Added { file } => println!("  + {}{}", file.path, describe(file)),
+            Drift::Removed { file } => println!("  - {}{}", file.path, describe(file)),
+            Drift::Modified { locked, current } => println!(
+                "  ~ {}: hash {} does not match locked {}",
+                current.path,
+                &current.sha256[..12.min(current.sha256.len())],
+                &locked.sha256[..12.min(locked.sha256.len())]
+            ),
+            Drift::Replaced { locked, current } => println!(
+                "  ↻ {}{} replaced by {}{}",
+                locked.path,
+                describe(locked),
+                current.path,
+                describe(current)
+            ),
+        }
+    }
+}
+
+fn print_outdated_report(server_name: &str, report: &mc_link_manager::OutdatedReport) {
+    println!(
+        "Updates for '{server_name}' (Minecraft {}, {}):",
+        report.minecraft_version.as_deref().unwrap_or("any version"),
+        report.loader.as_deref().unwrap_or("any loader")
+    );
+
+    let describe = |update: &mc_link_manager::ModUpdate| {
+        format!(
+            "{} {} -> {} ({:?})",
+            update.mod_name,
+            update.current_version.as_deref().unwrap_or("unknown"),
+            update
+                .version_number
+                .as_deref()
+                .unwrap_or(&update.file_name),
+            update.platform
+        )
+    };
+    if !report.updates.is_empty() {
+        println!("\n⬆ Outdated ({}):", report.updates.len());
+        for update in &report.updates {
+            println!("  {}", describe(update));
+        }
+    }
+    if !report.held.is_empty() {
+        println!("\n📌 Pinned ({}):", report.held.len());
+        for held in &report.held {
+            println!(
+                "  {}, pinned at {}",
+                describe(&held.update),
+                held.pinned_version
+            );
+        }
+    }
+    if !report.announced.is_empty() {
+        println!(
+            "\n📣 Announced by update JSON ({}):",
+            report.announced.len()
+        );
+        for announced in &report.announced {
+            let newer = [
+                announced
+                    .recommended
+                    .as_deref()
+                    .map(|v| format!("{v} recommended")),
+                announced.latest.as_deref().map(|v| format!("{v} latest")),
+            ];
+            let newer: Vec<String> = newer.into_iter().flatten().collect();
+            print!(
+                "  {} {} -> {}",
+                announced.mod_name,
+                announced.current_version.as_deref().unwrap_or("unknown"),
+                newer.join(", ")
+            );
+            if let Some(pinned) = &announced.pinned_version {
+                print!(", pinned at {pinned}");
+            }
+            match &announced.homepage {
+                Some(homepage) => println!(" ({homepage})"),
+                None => println!(),
+            }
+        }
+    }
+    if !report.unresolved.is_empty() {
+        println!("\n? Update status unknown ({}):", report.unresolved.len());
+        for mod_id in &report.unresolved {
+            println!("  {mod_id}");
+        }
+    }
+
+    println!("\n{} mods are up to date.", report.up_to_date.len());
+}
+
+/// Prints every compatibility decision made for a mod.
+fn print_explanation(plan: &mc_link_manager::SyncPlan, mod_id: &str) {
+    let decisions: Vec<_> = plan.explain(mod_id).collect();
+    if decisions.is_empty() {
+        println!("\nNo decisions recorded for '{mod_id}'; it is not installed on either side.");
+        return;
+    }
+
+    println!("\nDecisions for '{mod_id}':");
+    for (i, decision) in decisions.iter().enumerate() {
+        println!(
+            "  {}. {}, because {}",
+            i + 1,
+            decision.outcome,
+            decision.cause
+        );
+    }
+}
+
+/// Prints problems detected during a scan, if any.
+fn print_scan_findings(findings: &[mc_link_manager::ScanFinding]) {
+    use mc_link_manager::ScanFinding;
+
+    if findings.is_empty() {
+        return;
+    }
+
+    println!("\n⚠ {} problem(s) found:", findings.len());
+    for finding in findings {
+        match finding {
+            ScanFinding::Environment(issue) => println!("  - {issue}"),
+            ScanFinding::Duplicate(group) => println!("  - {group}"),
+            ScanFinding::Conflict(conflict) => println!("  - {conflict}"),
+        }
+    }
+}
+
+/// Prints a one-line overview of a sync plan, followed by any warnings.
+fn print_plan_summary(plan: &mc_link_manager::SyncPlan) {
+    let summary = &plan.summary;
+    println!(
+        "{} to add, {} to remove, {} to update ({} downgrades), {} to rename, {} kept as-is",
+        summary.mods_to_add,
+        summary.mods_to_remove,
+        summary.mods_to_update,
+        summary.mods_to_downgrade,
+        summary.mods_to_rename,
+        summary.mods_to_keep
+    );
+
+    for warning in &plan.warnings {
+        println!(
+            "⚠ {} ({}): {}",
+            warning.mod_name, warning.rule, warning.reason
+        );
+    }
+    if let Some(mismatch) = &plan.loader_mismatch {
+        println!("✗ Loader mismatch: {mismatch}");
+    }
+    for conflict in &plan.conflicts {
+        println!("⚠ Conflict: {conflict}");
+    }
+    for blocked in &plan.blocked {
+        if let mc_link_manager::SyncAction::AddMod { mod_info, .. } = &blocked.action {
+            println!("✗ Not adding {}: {}", mod_info.name, blocked.reason);
+        }
+    }
+}
+
+/// Lists planned downgrades and asks whether they should be applied.
+fn confirm_downgrades(plan: &mc_link_manager::SyncPlan) -> bool {
+    use mc_link_manager::SyncAction;
+    use std::io::{self, Write};
+
+    println!("The following mods would be downgraded:");
+    for action in plan.downgrades() {
+        if let SyncAction::UpdateMod {
+            mod_id,
+            from_version,
+            to_version,
+            ..
+        } = action
+        {
+            println!("  {mod_id}: {from_version} -> {to_version}");
+        }
+    }
+
+    // A closed or unreadable stdin, e.g. in CI, declines the downgrades
+    print!("Apply these downgrades? (y/N): ");
+    let mut input = String::new();
+    io::stdout().flush().is_ok()
+        && io::stdin().read_line(&mut input).is_ok()
+        && input.trim().to_lowercase().starts_with('y')
+}
+
 async fn run_tui() -> Result<(), Box<dyn std::error::Error>> {
     // Setup terminal
     enable_raw_mode()?;
@@ -280,12 +280,12 @@ impl AddServerForm {
         // Parse mod loader
         if let Some(mod_loader) = values.get("mod_loader") {
             use mc_link_config::ModLoader;
-            server_config.settings.mod_loader = match mod_loader.as_str() {
+            server_config.settings.mod_loader = Some(match mod_loader.as_str() {
                 "NeoForge" => ModLoader::NeoForge,
                 "Forge" => ModLoader::Forge,
                 "Fabric" => ModLoader::Fabric,
                 _ => ModLoader::NeoForge,
-            };
+            });
         }
 
         Ok(server_config)